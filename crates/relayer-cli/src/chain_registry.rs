@@ -118,6 +118,7 @@ where
         r#type: default::chain_type(),
         rpc_addr: rpc_data.rpc_address,
         grpc_addr: grpc_address,
+        broadcast_addrs: Vec::new(),
         event_source: EventSourceMode::Push {
             url: websocket_address,
             batch_delay: default::batch_delay(),
@@ -135,9 +136,11 @@ where
         gas_adjustment: None,
         gas_multiplier: Some(GasMultiplier::new(1.1).unwrap()),
         fee_granter: None,
+        authz_granter: None,
         max_msg_num: MaxMsgNum::default(),
         max_tx_size: MaxTxSize::default(),
         max_grpc_decoding_size: default::max_grpc_decoding_size(),
+        block_scan_window: default::block_scan_window(),
         clock_drift: default::clock_drift(),
         max_block_time: default::max_block_time(),
         trusting_period: None,
@@ -150,8 +153,12 @@ where
             denom: asset.base.to_owned(),
         },
         packet_filter: packet_filter.unwrap_or_default(),
+        min_balance: None,
+        critical_balance: None,
         address_type: AddressType::default(),
         sequential_batch_tx: false,
+        pause_on_critical_balance: false,
+        cache: Default::default(),
         extension_options: Vec::new(),
     })
 }
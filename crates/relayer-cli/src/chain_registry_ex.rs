@@ -289,6 +289,7 @@ where
             batch_delay: default::batch_delay(),
         },
         grpc_addr: grpc_any,
+        broadcast_addrs: Vec::new(),
         rpc_timeout: default::rpc_timeout(),
         trusted_node: true,
         genesis_restart: None,
@@ -302,9 +303,11 @@ where
         gas_adjustment: None,
         gas_multiplier: Some(GasMultiplier::new(1.1).unwrap()),
         fee_granter: None,
+        authz_granter: None,
         max_msg_num: MaxMsgNum::default(),
         max_tx_size: MaxTxSize::default(),
         max_grpc_decoding_size: default::max_grpc_decoding_size(),
+        block_scan_window: default::block_scan_window(),
         clock_drift: default::clock_drift(),
         max_block_time: default::max_block_time(),
         trusting_period: None,
@@ -317,8 +320,12 @@ where
             denom: asset.base.to_owned(),
         },
         packet_filter: packet_filter.unwrap_or_default(),
+        min_balance: None,
+        critical_balance: None,
         address_type: AddressType::default(),
         sequential_batch_tx: false,
+        pause_on_critical_balance: false,
+        cache: Default::default(),
         extension_options: Vec::new(),
     })
 }
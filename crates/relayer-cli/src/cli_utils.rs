@@ -2,8 +2,12 @@
 
 use alloc::sync::Arc;
 use eyre::eyre;
+use futures::future::join_all;
+use std::collections::HashMap;
 use tokio::runtime::Runtime as TokioRuntime;
-use tracing::debug;
+use tracing::{debug, warn};
+
+use ibc_chain_registry::{fetchable::Fetchable, paths::ChainIdMap};
 
 use ibc_relayer::chain::requests::{
     IncludeProof, QueryChannelRequest, QueryClientStateRequest, QueryConnectionRequest, QueryHeight,
@@ -13,7 +17,7 @@ use ibc_relayer::{
         counterparty::{channel_connection_client, ChannelConnectionClient},
         handle::{BaseChainHandle, ChainHandle},
     },
-    config::{Config, ChainConfig},
+    config::{load_json, Config, ChainConfig},
     spawn,
 };
 use ibc_relayer_types::core::ics02_client::client_state::ClientState;
@@ -103,6 +107,74 @@ pub fn spawn_runtime_generic<Handle: ChainHandle>(
     }
 }
 
+/// Fetches a chain registry resource, eg. [`ChainData`] or [`AssetList`], for each of the given chains.
+///
+/// Chains are matched to their registry name through the `~/.hermes/chain_chainid.json`
+/// map also used by `hermes config endpoints`. Chains which cannot be found in the map
+/// or in the registry are omitted from the result.
+///
+/// [`ChainData`]: ibc_chain_registry::chain::ChainData
+/// [`AssetList`]: ibc_chain_registry::asset_list::AssetList
+pub fn fetch_registry_data<'a, T>(
+    chain_ids: impl IntoIterator<Item = &'a ChainId>,
+) -> HashMap<ChainId, T>
+where
+    T: Fetchable + Send + 'static,
+{
+    let chain_names = match dirs::home_dir()
+        .map(|home| home.join(".hermes/chain_chainid.json"))
+        .filter(|path| path.exists())
+    {
+        Some(path) => match load_json::<ChainIdMap>(&path) {
+            Ok(chain_map) => chain_map.inverse(),
+            Err(e) => {
+                warn!("failed to load chain map file {:?}: {}", path, e);
+                return HashMap::new();
+            }
+        },
+        None => {
+            warn!("chain map file ~/.hermes/chain_chainid.json does not exist, skipping chain registry lookup");
+            return HashMap::new();
+        }
+    };
+
+    let lookups: Vec<(ChainId, String)> = chain_ids
+        .into_iter()
+        .filter_map(|chain_id| match chain_names.get(chain_id.as_str()) {
+            Some(name) => Some((chain_id.clone(), name.clone())),
+            None => {
+                warn!("chain {} was not found in the chain id map", chain_id);
+                None
+            }
+        })
+        .collect();
+
+    let rt = match TokioRuntime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            warn!(
+                "failed to start runtime for querying the chain registry: {}",
+                e
+            );
+            return HashMap::new();
+        }
+    };
+
+    rt.block_on(async {
+        let fetches = lookups.into_iter().map(|(chain_id, name)| async move {
+            match T::fetch(name.clone(), None).await {
+                Ok(data) => Some((chain_id, data)),
+                Err(e) => {
+                    warn!("failed to fetch {} from the chain registry: {}", name, e);
+                    None
+                }
+            }
+        });
+
+        join_all(fetches).await.into_iter().flatten().collect()
+    })
+}
+
 /// Spawns a chain runtime for specified chain identifier, queries the counterparty chain associated
 /// with specified port and channel id, and spawns a chain runtime for the counterparty chain.
 ///
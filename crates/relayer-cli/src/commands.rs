@@ -10,6 +10,8 @@ mod keys;
 mod listen;
 mod misbehaviour;
 mod query;
+mod replay;
+mod report;
 mod start;
 mod tx;
 mod update;
@@ -23,7 +25,7 @@ pub(crate) mod channels;
 use self::{
     clear::ClearCmds, completions::CompletionsCmd, config::ConfigCmd, create::CreateCmds,
     fee::FeeCmd, health::HealthCheckCmd, keys::KeysCmd, listen::ListenCmd,
-    misbehaviour::MisbehaviourCmd, query::QueryCmd, start::StartCmd, tx::TxCmd, update::UpdateCmds,
+    misbehaviour::MisbehaviourCmd, query::QueryCmd, replay::ReplayCmd, report::ReportCmds, start::StartCmd, tx::TxCmd, update::UpdateCmds,
     upgrade::UpgradeCmds, version::VersionCmd, osmosis::OsmosisTokensCmd, connections::ConnectionsCmd,
     chains_enumerator::ChainsEnumerableCmd, channels::ChannelsCmd
 };
@@ -75,6 +77,13 @@ pub enum CliCmd {
     /// Relays packets and open handshake messages between all chains in the config.
     Start(StartCmd),
 
+    /// Replay the event batches recorded with `hermes start --record-events`.
+    ///
+    /// Shows the objects, eg. the packet paths, to which the events of each batch would be dispatched,
+    /// along with the events dispatched to each of them. No worker is spawned, hence nothing is relayed
+    /// and no transaction is submitted. The objects are resolved by querying the chains, which must be reachable.
+    Replay(ReplayCmd),
+
     /// Query objects from the chain
     #[clap(subcommand)]
     Query(QueryCmd),
@@ -87,7 +96,11 @@ pub enum CliCmd {
     #[clap(subcommand)]
     Fee(FeeCmd),
 
-    /// Listen to and display IBC events emitted by a chain
+    /// Report on the activity of the relayer, such as the fees it paid and was rewarded
+    #[clap(subcommand)]
+    Report(ReportCmds),
+
+    /// Listen to and display IBC events emitted by one or more chains
     Listen(ListenCmd),
 
     /// Listen to client update IBC events and handles misbehaviour
@@ -323,7 +323,8 @@ impl Runnable for HealthyEndpointsCmd {
                     chain.event_source = c.event_source;
 
                     let websocket_url = match &chain.event_source {
-                        EventSourceMode::Push { url, .. } => url.to_string(),
+                        EventSourceMode::Push { url, .. }
+                        | EventSourceMode::Hybrid { url, .. } => url.to_string(),
                         _ => panic!("No websocket url found")
                     };
 
@@ -1,5 +1,8 @@
+use alloc::collections::BTreeMap;
+use core::fmt::Write;
 use core::str::FromStr;
 use std::{
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
 };
@@ -9,18 +12,27 @@ use abscissa_core::{Command, Runnable};
 
 use eyre::eyre;
 use hdpath::StandardHDPath;
+use ibc_chain_registry::chain::ChainData;
 use ibc_relayer::{
     chain::ChainType,
-    config::{ChainConfig, Config},
+    config::{AddressType, ChainConfig, Config},
     keyring::{
         AnySigningKeyPair, KeyRing, Secp256k1KeyPair, SigningKeyPair, SigningKeyPairSized, Store,
     },
 };
 use ibc_relayer_types::core::ics24_host::identifier::ChainId;
-use tracing::{warn, info};
+use serde::Serialize;
+use tracing::{info, warn};
 
 use crate::application::{app_config, app_home};
-use crate::conclude::Output;
+use crate::cli_utils::fetch_registry_data;
+use crate::conclude::{json, Output, Status};
+
+/// The SLIP-44 coin type used by Cosmos SDK chains.
+const COSMOS_COIN_TYPE: u32 = 118;
+
+/// The SLIP-44 coin type used by Ethermint-based chains.
+const ETHERMINT_COIN_TYPE: u32 = 60;
 
 /// The data structure that represents the arguments when invoking the `keys add` CLI command.
 ///
@@ -34,22 +46,41 @@ use crate::conclude::Output;
 ///
 /// `keys add [OPTIONS] --chain <CHAIN_ID> --mnemonic-file <MNEMONIC_FILE>`
 ///
+/// The command to restore a key from a file containing mnemonic on every configured chain:
+///
+/// `keys add [OPTIONS] --all-chains --mnemonic-file <MNEMONIC_FILE>`
+///
 /// The key-file and mnemonic-file flags can't be given at the same time, this will cause a terminating error.
 /// If successful the key will be created or restored, depending on which flag was given.
+///
+/// With `--all-chains`, the derivation path of each chain is built from the coin type found
+/// in the chain registry (`slip44`), and keys which already exist are skipped unless
+/// `--overwrite` is given.
 #[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
 #[clap(
     override_usage = "hermes keys add [OPTIONS] --chain <CHAIN_ID> --key-file <KEY_FILE>
 
-    hermes keys add [OPTIONS] --chain <CHAIN_ID> --mnemonic-file <MNEMONIC_FILE>"
+    hermes keys add [OPTIONS] --chain <CHAIN_ID> --mnemonic-file <MNEMONIC_FILE>
+
+    hermes keys add [OPTIONS] --all-chains --mnemonic-file <MNEMONIC_FILE>"
 )]
 pub struct KeysAddCmd {
     #[clap(
         long = "chain",
-        required = true,
+        required_unless_present = "all-chains",
         help_heading = "FLAGS",
         help = "Identifier of the chain"
     )]
-    chain_id: ChainId,
+    chain_id: Option<ChainId>,
+
+    #[clap(
+        long = "all-chains",
+        conflicts_with_all = &["chain-id", "key-file", "hd-path"],
+        help_heading = "FLAGS",
+        help = "Restore the key from the mnemonic file on every chain in the configuration, \
+                deriving it with the coin type found in the chain registry"
+    )]
+    all_chains: bool,
 
     #[clap(
         long = "key-file",
@@ -94,10 +125,10 @@ pub struct KeysAddCmd {
 }
 
 impl KeysAddCmd {
-    fn options(&self, config: &Config) -> eyre::Result<KeysAddOptions> {
+    fn options(&self, config: &Config, chain_id: &ChainId) -> eyre::Result<KeysAddOptions> {
         let chain_config = config
-            .find_chain(&self.chain_id)
-            .ok_or_else(|| eyre!("chain '{}' not found in configuration file", self.chain_id))?;
+            .find_chain(chain_id)
+            .ok_or_else(|| eyre!("chain '{}' not found in configuration file", chain_id))?;
 
         let name = self
             .key_name
@@ -128,7 +159,12 @@ impl Runnable for KeysAddCmd {
         info!("Home directory: {:?}", home);
         let config = app_config();
 
-        let opts = match self.options(&config) {
+        let chain_id = match &self.chain_id {
+            Some(chain_id) => chain_id,
+            None => self.run_all_chains(&config),
+        };
+
+        let opts = match self.options(&config, chain_id) {
             Err(err) => Output::error(err).exit(),
             Ok(result) => result,
         };
@@ -153,7 +189,7 @@ impl Runnable for KeysAddCmd {
                     .exit(),
                     Err(e) => Output::error(format!(
                         "An error occurred adding the key on chain {} from file {:?}: {}",
-                        self.chain_id, key_file, e
+                        chain_id, key_file, e
                     ))
                     .exit(),
                 }
@@ -177,7 +213,7 @@ impl Runnable for KeysAddCmd {
                     .exit(),
                     Err(e) => Output::error(format!(
                         "An error occurred restoring the key on chain {} from file {:?}: {}",
-                        self.chain_id, mnemonic_file, e
+                        chain_id, mnemonic_file, e
                     ))
                     .exit(),
                 }
@@ -193,6 +229,229 @@ impl Runnable for KeysAddCmd {
     }
 }
 
+impl KeysAddCmd {
+    /// Restore the key from the mnemonic file on every configured chain,
+    /// print the resulting addresses and exit.
+    fn run_all_chains(&self, config: &Config) -> ! {
+        let mnemonic_file = match &self.mnemonic_file {
+            Some(mnemonic_file) => mnemonic_file,
+            None => Output::error("--all-chains requires --mnemonic-file").exit(),
+        };
+
+        let mnemonic = match fs::read_to_string(mnemonic_file) {
+            Ok(mnemonic) => mnemonic,
+            Err(e) => Output::error(format!(
+                "error reading the mnemonic file {mnemonic_file:?}: {e}"
+            ))
+            .exit(),
+        };
+
+        let coin_types: HashMap<ChainId, u32> =
+            fetch_registry_data::<ChainData>(config.chains.iter().map(|chain| &chain.id))
+                .into_iter()
+                .map(|(chain_id, chain_data)| (chain_id, chain_data.slip44))
+                .collect();
+
+        let results: Vec<ProvisionedKey> = config
+            .chains
+            .iter()
+            .map(|chain_config| {
+                let key_name = self
+                    .key_name
+                    .clone()
+                    .unwrap_or_else(|| chain_config.key_name.clone());
+
+                let coin_type = coin_types
+                    .get(&chain_config.id)
+                    .copied()
+                    .unwrap_or_else(|| default_coin_type(&chain_config.address_type));
+
+                provision_key(
+                    chain_config,
+                    &key_name,
+                    &mnemonic,
+                    coin_type,
+                    self.overwrite,
+                )
+            })
+            .collect();
+
+        let status = if results
+            .iter()
+            .any(|result| result.status == ProvisionStatus::Failed)
+        {
+            Status::Error
+        } else {
+            Status::Success
+        };
+
+        if json() {
+            Output::new(status).with_result(results).exit()
+        } else {
+            Output::new(status)
+                .with_msg(format_provisioned_keys(&results))
+                .exit()
+        }
+    }
+}
+
+/// The outcome of restoring a key on a single chain with `keys add --all-chains`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ProvisionStatus {
+    Added,
+    Overwritten,
+    Skipped,
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ProvisionedKey {
+    chain_id: ChainId,
+    key_name: String,
+    hd_path: String,
+    status: ProvisionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn provision_key(
+    config: &ChainConfig,
+    key_name: &str,
+    mnemonic: &str,
+    coin_type: u32,
+    overwrite: bool,
+) -> ProvisionedKey {
+    let hd_path = format!("m/44'/{coin_type}'/0'/0/0");
+
+    let mut result = ProvisionedKey {
+        chain_id: config.id.clone(),
+        key_name: key_name.to_string(),
+        hd_path: hd_path.clone(),
+        status: ProvisionStatus::Failed,
+        address: None,
+        error: None,
+    };
+
+    let restored = match config.r#type {
+        ChainType::CosmosSdk => KeyRing::new_secp256k1(
+            Store::Test,
+            &config.account_prefix,
+            &config.id,
+            &config.key_store_folder,
+        )
+        .map_err(eyre::Report::from)
+        .and_then(|mut keyring| {
+            let existing = keyring.get_key(key_name).ok();
+
+            if let (Some(key), false) = (&existing, overwrite) {
+                return Ok((ProvisionStatus::Skipped, key.account()));
+            }
+
+            let hd_path = StandardHDPath::from_str(&hd_path)
+                .map_err(|_| eyre!("invalid derivation path: {}", hd_path))?;
+
+            let key_pair = Secp256k1KeyPair::from_mnemonic(
+                mnemonic,
+                &hd_path,
+                &config.address_type,
+                keyring.account_prefix(),
+            )?;
+
+            keyring.add_key(key_name, key_pair.clone())?;
+
+            let status = if existing.is_some() {
+                ProvisionStatus::Overwritten
+            } else {
+                ProvisionStatus::Added
+            };
+
+            Ok((status, key_pair.account()))
+        }),
+    };
+
+    match restored {
+        Ok((status, address)) => {
+            result.status = status;
+            result.address = Some(address);
+        }
+        Err(e) => {
+            warn!(
+                "failed to restore key '{}' on chain {}: {}",
+                key_name, config.id, e
+            );
+            result.error = Some(e.to_string());
+        }
+    }
+
+    result
+}
+
+fn default_coin_type(address_type: &AddressType) -> u32 {
+    match address_type {
+        AddressType::Cosmos => COSMOS_COIN_TYPE,
+        AddressType::Ethermint { .. } => ETHERMINT_COIN_TYPE,
+    }
+}
+
+fn format_provisioned_keys(results: &[ProvisionedKey]) -> String {
+    let rows: BTreeMap<&ChainId, &ProvisionedKey> = results
+        .iter()
+        .map(|result| (&result.chain_id, result))
+        .collect();
+
+    let chain_width = rows
+        .keys()
+        .map(|chain_id| chain_id.as_str().len())
+        .chain(Some("CHAIN".len()))
+        .max()
+        .unwrap_or_default();
+
+    let name_width = rows
+        .values()
+        .map(|result| result.key_name.len())
+        .chain(Some("KEY NAME".len()))
+        .max()
+        .unwrap_or_default();
+
+    let mut table = String::new();
+
+    let _ = write!(
+        table,
+        "\n{:<chain_width$}  {:<name_width$}  {:<18}  {:<11}  ADDRESS",
+        "CHAIN", "KEY NAME", "HD PATH", "STATUS"
+    );
+
+    for (chain_id, result) in rows {
+        let status = match result.status {
+            ProvisionStatus::Added => "added",
+            ProvisionStatus::Overwritten => "overwritten",
+            ProvisionStatus::Skipped => "skipped",
+            ProvisionStatus::Failed => "failed",
+        };
+
+        let address = match (&result.address, &result.error) {
+            (Some(address), _) => address.as_str(),
+            (None, Some(error)) => error.as_str(),
+            (None, None) => "",
+        };
+
+        let _ = write!(
+            table,
+            "\n{:<chain_width$}  {:<name_width$}  {:<18}  {:<11}  {}",
+            chain_id.as_str(),
+            result.key_name,
+            result.hd_path,
+            status,
+            address
+        );
+    }
+
+    table
+}
+
 pub fn add_key(
     config: &ChainConfig,
     key_name: &str,
@@ -285,7 +544,8 @@ mod tests {
     fn test_keys_add_key_file() {
         assert_eq!(
             KeysAddCmd {
-                chain_id: ChainId::from_string("chain_id"),
+                chain_id: Some(ChainId::from_string("chain_id")),
+                all_chains: false,
                 key_file: Some(PathBuf::from("key_file")),
                 mnemonic_file: None,
                 key_name: None,
@@ -300,7 +560,8 @@ mod tests {
     fn test_keys_add_mnemonic_file() {
         assert_eq!(
             KeysAddCmd {
-                chain_id: ChainId::from_string("chain_id"),
+                chain_id: Some(ChainId::from_string("chain_id")),
+                all_chains: false,
                 key_file: None,
                 mnemonic_file: Some(PathBuf::from("mnemonic_file")),
                 key_name: None,
@@ -321,7 +582,8 @@ mod tests {
     fn test_keys_add_key_file_overwrite() {
         assert_eq!(
             KeysAddCmd {
-                chain_id: ChainId::from_string("chain_id"),
+                chain_id: Some(ChainId::from_string("chain_id")),
+                all_chains: false,
                 key_file: Some(PathBuf::from("key_file")),
                 mnemonic_file: None,
                 key_name: None,
@@ -343,7 +605,8 @@ mod tests {
     fn test_keys_add_mnemonic_file_overwrite() {
         assert_eq!(
             KeysAddCmd {
-                chain_id: ChainId::from_string("chain_id"),
+                chain_id: Some(ChainId::from_string("chain_id")),
+                all_chains: false,
                 key_file: None,
                 mnemonic_file: Some(PathBuf::from("mnemonic_file")),
                 key_name: None,
@@ -384,4 +647,59 @@ mod tests {
     fn test_keys_add_no_chain() {
         assert!(KeysAddCmd::try_parse_from(["test", "--key-file", "key_file"]).is_err());
     }
+
+    #[test]
+    fn test_keys_add_all_chains_mnemonic_file() {
+        assert_eq!(
+            KeysAddCmd {
+                chain_id: None,
+                all_chains: true,
+                key_file: None,
+                mnemonic_file: Some(PathBuf::from("mnemonic_file")),
+                key_name: None,
+                hd_path: "m/44'/118'/0'/0/0".to_string(),
+                overwrite: true,
+            },
+            KeysAddCmd::parse_from([
+                "test",
+                "--all-chains",
+                "--mnemonic-file",
+                "mnemonic_file",
+                "--overwrite"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_keys_add_all_chains_and_chain() {
+        assert!(KeysAddCmd::try_parse_from([
+            "test",
+            "--all-chains",
+            "--chain",
+            "chain_id",
+            "--mnemonic-file",
+            "mnemonic_file"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_keys_add_all_chains_key_file() {
+        assert!(
+            KeysAddCmd::try_parse_from(["test", "--all-chains", "--key-file", "key_file"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_keys_add_all_chains_hd_path() {
+        assert!(KeysAddCmd::try_parse_from([
+            "test",
+            "--all-chains",
+            "--mnemonic-file",
+            "mnemonic_file",
+            "--hd-path",
+            "m/44'/60'/0'/0/0"
+        ])
+        .is_err());
+    }
 }
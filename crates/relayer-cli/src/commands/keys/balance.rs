@@ -1,14 +1,21 @@
+use std::collections::HashMap;
 use std::fmt::Write;
+use std::thread;
 
 use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
+use serde::Serialize;
 
+use ibc_chain_registry::asset_list::AssetList;
+use ibc_relayer::chain::cosmos::gas::default_tx_fee;
+use ibc_relayer::chain::cosmos::types::gas::GasConfig;
 use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::config::{ChainConfig, Config};
 use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 
 use crate::application::app_config;
-use crate::cli_utils::spawn_chain_runtime;
-use crate::conclude::{exit_with_unrecoverable_error, json, Output};
+use crate::cli_utils::{fetch_registry_data, spawn_chain_runtime};
+use crate::conclude::{exit_with_unrecoverable_error, json, Output, Status};
 
 /// The data structure that represents the arguments when invoking the `keys balance` CLI command.
 ///
@@ -19,16 +26,31 @@ use crate::conclude::{exit_with_unrecoverable_error, json, Output};
 /// If no key name is given, it will be taken from the configuration file.
 /// If successful the balance and denominator of the account, associated with the key name
 /// on the given chain, will be displayed.
+///
+/// The balance of the relayer wallet on every configured chain can be queried with:
+///
+/// `keys balance --all-chains`
+///
+/// Each balance is compared against the `min_balance` configured for its chain,
+/// and the command fails if any wallet is below its minimum balance.
 #[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
 pub struct KeyBalanceCmd {
     #[clap(
         long = "chain",
-        required = true,
+        required_unless_present = "all-chains",
         value_name = "CHAIN_ID",
         help_heading = "REQUIRED",
         help = "Identifier of the chain"
     )]
-    chain_id: ChainId,
+    chain_id: Option<ChainId>,
+
+    #[clap(
+        long = "all-chains",
+        conflicts_with_all = &["chain-id", "key-name", "denom", "all"],
+        help = "(optional) query the balance of the relayer wallet on every configured chain, \
+                and fail if any of them is below the chain's `min_balance`"
+    )]
+    all_chains: bool,
 
     #[clap(
         long = "key-name",
@@ -55,8 +77,13 @@ impl Runnable for KeyBalanceCmd {
     fn run(&self) {
         let config = app_config();
 
-        let chain = spawn_chain_runtime(&config, &self.chain_id)
-            .unwrap_or_else(exit_with_unrecoverable_error);
+        let chain_id = match &self.chain_id {
+            Some(chain_id) => chain_id,
+            None => get_wallet_balances(&config),
+        };
+
+        let chain =
+            spawn_chain_runtime(&config, chain_id).unwrap_or_else(exit_with_unrecoverable_error);
 
         let key_name = self.key_name.clone();
 
@@ -110,19 +137,241 @@ fn get_balances(chain: impl ChainHandle, key_name: Option<String>) {
     }
 }
 
+/// The balance of the relayer wallet on a chain, as reported by `keys balance --all-chains`.
+#[derive(Clone, Debug, Serialize)]
+struct WalletBalance {
+    chain_id: ChainId,
+    key_name: String,
+    denom: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_balance: Option<String>,
+    status: WalletStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum WalletStatus {
+    Ok,
+    Low,
+    Failed,
+}
+
+fn get_wallet_balances(config: &Config) -> ! {
+    let asset_lists: HashMap<ChainId, AssetList> =
+        fetch_registry_data(config.chains.iter().map(|chain| &chain.id));
+
+    let balances: Vec<WalletBalance> = thread::scope(|s| {
+        let handles: Vec<_> = config
+            .chains
+            .iter()
+            .map(|chain_config| s.spawn(|| get_wallet_balance(config, chain_config)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("balance query thread panicked"))
+            .collect()
+    });
+
+    let balances: Vec<WalletBalance> = balances
+        .into_iter()
+        .map(|mut balance| {
+            if let Some(asset_list) = asset_lists.get(&balance.chain_id) {
+                balance.display_amount = balance
+                    .amount
+                    .as_deref()
+                    .and_then(|amount| to_display_amount(amount, &balance.denom, asset_list));
+            }
+            balance
+        })
+        .collect();
+
+    let status = if balances
+        .iter()
+        .all(|balance| balance.status == WalletStatus::Ok)
+    {
+        Status::Success
+    } else {
+        Status::Error
+    };
+
+    if json() {
+        Output::new(status).with_result(balances).exit()
+    } else {
+        Output::new(status)
+            .with_msg(format_wallet_balances(&balances))
+            .exit()
+    }
+}
+
+fn get_wallet_balance(config: &Config, chain_config: &ChainConfig) -> WalletBalance {
+    let min_balance = chain_config
+        .min_balance
+        .as_ref()
+        .map(|min_balance| min_balance.amount(default_tx_fee(&GasConfig::from(chain_config))));
+
+    let mut wallet = WalletBalance {
+        chain_id: chain_config.id.clone(),
+        key_name: chain_config.key_name.clone(),
+        denom: chain_config.gas_price.denom.clone(),
+        amount: None,
+        display_amount: None,
+        min_balance: min_balance.map(|amount| amount.to_string()),
+        status: WalletStatus::Failed,
+        error: None,
+    };
+
+    let balance = spawn_chain_runtime(config, &chain_config.id)
+        .map_err(|e| e.to_string())
+        .and_then(|chain| chain.query_balance(None, None).map_err(|e| e.to_string()));
+
+    match balance {
+        Ok(balance) => {
+            let is_low = match (balance.amount.parse::<u128>(), min_balance) {
+                (Ok(amount), Some(min_balance)) => amount < min_balance,
+                _ => false,
+            };
+
+            wallet.status = if is_low {
+                WalletStatus::Low
+            } else {
+                WalletStatus::Ok
+            };
+            wallet.amount = Some(balance.amount);
+            wallet.denom = balance.denom;
+        }
+        Err(e) => wallet.error = Some(e),
+    }
+
+    wallet
+}
+
+/// Converts an amount of the given base denomination into the display unit
+/// of the corresponding asset in the chain registry, eg. `1500000uatom` into `1.5 ATOM`.
+fn to_display_amount(amount: &str, denom: &str, asset_list: &AssetList) -> Option<String> {
+    let asset = asset_list.assets.iter().find(|asset| asset.base == denom)?;
+
+    let exponent = asset
+        .denom_units
+        .iter()
+        .find(|unit| unit.denom == asset.display)?
+        .exponent;
+
+    let amount = amount.parse::<u128>().ok()?;
+    let scale = 10u128.checked_pow(u32::from(exponent))?;
+
+    let integral = amount / scale;
+    let fractional = amount % scale;
+
+    let symbol = if asset.symbol.is_empty() {
+        &asset.display
+    } else {
+        &asset.symbol
+    };
+
+    if fractional == 0 {
+        Some(format!("{integral} {symbol}"))
+    } else {
+        let fractional = format!("{fractional:0width$}", width = usize::from(exponent));
+        Some(format!(
+            "{integral}.{} {symbol}",
+            fractional.trim_end_matches('0')
+        ))
+    }
+}
+
+fn format_wallet_balances(balances: &[WalletBalance]) -> String {
+    let chain_width = balances
+        .iter()
+        .map(|balance| balance.chain_id.as_str().len())
+        .chain(Some("CHAIN".len()))
+        .max()
+        .unwrap_or_default();
+
+    let rows: Vec<(String, String)> = balances
+        .iter()
+        .map(|balance| {
+            let amount = match (&balance.display_amount, &balance.amount) {
+                (Some(display_amount), Some(amount)) => {
+                    format!("{display_amount} ({amount}{})", balance.denom)
+                }
+                (None, Some(amount)) => format!("{amount}{}", balance.denom),
+                _ => "-".to_string(),
+            };
+
+            let min_balance = match &balance.min_balance {
+                Some(min_balance) => format!("{min_balance}{}", balance.denom),
+                None => "-".to_string(),
+            };
+
+            (amount, min_balance)
+        })
+        .collect();
+
+    let amount_width = rows
+        .iter()
+        .map(|(amount, _)| amount.len())
+        .chain(Some("BALANCE".len()))
+        .max()
+        .unwrap_or_default();
+
+    let min_width = rows
+        .iter()
+        .map(|(_, min_balance)| min_balance.len())
+        .chain(Some("MIN BALANCE".len()))
+        .max()
+        .unwrap_or_default();
+
+    let mut table = String::new();
+
+    let _ = write!(
+        table,
+        "\n{:<chain_width$}  {:<amount_width$}  {:<min_width$}  STATUS",
+        "CHAIN", "BALANCE", "MIN BALANCE"
+    );
+
+    for (balance, (amount, min_balance)) in balances.iter().zip(rows) {
+        let status = match (balance.status, &balance.error) {
+            (WalletStatus::Ok, _) => "ok".to_string(),
+            (WalletStatus::Low, _) => "LOW".to_string(),
+            (WalletStatus::Failed, Some(e)) => format!("failed: {e}"),
+            (WalletStatus::Failed, None) => "failed".to_string(),
+        };
+
+        let _ = write!(
+            table,
+            "\n{:<chain_width$}  {:<amount_width$}  {:<min_width$}  {}",
+            balance.chain_id.as_str(),
+            amount,
+            min_balance,
+            status
+        );
+    }
+
+    table
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::KeyBalanceCmd;
+    use super::{to_display_amount, KeyBalanceCmd};
 
     use abscissa_core::clap::Parser;
+    use ibc_chain_registry::asset_list::{Asset, AssetList, DenomUnit};
     use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 
     #[test]
     fn test_keys_balance_required_only() {
         assert_eq!(
             KeyBalanceCmd {
-                chain_id: ChainId::from_string("chain_id"),
+                chain_id: Some(ChainId::from_string("chain_id")),
+                all_chains: false,
                 key_name: None,
                 denom: None,
                 all: false,
@@ -135,7 +384,8 @@ mod tests {
     fn test_keys_balance_name() {
         assert_eq!(
             KeyBalanceCmd {
-                chain_id: ChainId::from_string("chain_id"),
+                chain_id: Some(ChainId::from_string("chain_id")),
+                all_chains: false,
                 key_name: Some("kname".to_owned()),
                 denom: None,
                 all: false,
@@ -148,7 +398,8 @@ mod tests {
     fn test_keys_balance_denom() {
         assert_eq!(
             KeyBalanceCmd {
-                chain_id: ChainId::from_string("chain_id"),
+                chain_id: Some(ChainId::from_string("chain_id")),
+                all_chains: false,
                 key_name: None,
                 denom: Some("samoleans".to_owned()),
                 all: false,
@@ -161,7 +412,8 @@ mod tests {
     fn test_keys_balance_all_denom() {
         assert_eq!(
             KeyBalanceCmd {
-                chain_id: ChainId::from_string("chain_id"),
+                chain_id: Some(ChainId::from_string("chain_id")),
+                all_chains: false,
                 key_name: None,
                 denom: None,
                 all: true,
@@ -174,4 +426,62 @@ mod tests {
     fn test_keys_balance_no_chain() {
         assert!(KeyBalanceCmd::try_parse_from(["test"]).is_err())
     }
+
+    #[test]
+    fn test_keys_balance_all_chains() {
+        assert_eq!(
+            KeyBalanceCmd {
+                chain_id: None,
+                all_chains: true,
+                key_name: None,
+                denom: None,
+                all: false,
+            },
+            KeyBalanceCmd::parse_from(["test", "--all-chains"])
+        )
+    }
+
+    #[test]
+    fn test_keys_balance_all_chains_and_chain() {
+        assert!(
+            KeyBalanceCmd::try_parse_from(["test", "--all-chains", "--chain", "chain_id"]).is_err()
+        )
+    }
+
+    #[test]
+    fn test_display_amount() {
+        let asset_list = AssetList {
+            assets: vec![Asset {
+                base: "uatom".to_string(),
+                display: "atom".to_string(),
+                symbol: "ATOM".to_string(),
+                denom_units: vec![
+                    DenomUnit {
+                        denom: "uatom".to_string(),
+                        exponent: 0,
+                    },
+                    DenomUnit {
+                        denom: "atom".to_string(),
+                        exponent: 6,
+                    },
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            to_display_amount("1500000", "uatom", &asset_list).as_deref(),
+            Some("1.5 ATOM")
+        );
+        assert_eq!(
+            to_display_amount("2000000", "uatom", &asset_list).as_deref(),
+            Some("2 ATOM")
+        );
+        assert_eq!(
+            to_display_amount("42", "uatom", &asset_list).as_deref(),
+            Some("0.000042 ATOM")
+        );
+        assert_eq!(to_display_amount("42", "uosmo", &asset_list), None);
+    }
 }
@@ -4,23 +4,37 @@ use core::{
     ops::Deref,
     str::FromStr,
 };
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
 use std::thread;
 
 use abscissa_core::clap::Parser;
 use abscissa_core::{application::fatal_error, Runnable};
 use eyre::eyre;
 use itertools::Itertools;
-use tendermint_rpc::{client::CompatMode, Client, HttpClient};
+use serde::Serialize;
+use tendermint_rpc::{Client, HttpClient};
 use tokio::runtime::Runtime as TokioRuntime;
-use tracing::{error, info, instrument};
+use tracing::{error, info};
 
 use ibc_relayer::{
-    chain::handle::Subscription,
+    chain::{
+        cosmos::compatibility::{comet_compat_or_default, CometCompat},
+        handle::Subscription,
+    },
     config::{ChainConfig, EventSourceMode},
-    event::source::websocket::EventSource,
+    event::{source::websocket::EventSource, IbcEventWithHeight},
+};
+use ibc_relayer_types::{
+    core::{
+        ics04_channel::packet::Sequence,
+        ics24_host::identifier::{ChainId, ChannelId, PortId},
+    },
+    events::IbcEvent,
 };
-use ibc_relayer_types::{core::ics24_host::identifier::ChainId, events::IbcEvent};
 
+use crate::conclude::json;
 use crate::prelude::*;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -59,30 +73,191 @@ impl FromStr for EventFilter {
     }
 }
 
+/// Filter on a field of the packet data, which must then be JSON-encoded, eg. as for ICS-20 packets.
+///
+/// Nested fields are given as a dot-separated path, eg. `memo.forward.receiver`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PacketDataFilter {
+    path: Vec<String>,
+    value: String,
+}
+
+impl PacketDataFilter {
+    pub fn matches(&self, data: &[u8]) -> bool {
+        let Ok(json) = serde_json::from_slice::<serde_json::Value>(data) else {
+            return false;
+        };
+
+        let field = self
+            .path
+            .iter()
+            .try_fold(&json, |value, key| value.get(key.as_str()));
+
+        match field {
+            Some(serde_json::Value::String(s)) => s == &self.value,
+            Some(value) => serde_json::from_str::<serde_json::Value>(&self.value)
+                .map_or(false, |expected| &expected == value),
+            None => false,
+        }
+    }
+}
+
+impl FromStr for PacketDataFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (path, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid packet data filter '{s}', expected FIELD=VALUE"))?;
+
+        if path.is_empty() {
+            return Err(format!(
+                "invalid packet data filter '{s}', the field is empty"
+            ));
+        }
+
+        Ok(Self {
+            path: path.split('.').map(String::from).collect(),
+            value: value.to_string(),
+        })
+    }
+}
+
+/// Filters on the packet and channel fields of the events.
+///
+/// An event matches if, for each kind of filter given, it matches at least one of the values.
+/// Events without the filtered field, such as `NewBlock` events, never match.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldFilters {
+    pub port_ids: Vec<PortId>,
+    pub channel_ids: Vec<ChannelId>,
+    pub sequences: Vec<Sequence>,
+    pub packet_data: Vec<PacketDataFilter>,
+}
+
+impl FieldFilters {
+    pub fn is_empty(&self) -> bool {
+        self.port_ids.is_empty()
+            && self.channel_ids.is_empty()
+            && self.sequences.is_empty()
+            && self.packet_data.is_empty()
+    }
+
+    pub fn matches(&self, event: &IbcEvent) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        if let Some(packet) = event.packet() {
+            return self.matches_ends(
+                &[&packet.source_port, &packet.destination_port],
+                &[&packet.source_channel, &packet.destination_channel],
+            ) && (self.sequences.is_empty() || self.sequences.contains(&packet.sequence))
+                && (self.packet_data.is_empty()
+                    || self.packet_data.iter().any(|f| f.matches(&packet.data)));
+        }
+
+        // Channel handshake events have neither a sequence nor packet data
+        if let Some(attrs) = event.clone().channel_attributes() {
+            let channels = [&attrs.channel_id, &attrs.counterparty_channel_id]
+                .into_iter()
+                .flatten()
+                .collect_vec();
+
+            return self.sequences.is_empty()
+                && self.packet_data.is_empty()
+                && self.matches_ends(&[&attrs.port_id, &attrs.counterparty_port_id], &channels);
+        }
+
+        false
+    }
+
+    fn matches_ends(&self, ports: &[&PortId], channels: &[&ChannelId]) -> bool {
+        (self.port_ids.is_empty() || ports.iter().any(|p| self.port_ids.contains(p)))
+            && (self.channel_ids.is_empty()
+                || channels.iter().any(|c| self.channel_ids.contains(c)))
+    }
+}
+
 #[derive(Debug, Parser, PartialEq, Eq)]
 pub struct ListenCmd {
-    /// Identifier of the chain to listen for events from
+    /// Identifier of a chain to listen for events from, can be repeated
     #[clap(
         long = "chain",
         required = true,
+        multiple_occurrences = true,
         help_heading = "REQUIRED",
         value_name = "CHAIN_ID"
     )]
-    chain_id: ChainId,
+    chain_ids: Vec<ChainId>,
 
     /// Add an event type to listen for, can be repeated.
     /// Listen for all events by default (available: Tx, NewBlock).
     #[clap(long = "events", value_name = "EVENT", multiple_values = true)]
     events: Vec<EventFilter>,
+
+    /// Only display the packet or channel events on the given port, on either end, can be repeated
+    #[clap(long = "port", value_name = "PORT_ID", multiple_occurrences = true)]
+    port_ids: Vec<PortId>,
+
+    /// Only display the packet or channel events on the given channel, on either end, can be repeated
+    #[clap(
+        long = "channel",
+        value_name = "CHANNEL_ID",
+        multiple_occurrences = true
+    )]
+    channel_ids: Vec<ChannelId>,
+
+    /// Only display the packet events with the given sequence, can be repeated
+    #[clap(
+        long = "sequence",
+        value_name = "SEQUENCE",
+        multiple_occurrences = true
+    )]
+    sequences: Vec<Sequence>,
+
+    /// Only display the packet events whose JSON packet data has the given value in the given
+    /// field, eg. `sender=cosmos1...` or `memo.forward.receiver=osmo1...`, can be repeated
+    #[clap(
+        long = "packet-data",
+        value_name = "FIELD=VALUE",
+        multiple_occurrences = true
+    )]
+    packet_data: Vec<PacketDataFilter>,
+
+    /// Also write the events as JSON lines to the given file, which is rotated when it gets too large
+    #[clap(long = "output-file", value_name = "PATH")]
+    output_file: Option<PathBuf>,
+
+    /// Size in bytes above which the output file is rotated
+    #[clap(
+        long = "max-file-size",
+        value_name = "BYTES",
+        default_value_t = DEFAULT_MAX_FILE_SIZE
+    )]
+    max_file_size: u64,
+
+    /// Number of rotated output files to keep
+    #[clap(long = "max-files", value_name = "COUNT", default_value_t = DEFAULT_MAX_FILES)]
+    max_files: usize,
 }
 
+const DEFAULT_MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 5;
+
 impl ListenCmd {
     fn cmd(&self) -> eyre::Result<()> {
         let config = app_config();
 
-        let chain_config = config
-            .find_chain(&self.chain_id)
-            .ok_or_else(|| eyre!("chain '{}' not found in configuration", self.chain_id))?;
+        let chain_configs = self
+            .chain_ids
+            .iter()
+            .map(|chain_id| {
+                config
+                    .find_chain(chain_id)
+                    .ok_or_else(|| eyre!("chain '{}' not found in configuration", chain_id))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
 
         let events = if self.events.is_empty() {
             &[EventFilter::Tx, EventFilter::NewBlock]
@@ -90,7 +265,20 @@ impl ListenCmd {
             self.events.as_slice()
         };
 
-        listen(chain_config, events)
+        let field_filters = FieldFilters {
+            port_ids: self.port_ids.clone(),
+            channel_ids: self.channel_ids.clone(),
+            sequences: self.sequences.clone(),
+            packet_data: self.packet_data.clone(),
+        };
+
+        let output_file = self
+            .output_file
+            .as_ref()
+            .map(|path| RotatingFile::open(path.clone(), self.max_file_size, self.max_files))
+            .transpose()?;
+
+        listen(&chain_configs, events, &field_filters, output_file)
     }
 }
 
@@ -101,31 +289,83 @@ impl Runnable for ListenCmd {
     }
 }
 
+/// An event, as output by `listen` in JSON
+#[derive(Serialize)]
+struct ListenedEvent<'a> {
+    chain_id: &'a ChainId,
+    #[serde(flatten)]
+    event: &'a IbcEventWithHeight,
+}
+
 /// Listen to events
-#[instrument(skip_all, level = "error", fields(chain = %config.id))]
-pub fn listen(config: &ChainConfig, filters: &[EventFilter]) -> eyre::Result<()> {
+pub fn listen(
+    configs: &[&ChainConfig],
+    filters: &[EventFilter],
+    field_filters: &FieldFilters,
+    mut output_file: Option<RotatingFile>,
+) -> eyre::Result<()> {
     let rt = Arc::new(TokioRuntime::new()?);
-    let compat_mode = detect_compatibility_mode(config, rt.clone())?;
-    let rx = subscribe(config, compat_mode, rt)?;
+
+    // Merge the subscriptions to all the chains into a single channel
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    for config in configs {
+        let _span = tracing::error_span!("listen", chain = %config.id).entered();
+
+        let compat_mode = detect_compatibility_mode(config, rt.clone())?;
+        let subscription = subscribe(config, compat_mode, rt.clone())?;
+
+        let tx = tx.clone();
+        thread::spawn(move || {
+            while let Ok(event_batch) = subscription.recv() {
+                if tx.send(event_batch).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    drop(tx);
 
     while let Ok(event_batch) = rx.recv() {
         match event_batch.as_ref() {
             Ok(batch) => {
-                let _span =
-                    tracing::error_span!("event_batch", batch_height = %batch.height).entered();
+                let _span = tracing::error_span!(
+                    "event_batch",
+                    chain = %batch.chain_id,
+                    batch_height = %batch.height
+                )
+                .entered();
 
                 let matching_events = batch
                     .events
                     .iter()
-                    .filter(|e| event_match(&e.event, filters))
+                    .filter(|e| event_match(&e.event, filters) && field_filters.matches(&e.event))
                     .collect_vec();
 
-                if matching_events.is_empty() {
-                    continue;
-                }
-
                 for event in matching_events {
-                    info!("{}", event);
+                    let listened = ListenedEvent {
+                        chain_id: &batch.chain_id,
+                        event,
+                    };
+
+                    if json() || output_file.is_some() {
+                        let line = serde_json::to_string(&listened)?;
+
+                        if json() {
+                            println!("{line}");
+                        }
+
+                        if let Some(file) = output_file.as_mut() {
+                            if let Err(e) = file.write_line(&line) {
+                                error!("failed to write event to output file: {}", e);
+                            }
+                        }
+                    }
+
+                    if !json() {
+                        info!("{}", event);
+                    }
                 }
             }
             Err(e) => error!("- error: {}", e),
@@ -139,20 +379,102 @@ fn event_match(event: &IbcEvent, filters: &[EventFilter]) -> bool {
     filters.iter().any(|f| f.matches(event))
 }
 
+/// A file to which lines are appended, which is rotated once it exceeds a maximum size.
+///
+/// When rotated, the file `PATH` is renamed to `PATH.1`, `PATH.1` to `PATH.2` and so on,
+/// up to the maximum number of rotated files to keep, after which the oldest one is removed.
+#[derive(Debug)]
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    pub fn open(path: PathBuf, max_size: u64, max_files: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_size,
+            max_files,
+        })
+    }
+
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 + 1 > self.max_size {
+            self.rotate()?;
+        }
+
+        self.file.write_all(line.as_bytes())?;
+        self.file.write_all(b"\n")?;
+        self.size += line.len() as u64 + 1;
+
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let oldest = self.rotated_path(self.max_files);
+            if oldest.exists() {
+                fs::remove_file(oldest)?;
+            }
+
+            for index in (1..self.max_files).rev() {
+                let rotated = self.rotated_path(index);
+                if rotated.exists() {
+                    fs::rename(rotated, self.rotated_path(index + 1))?;
+                }
+            }
+
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+
+        Ok(())
+    }
+}
+
 fn subscribe(
     chain_config: &ChainConfig,
-    compat_mode: CompatMode,
+    comet_compat: CometCompat,
     rt: Arc<TokioRuntime>,
 ) -> eyre::Result<Subscription> {
-    let EventSourceMode::Push { url, batch_delay } = &chain_config.event_source else {
-        return Err(eyre!("unsupported event source mode, only 'push' is supported for listening to events"));
+    let (EventSourceMode::Push { url, batch_delay }
+    | EventSourceMode::Hybrid {
+        url, batch_delay, ..
+    }) = &chain_config.event_source
+    else {
+        return Err(eyre!("unsupported event source mode, only 'push' and 'hybrid' are supported for listening to events"));
     };
 
     let (mut event_source, tx_cmd) = EventSource::new(
         chain_config.id.clone(),
         url.clone(),
-        compat_mode,
+        comet_compat.compat_mode,
         *batch_delay,
+        None,
+        comet_compat.block_results_layout,
         rt,
     )
     .map_err(|e| eyre!("could not initialize event source: {}", e))?;
@@ -173,32 +495,64 @@ fn subscribe(
 fn detect_compatibility_mode(
     config: &ChainConfig,
     rt: Arc<TokioRuntime>,
-) -> eyre::Result<CompatMode> {
+) -> eyre::Result<CometCompat> {
     let client = HttpClient::new(config.rpc_addr.clone())?;
     let status = rt.block_on(client.status())?;
-    let compat_mode = CompatMode::from_version(status.node_info.version).unwrap_or_else(|e| {
-        warn!("Unsupported tendermint version, will use v0.37 compatibility mode but relaying might not work as desired: {e}");
-        CompatMode::V0_37
-    });
-    Ok(compat_mode)
+    Ok(comet_compat_or_default(&status.node_info.version))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{EventFilter, ListenCmd};
+    use super::{
+        EventFilter, FieldFilters, ListenCmd, PacketDataFilter, RotatingFile, DEFAULT_MAX_FILES,
+        DEFAULT_MAX_FILE_SIZE,
+    };
 
     use std::str::FromStr;
 
     use abscissa_core::clap::Parser;
+    use ibc_relayer_types::core::ics02_client::events::NewBlock;
+    use ibc_relayer_types::core::ics04_channel::events::SendPacket;
+    use ibc_relayer_types::core::ics04_channel::packet::Packet;
     use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+    use ibc_relayer_types::events::IbcEvent;
+    use ibc_relayer_types::Height;
+
+    fn listen_cmd(chain_ids: &[&str], events: Vec<EventFilter>) -> ListenCmd {
+        ListenCmd {
+            chain_ids: chain_ids
+                .iter()
+                .map(|id| ChainId::from_string(id))
+                .collect(),
+            events,
+            port_ids: vec![],
+            channel_ids: vec![],
+            sequences: vec![],
+            packet_data: vec![],
+            output_file: None,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            max_files: DEFAULT_MAX_FILES,
+        }
+    }
+
+    fn send_packet(data: &str) -> IbcEvent {
+        IbcEvent::SendPacket(SendPacket {
+            packet: Packet {
+                sequence: 7.into(),
+                source_port: "transfer".parse().unwrap(),
+                source_channel: "channel-0".parse().unwrap(),
+                destination_port: "transfer".parse().unwrap(),
+                destination_channel: "channel-5".parse().unwrap(),
+                data: data.as_bytes().to_vec(),
+                ..Packet::default()
+            },
+        })
+    }
 
     #[test]
     fn test_listen_required_only() {
         assert_eq!(
-            ListenCmd {
-                chain_id: ChainId::from_string("chain_id"),
-                events: vec!()
-            },
+            listen_cmd(&["chain_id"], vec!()),
             ListenCmd::parse_from(["test", "--chain", "chain_id"])
         )
     }
@@ -206,10 +560,7 @@ mod tests {
     #[test]
     fn test_listen_single_event() {
         assert_eq!(
-            ListenCmd {
-                chain_id: ChainId::from_string("chain_id"),
-                events: vec!(EventFilter::from_str("Tx").unwrap())
-            },
+            listen_cmd(&["chain_id"], vec!(EventFilter::from_str("Tx").unwrap())),
             ListenCmd::parse_from(["test", "--chain", "chain_id", "--events", "Tx"])
         )
     }
@@ -217,13 +568,13 @@ mod tests {
     #[test]
     fn test_listen_multiple_events() {
         assert_eq!(
-            ListenCmd {
-                chain_id: ChainId::from_string("chain_id"),
-                events: vec!(
+            listen_cmd(
+                &["chain_id"],
+                vec!(
                     EventFilter::from_str("Tx").unwrap(),
                     EventFilter::from_str("NewBlock").unwrap()
                 )
-            },
+            ),
             ListenCmd::parse_from([
                 "test", "--chain", "chain_id", "--events", "Tx", "--events", "NewBlock"
             ])
@@ -233,13 +584,13 @@ mod tests {
     #[test]
     fn test_listen_multiple_events_single_flag() {
         assert_eq!(
-            ListenCmd {
-                chain_id: ChainId::from_string("chain_id"),
-                events: vec!(
+            listen_cmd(
+                &["chain_id"],
+                vec!(
                     EventFilter::from_str("Tx").unwrap(),
                     EventFilter::from_str("NewBlock").unwrap()
                 )
-            },
+            ),
             ListenCmd::parse_from(["test", "--chain", "chain_id", "--events", "Tx", "NewBlock"])
         )
     }
@@ -260,4 +611,99 @@ mod tests {
     fn test_listen_unknown_no_chain() {
         assert!(ListenCmd::try_parse_from(["test"]).is_err())
     }
+
+    #[test]
+    fn test_listen_multiple_chains_and_filters() {
+        let mut cmd = listen_cmd(&["chain_a", "chain_b"], vec![]);
+        cmd.channel_ids = vec!["channel-0".parse().unwrap()];
+        cmd.sequences = vec![7.into(), 8.into()];
+        cmd.packet_data = vec!["sender=alice".parse().unwrap()];
+        cmd.output_file = Some("events.jsonl".into());
+        cmd.max_files = 2;
+
+        assert_eq!(
+            cmd,
+            ListenCmd::parse_from([
+                "test",
+                "--chain",
+                "chain_a",
+                "--chain",
+                "chain_b",
+                "--channel",
+                "channel-0",
+                "--sequence",
+                "7",
+                "--sequence",
+                "8",
+                "--packet-data",
+                "sender=alice",
+                "--output-file",
+                "events.jsonl",
+                "--max-files",
+                "2"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_field_filters() {
+        let event =
+            send_packet(r#"{"sender":"alice","amount":10,"memo":{"forward":{"port":"transfer"}}}"#);
+
+        assert!(FieldFilters::default().matches(&event));
+
+        let filters = |f: fn(&mut FieldFilters)| {
+            let mut filters = FieldFilters::default();
+            f(&mut filters);
+            filters
+        };
+
+        assert!(filters(|f| f.port_ids = vec!["transfer".parse().unwrap()]).matches(&event));
+        assert!(filters(|f| f.channel_ids = vec!["channel-5".parse().unwrap()]).matches(&event));
+        assert!(!filters(|f| f.channel_ids = vec!["channel-1".parse().unwrap()]).matches(&event));
+        assert!(filters(|f| f.sequences = vec![1.into(), 7.into()]).matches(&event));
+        assert!(!filters(|f| f.sequences = vec![1.into()]).matches(&event));
+        assert!(filters(|f| f.packet_data = vec!["sender=alice".parse().unwrap()]).matches(&event));
+        assert!(
+            filters(|f| f.packet_data = vec!["memo.forward.port=transfer".parse().unwrap()])
+                .matches(&event)
+        );
+        assert!(filters(|f| f.packet_data = vec!["amount=10".parse().unwrap()]).matches(&event));
+        assert!(!filters(|f| f.packet_data = vec!["sender=bob".parse().unwrap()]).matches(&event));
+        assert!(
+            !filters(|f| f.packet_data = vec!["sender=alice".parse().unwrap()])
+                .matches(&send_packet("not json"))
+        );
+        assert!(
+            !filters(|f| f.sequences = vec![7.into()]).matches(&IbcEvent::NewBlock(NewBlock::new(
+                Height::new(0, 1).unwrap()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_packet_data_filter_invalid() {
+        assert!(PacketDataFilter::from_str("sender").is_err());
+        assert!(PacketDataFilter::from_str("=alice").is_err());
+    }
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = std::env::temp_dir().join(format!("hermes-listen-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("events.jsonl");
+
+        let mut file = RotatingFile::open(path, 10, 2).unwrap();
+        for line in ["line-1", "line-2", "line-3", "line-4"] {
+            file.write_line(line).unwrap();
+        }
+
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("events.jsonl"), "line-4\n");
+        assert_eq!(read("events.jsonl.1"), "line-3\n");
+        assert_eq!(read("events.jsonl.2"), "line-2\n");
+        assert!(!dir.join("events.jsonl.3").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -5,6 +5,7 @@ mod ack;
 mod acks;
 mod commitment;
 mod commitments;
+mod history;
 mod pending;
 mod pending_acks;
 mod pending_sends;
@@ -32,4 +33,7 @@ pub enum QueryPacketCmds {
 
     /// Output a summary of pending packets in both directions
     Pending(pending::QueryPendingPacketsCmd),
+
+    /// Look up the packet messages relayed on a channel in the packet audit log
+    History(history::QueryPacketHistoryCmd),
 }
@@ -0,0 +1,154 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc_relayer::audit::{audit_log_path, read_audit_log, PacketRecord};
+use ibc_relayer_types::core::ics04_channel::packet::Sequence;
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId};
+
+use crate::conclude::Output;
+use crate::prelude::*;
+
+/// Looks up the packet messages relayed on a channel in the audit log,
+/// which is written by `hermes start` if enabled in the `[audit]` section.
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct QueryPacketHistoryCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain at either end of the channel"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "channel",
+        visible_alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel on the chain"
+    )]
+    channel_id: ChannelId,
+
+    #[clap(
+        long = "sequence",
+        visible_alias = "seq",
+        value_name = "SEQUENCE",
+        help = "Sequence of the packet to look up. Leave unspecified for all the packets of the channel."
+    )]
+    sequence: Option<Sequence>,
+}
+
+impl QueryPacketHistoryCmd {
+    fn matches(&self, record: &PacketRecord) -> bool {
+        record.involves(&self.chain_id, &self.channel_id)
+            && self.sequence.map_or(true, |seq| record.sequence == seq)
+    }
+}
+
+impl Runnable for QueryPacketHistoryCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let path = match audit_log_path(&config.audit) {
+            Ok(path) => path,
+            Err(e) => Output::error(e).exit(),
+        };
+
+        let records = match read_audit_log(&path) {
+            Ok(records) => records,
+            Err(e) => Output::error(format!(
+                "failed to read the packet audit log, which is recorded by `hermes start` if \
+                 enabled in the `[audit]` section: {e}"
+            ))
+            .exit(),
+        };
+
+        let history = records
+            .into_iter()
+            .filter(|record| self.matches(record))
+            .collect::<Vec<_>>();
+
+        Output::success(history).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryPacketHistoryCmd;
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc_relayer_types::core::ics04_channel::packet::Sequence;
+    use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId};
+
+    #[test]
+    fn test_query_packet_history() {
+        assert_eq!(
+            QueryPacketHistoryCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                sequence: Some(Sequence::from(42)),
+            },
+            QueryPacketHistoryCmd::parse_from([
+                "test",
+                "--chain",
+                "chain_id",
+                "--channel",
+                "channel-07",
+                "--sequence",
+                "42"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_packet_history_aliases() {
+        assert_eq!(
+            QueryPacketHistoryCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                sequence: Some(Sequence::from(42)),
+            },
+            QueryPacketHistoryCmd::parse_from([
+                "test",
+                "--chain",
+                "chain_id",
+                "--chan",
+                "channel-07",
+                "--seq",
+                "42"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_packet_history_no_sequence() {
+        assert_eq!(
+            QueryPacketHistoryCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                sequence: None,
+            },
+            QueryPacketHistoryCmd::parse_from([
+                "test",
+                "--chain",
+                "chain_id",
+                "--chan",
+                "channel-07"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_packet_history_no_chan() {
+        assert!(QueryPacketHistoryCmd::try_parse_from(["test", "--chain", "chain_id"]).is_err())
+    }
+
+    #[test]
+    fn test_query_packet_history_no_chain() {
+        assert!(QueryPacketHistoryCmd::try_parse_from(["test", "--channel", "channel-07"]).is_err())
+    }
+}
@@ -0,0 +1,145 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc_relayer::chain::handle::BaseChainHandle;
+use ibc_relayer::event::record::read_recorded_batches;
+use ibc_relayer::event::IbcEventWithHeight;
+use ibc_relayer::registry::Registry;
+use ibc_relayer::supervisor::client_state_filter::FilterPolicy;
+use ibc_relayer::supervisor::replay_batch;
+use ibc_relayer::worker::WorkerMap;
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use ibc_relayer_types::Height;
+
+use crate::conclude::{exit_with_unrecoverable_error, json, Output};
+use crate::prelude::*;
+
+/// The events of a replayed batch which would have been forwarded to the worker of an object.
+#[derive(Debug, Serialize)]
+struct ReplayedObject {
+    object: String,
+    events: Vec<IbcEventWithHeight>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReplayedBatch {
+    chain_id: ChainId,
+    height: Height,
+    tracking_id: String,
+    objects: Vec<ReplayedObject>,
+}
+
+#[derive(Clone, Command, Debug, Parser, PartialEq, Eq)]
+pub struct ReplayCmd {
+    #[clap(
+        value_name = "DIR",
+        help = "Directory in which the event batches were recorded with `hermes start --record-events`"
+    )]
+    dir: PathBuf,
+}
+
+impl Runnable for ReplayCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let batches = match read_recorded_batches(&self.dir) {
+            Ok(batches) => batches,
+            Err(e) => Output::error(format!("failed to read the recorded events: {e}")).exit(),
+        };
+
+        info!("replaying {} recorded event batches", batches.len());
+
+        // No worker is ever spawned, so no transaction gets submitted
+        let mut registry = Registry::<BaseChainHandle>::new((*config).clone());
+        let mut client_state_filter = FilterPolicy::default();
+        let workers = WorkerMap::new();
+
+        let mut replayed = Vec::new();
+
+        for batch in batches {
+            let forwarded = match replay_batch(
+                &config,
+                &mut registry,
+                &mut client_state_filter,
+                &workers,
+                &batch,
+            ) {
+                Ok(forwarded) => forwarded,
+                Err(e) => {
+                    error!(
+                        "failed to replay batch for chain {} at height {}: {}",
+                        batch.chain_id, batch.height, e
+                    );
+                    continue;
+                }
+            };
+
+            replayed.push(ReplayedBatch {
+                chain_id: batch.chain_id,
+                height: batch.height,
+                tracking_id: batch.tracking_id.to_string(),
+                objects: forwarded
+                    .into_iter()
+                    .map(|(object, events)| ReplayedObject {
+                        object: object.short_name(),
+                        events,
+                    })
+                    .collect(),
+            });
+        }
+
+        if json() {
+            Output::success(replayed).exit()
+        }
+
+        let mut pretty_output = format!("replayed {} event batches", replayed.len());
+
+        for batch in &replayed {
+            write!(
+                pretty_output,
+                "\nbatch for chain {} at height {} (tracking id: {})",
+                batch.chain_id, batch.height, batch.tracking_id
+            )
+            .unwrap_or_else(exit_with_unrecoverable_error);
+
+            for object in &batch.objects {
+                write!(pretty_output, "\n  {}", object.object)
+                    .unwrap_or_else(exit_with_unrecoverable_error);
+
+                for event in &object.events {
+                    write!(pretty_output, "\n    - {event}")
+                        .unwrap_or_else(exit_with_unrecoverable_error);
+                }
+            }
+        }
+
+        Output::success_msg(pretty_output).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReplayCmd;
+
+    use abscissa_core::clap::Parser;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_replay() {
+        assert_eq!(
+            ReplayCmd {
+                dir: PathBuf::from("events/")
+            },
+            ReplayCmd::parse_from(["test", "events/"])
+        )
+    }
+
+    #[test]
+    fn test_replay_no_dir() {
+        assert!(ReplayCmd::try_parse_from(["test"]).is_err())
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc_relayer::accounting::{ledger_path, read_ledger, CostReport};
+
+use crate::application::app_config;
+use crate::conclude::Output;
+
+/// `report` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum ReportCmds {
+    /// Summarize the fees paid for the transactions submitted by Hermes and the ICS29 fees
+    /// rewarded to it, per chain, wallet and channel, as recorded in the accounting ledger
+    Costs(ReportCostsCmd),
+}
+
+#[derive(Debug, Parser, Command, PartialEq, Eq)]
+pub struct ReportCostsCmd {
+    #[clap(
+        long = "since",
+        value_name = "SINCE",
+        value_parser = parse_since,
+        help = "Only summarize the fees recorded since the given duration ago, eg. '24h' or '7d', \
+                or since the given RFC 3339 timestamp, eg. '2023-06-01T00:00:00Z' (default: all the fees recorded)"
+    )]
+    since: Option<u64>,
+}
+
+/// Parses the start of the reported period, as seconds since the Unix epoch.
+fn parse_since(since: &str) -> Result<u64, String> {
+    let time = match humantime::parse_duration(since) {
+        Ok(duration) => SystemTime::now()
+            .checked_sub(duration)
+            .ok_or_else(|| format!("duration '{since}' is too long"))?,
+        Err(_) => humantime::parse_rfc3339_weak(since)
+            .map_err(|_| format!("'{since}' is neither a duration nor an RFC 3339 timestamp"))?,
+    };
+
+    Ok(time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default())
+}
+
+impl Runnable for ReportCostsCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let path = match ledger_path(&config.accounting) {
+            Ok(path) => path,
+            Err(e) => Output::error(e).exit(),
+        };
+
+        let entries = match read_ledger(&path) {
+            Ok(entries) => entries,
+            Err(e) => Output::error(format!(
+                "failed to read the accounting ledger, which is recorded by `hermes start` if \
+                 enabled in the `[accounting]` section: {e}"
+            ))
+            .exit(),
+        };
+
+        Output::success(CostReport::new(&entries, self.since.unwrap_or_default())).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReportCostsCmd;
+
+    use abscissa_core::clap::Parser;
+
+    #[test]
+    fn test_report_costs_all() {
+        assert_eq!(
+            ReportCostsCmd { since: None },
+            ReportCostsCmd::parse_from(["test"])
+        )
+    }
+
+    #[test]
+    fn test_report_costs_since_timestamp() {
+        assert_eq!(
+            ReportCostsCmd {
+                since: Some(1685577600)
+            },
+            ReportCostsCmd::parse_from(["test", "--since", "2023-06-01T00:00:00Z"])
+        )
+    }
+
+    #[test]
+    fn test_report_costs_since_duration() {
+        let cmd = ReportCostsCmd::parse_from(["test", "--since", "1d"]);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        assert!(
+            matches!(cmd.since, Some(since) if now - since >= 86400 && now - since < 86400 + 60)
+        );
+    }
+
+    #[test]
+    fn test_report_costs_invalid_since() {
+        assert!(ReportCostsCmd::try_parse_from(["test", "--since", "yesterday"]).is_err())
+    }
+}
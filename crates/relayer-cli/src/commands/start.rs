@@ -2,6 +2,8 @@ use ibc_relayer::supervisor::SupervisorOptions;
 use ibc_relayer::util::debug_section::DebugSection;
 use std::error::Error;
 use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
 
 use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
@@ -11,7 +13,10 @@ use ibc_relayer::chain::handle::{CachingChainHandle, ChainHandle};
 use ibc_relayer::config::Config;
 use ibc_relayer::registry::SharedRegistry;
 use ibc_relayer::rest;
+use ibc_relayer::store::load_processed_heights;
 use ibc_relayer::supervisor::{cmd::SupervisorCmd, spawn_supervisor, SupervisorHandle};
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use tendermint::block::Height as BlockHeight;
 
 use crate::conclude::json;
 use crate::conclude::Output;
@@ -24,6 +29,51 @@ pub struct StartCmd {
         help = "Force a full scan of the chains for clients, connections and channels"
     )]
     full_scan: bool,
+
+    #[clap(
+        long = "from-height",
+        value_name = "CHAIN_ID=HEIGHT",
+        multiple_occurrences = true,
+        help = "Replay the events emitted on the given chain from the given height, \
+                instead of resuming from the last height processed before Hermes was stopped. \
+                Only supported by the pull and hybrid event sources, can be repeated."
+    )]
+    from_heights: Vec<FromHeight>,
+
+    #[clap(
+        long = "record-events",
+        value_name = "DIR",
+        help = "Record the event batches received from the chains to an append-only file \
+                in the given directory, so that they can be replayed with `hermes replay`"
+    )]
+    record_events: Option<PathBuf>,
+}
+
+/// The height from which to collect the events of a chain on start.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FromHeight {
+    chain_id: ChainId,
+    height: BlockHeight,
+}
+
+impl FromStr for FromHeight {
+    type Err = Box<dyn Error + Send + Sync + 'static>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (chain_id, height) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected CHAIN_ID=HEIGHT, got '{s}'"))?;
+
+        let chain_id = ChainId::from_string(chain_id);
+        let height = height
+            .parse::<u64>()
+            .map_err(|e| format!("invalid height '{height}': {e}"))?;
+
+        Ok(Self {
+            chain_id,
+            height: BlockHeight::try_from(height)?,
+        })
+    }
 }
 
 impl Runnable for StartCmd {
@@ -54,11 +104,19 @@ impl Runnable for StartCmd {
             open_or_create_profile_file(Path::new(&path_str));
         }
 
-        let config = (*app_config()).clone();
+        let mut config = (*app_config()).clone();
+
+        set_start_heights(&mut config, &self.from_heights).unwrap_or_else(|e| {
+            Output::error(format!("Hermes failed to start, last error: {e}")).exit()
+        });
+
+        let traces_enabled = config.traces.enabled;
 
         let options = SupervisorOptions {
             force_full_scan: self.full_scan,
             health_check: true,
+            record_events: self.record_events.clone(),
+            config_path: crate::config::config_path(),
         };
 
         let supervisor_handle = make_supervisor::<CachingChainHandle>(config, options)
@@ -68,7 +126,8 @@ impl Runnable for StartCmd {
 
         match crate::config::config_path() {
             Some(_) => {
-                register_signals(supervisor_handle.sender.clone()).unwrap_or_else(|e| {
+                let tx_cmd = supervisor_handle.sender.clone();
+                register_signals(tx_cmd, traces_enabled).unwrap_or_else(|e| {
                     warn!("failed to install signal handler: {}", e);
                 });
             }
@@ -84,25 +143,38 @@ impl Runnable for StartCmd {
 }
 
 /// Register the SIGHUP and SIGUSR1 signals, and notify the supervisor.
-/// - [DEPRECATED] SIGHUP: Trigger a reload of the configuration.
+/// - SIGHUP: Ask the supervisor to reload the configuration file.
 /// - SIGUSR1: Ask the supervisor to dump its state and print it to the console.
-fn register_signals(tx_cmd: Sender<SupervisorCmd>) -> Result<(), io::Error> {
-    use signal_hook::{consts::signal::*, iterator::Signals};
-
-    let sigs = vec![
-        SIGHUP,  // Reload of configuration (disabled)
+///
+/// If the export of traces is enabled, also register the SIGINT and SIGTERM signals,
+/// on which the pending traces are exported before terminating as by default.
+fn register_signals(tx_cmd: Sender<SupervisorCmd>, traces_enabled: bool) -> Result<(), io::Error> {
+    use signal_hook::{consts::signal::*, iterator::Signals, low_level::emulate_default_handler};
+
+    let mut sigs = vec![
+        SIGHUP,  // Reload of configuration
         SIGUSR1, // Dump state
     ];
 
+    if traces_enabled {
+        sigs.extend([
+            SIGINT,  // Export of traces before termination
+            SIGTERM, // Export of traces before termination
+        ]);
+    }
+
     let mut signals = Signals::new(sigs)?;
 
     std::thread::spawn(move || {
         for signal in &mut signals {
             match signal {
-                SIGHUP => warn!(
-                    "configuration reloading via SIGHUP has been disabled, \
-                     the signal handler will be removed in the future"
-                ),
+                SIGHUP => {
+                    info!("reloading configuration (triggered by SIGHUP)");
+
+                    // The supervisor logs the outcome of the reload
+                    let (tx, _rx) = crossbeam_channel::bounded(1);
+                    tx_cmd.try_send(SupervisorCmd::ReloadConfig(tx)).unwrap();
+                }
                 SIGUSR1 => {
                     info!("dumping state (triggered by SIGUSR1)");
 
@@ -124,6 +196,14 @@ fn register_signals(tx_cmd: Sender<SupervisorCmd>) -> Result<(), io::Error> {
                         }
                     });
                 }
+                SIGINT | SIGTERM => {
+                    crate::components::shutdown_traces();
+
+                    if let Err(e) = emulate_default_handler(signal) {
+                        error!("failed to terminate on signal {}: {}", signal, e);
+                        std::process::exit(1);
+                    }
+                }
 
                 _ => (),
             }
@@ -149,7 +229,8 @@ fn spawn_rest_server(config: &Config) -> Option<rest::Receiver> {
     let (tx, rx) = crossbeam_channel::unbounded();
 
     spawn_blocking(async move {
-        let result = ibc_relayer_rest::spawn((rest.host.as_str(), rest.port), tx);
+        let result =
+            ibc_relayer_rest::spawn((rest.host.as_str(), rest.port), tx, rest.token.clone());
 
         match result {
             Ok(handle) => {
@@ -232,6 +313,53 @@ fn spawn_telemetry_server(config: &Config) {
     }
 }
 
+/// Sets the height from which the pull or hybrid event source of every chain starts collecting
+/// events on start: the height given with `--from-height` for that chain, if any,
+/// or otherwise the height following the last height processed for that chain
+/// before Hermes was stopped, as recorded in the state store, if enabled.
+fn set_start_heights(
+    config: &mut Config,
+    from_heights: &[FromHeight],
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    for from_height in from_heights {
+        if config.find_chain(&from_height.chain_id).is_none() {
+            return Err(format!(
+                "cannot replay events from chain '{}', which is not in the configuration",
+                from_height.chain_id
+            )
+            .into());
+        }
+    }
+
+    let processed_heights = load_processed_heights(&config.store)?;
+
+    for chain_config in config.chains.iter_mut() {
+        let from_height = from_heights
+            .iter()
+            .find(|from_height| from_height.chain_id == chain_config.id)
+            .map(|from_height| from_height.height);
+
+        let Some(start_height) = chain_config.event_source.start_height_mut() else {
+            if from_height.is_some() {
+                warn!(
+                    chain = %chain_config.id,
+                    "replaying past events is only supported by the pull and hybrid event sources, ignoring --from-height"
+                );
+            }
+
+            continue;
+        };
+
+        *start_height = from_height.or_else(|| {
+            processed_heights
+                .get(&chain_config.id)
+                .and_then(|height| BlockHeight::try_from(height.revision_height() + 1).ok())
+        });
+    }
+
+    Ok(())
+}
+
 fn make_supervisor<Chain: ChainHandle>(
     config: Config,
     options: SupervisorOptions,
@@ -254,7 +382,11 @@ mod tests {
     #[test]
     fn test_start_required_only() {
         assert_eq!(
-            StartCmd { full_scan: false },
+            StartCmd {
+                full_scan: false,
+                from_heights: vec![],
+                record_events: None,
+            },
             StartCmd::parse_from(["test"])
         )
     }
@@ -262,8 +394,36 @@ mod tests {
     #[test]
     fn test_start_full_scan() {
         assert_eq!(
-            StartCmd { full_scan: true },
+            StartCmd {
+                full_scan: true,
+                from_heights: vec![],
+                record_events: None,
+            },
             StartCmd::parse_from(["test", "--full-scan"])
         )
     }
+
+    #[test]
+    fn test_start_from_heights() {
+        assert_eq!(
+            StartCmd {
+                full_scan: false,
+                from_heights: vec!["ibc-0=100".parse().unwrap(), "ibc-1=200".parse().unwrap()],
+                record_events: None,
+            },
+            StartCmd::parse_from([
+                "test",
+                "--from-height",
+                "ibc-0=100",
+                "--from-height",
+                "ibc-1=200"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_start_from_height_invalid() {
+        assert!(StartCmd::try_parse_from(["test", "--from-height", "ibc-0"]).is_err());
+        assert!(StartCmd::try_parse_from(["test", "--from-height", "ibc-0=abc"]).is_err());
+    }
 }
@@ -1,10 +1,17 @@
 //! Various components for internal use by the Abscissa subsystem.
 
-use abscissa_core::{Component, FrameworkError, FrameworkErrorKind};
-use tracing_subscriber::{filter::EnvFilter, util::SubscriberInitExt, FmtSubscriber};
+use abscissa_core::component::Id;
+use abscissa_core::{
+    Application, Component, FrameworkError, FrameworkErrorKind, Shutdown, Version,
+};
+use tokio::runtime::Runtime;
+use tracing_subscriber::{
+    filter::EnvFilter, fmt, layer::SubscriberExt, registry::LookupSpan, util::SubscriberInitExt,
+    Layer,
+};
 
 use ibc_relayer::{
-    config::{GlobalConfig, LogLevel},
+    config::{GlobalConfig, LogLevel, TracesConfig},
     util::debug_section::DebugSection,
 };
 
@@ -20,33 +27,58 @@ const HERMES_LOG_VAR: &str = "RUST_LOG";
 /// - Customizing the log output level, for filtering the output produced via tracing macros
 ///   (`debug!`, `info!`, etc.) or abscissa macros (`status_err`, `status_info`, etc.).
 /// - Enabling JSON-formatted output without coloring
-#[derive(Component, Debug)]
-pub struct JsonTracing;
+/// - Exporting the relaying pipeline as OpenTelemetry traces, if enabled
+#[derive(Debug)]
+pub struct JsonTracing {
+    /// The runtime on which the traces are exported, if enabled
+    #[allow(dead_code)]
+    traces_runtime: Option<Runtime>,
+}
 
 impl JsonTracing {
     /// Creates a new [`JsonTracing`] component
-    pub fn new(cfg: GlobalConfig, debug_sections: &[DebugSection]) -> Result<Self, FrameworkError> {
+    pub fn new(
+        cfg: GlobalConfig,
+        traces: TracesConfig,
+        debug_sections: &[DebugSection],
+    ) -> Result<Self, FrameworkError> {
         let filter = build_tracing_filter(cfg.log_level, debug_sections)?;
         // Note: JSON formatter is un-affected by ANSI 'color' option. Set to 'false'.
         let use_color = false;
 
-        // Construct a tracing subscriber with the supplied filter and enable reloading.
-        let builder = FmtSubscriber::builder()
+        let fmt_layer = fmt::layer()
             .with_target(false)
-            .with_env_filter(filter)
             .with_writer(std::io::stdout)
             .with_ansi(use_color)
             .with_thread_ids(true)
-            .json();
+            .json()
+            .with_filter(filter);
 
-        let subscriber = builder.finish();
-        subscriber.init();
+        let (traces_layer, traces_runtime) = build_traces_layer(&traces)?;
 
-        Ok(Self)
+        tracing_subscriber::registry()
+            .with(fmt_layer)
+            .with(traces_layer)
+            .init();
+
+        Ok(Self { traces_runtime })
     }
 }
 
-#[derive(Component, Debug)]
+impl<A: Application> Component<A> for JsonTracing {
+    fn id(&self) -> Id {
+        Id::new(concat!(module_path!(), "::", "JsonTracing"))
+    }
+
+    fn version(&self) -> Version {
+        Version::parse(env!("CARGO_PKG_VERSION")).unwrap()
+    }
+
+    fn before_shutdown(&self, _kind: Shutdown) -> Result<(), FrameworkError> {
+        shutdown_traces();
+        Ok(())
+    }
+}
 
 /// A custom component for parametrizing `tracing` in the relayer.
 /// Primarily used for:
@@ -54,28 +86,133 @@ impl JsonTracing {
 /// - Customizing the log output level, for filtering the output produced via tracing macros
 ///   (`debug!`, `info!`, etc.) or abscissa macros (`status_err`, `status_info`, etc.).
 /// - Enabling pretty output with coloring
-pub struct PrettyTracing;
+/// - Exporting the relaying pipeline as OpenTelemetry traces, if enabled
+#[derive(Debug)]
+pub struct PrettyTracing {
+    /// The runtime on which the traces are exported, if enabled
+    #[allow(dead_code)]
+    traces_runtime: Option<Runtime>,
+}
 
 impl PrettyTracing {
     /// Creates a new [`PrettyTracing`] component
-    pub fn new(cfg: GlobalConfig, debug_sections: &[DebugSection]) -> Result<Self, FrameworkError> {
+    pub fn new(
+        cfg: GlobalConfig,
+        traces: TracesConfig,
+        debug_sections: &[DebugSection],
+    ) -> Result<Self, FrameworkError> {
         let filter = build_tracing_filter(cfg.log_level, debug_sections)?;
 
-        // Construct a tracing subscriber with the supplied filter and enable reloading.
-        let builder = FmtSubscriber::builder()
+        let fmt_layer = fmt::layer()
             .with_target(false)
-            .with_env_filter(filter)
             .with_writer(std::io::stderr)
             .with_ansi(enable_ansi())
-            .with_thread_ids(true);
+            .with_thread_ids(true)
+            .with_filter(filter);
+
+        let (traces_layer, traces_runtime) = build_traces_layer(&traces)?;
 
-        let subscriber = builder.finish();
-        subscriber.init();
+        tracing_subscriber::registry()
+            .with(fmt_layer)
+            .with(traces_layer)
+            .init();
 
-        Ok(Self)
+        Ok(Self { traces_runtime })
     }
 }
 
+impl<A: Application> Component<A> for PrettyTracing {
+    fn id(&self) -> Id {
+        Id::new(concat!(module_path!(), "::", "PrettyTracing"))
+    }
+
+    fn version(&self) -> Version {
+        Version::parse(env!("CARGO_PKG_VERSION")).unwrap()
+    }
+
+    fn before_shutdown(&self, _kind: Shutdown) -> Result<(), FrameworkError> {
+        shutdown_traces();
+        Ok(())
+    }
+}
+
+/// A layer exporting the spans of the relaying pipeline as OpenTelemetry traces.
+type TracesLayer<S> = Box<dyn Layer<S> + Send + Sync>;
+
+/// Builds the layer exporting the spans of the relaying pipeline to the OTLP collector
+/// configured in the `[traces]` section, along with the runtime on which they are exported.
+/// Returns no layer if the export of traces is disabled.
+#[cfg(feature = "telemetry")]
+fn build_traces_layer<S>(
+    cfg: &TracesConfig,
+) -> Result<(Option<TracesLayer<S>>, Option<Runtime>), FrameworkError>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+{
+    use ibc_relayer::chain::tracking::RELAY_SPAN_TARGET;
+    use tracing::Level;
+    use tracing_subscriber::filter::Targets;
+
+    if !cfg.enabled {
+        return Ok((None, None));
+    }
+
+    let traces_error = |reason: String| -> FrameworkError {
+        FrameworkErrorKind::ConfigError
+            .context(Error::traces_exporter(cfg.endpoint.clone(), reason))
+            .into()
+    };
+
+    // The spans are exported in batches by a task which lives on a dedicated runtime,
+    // as the commands run their own runtimes, if any, only once tracing is set up.
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("hermes-traces")
+        .enable_all()
+        .build()
+        .map_err(|e| traces_error(e.to_string()))?;
+
+    let layer = {
+        let _guard = runtime.enter();
+        ibc_telemetry::traces::layer(&cfg.endpoint, &cfg.service_name)
+            .map_err(|e| traces_error(e.to_string()))?
+    };
+
+    // Only the spans of the relaying pipeline are exported, regardless of the log level
+    let layer = layer.with_filter(Targets::new().with_target(RELAY_SPAN_TARGET, Level::INFO));
+
+    Ok((Some(Box::new(layer)), Some(runtime)))
+}
+
+#[cfg(not(feature = "telemetry"))]
+fn build_traces_layer<S>(
+    cfg: &TracesConfig,
+) -> Result<(Option<TracesLayer<S>>, Option<Runtime>), FrameworkError>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+{
+    if cfg.enabled {
+        return Err(FrameworkErrorKind::ConfigError
+            .context(Error::traces_exporter(
+                cfg.endpoint.clone(),
+                "Hermes was built without the `telemetry` feature".to_string(),
+            ))
+            .into());
+    }
+
+    Ok((None, None))
+}
+
+/// Exports the spans of the relaying pipeline which are not exported yet, if enabled,
+/// before Hermes exits.
+#[cfg(feature = "telemetry")]
+pub fn shutdown_traces() {
+    ibc_telemetry::traces::shutdown();
+}
+
+#[cfg(not(feature = "telemetry"))]
+pub fn shutdown_traces() {}
+
 /// Check if both stdout and stderr are proper terminal (tty),
 /// so that we know whether or not to enable colored output,
 /// using ANSI escape codes. If either is not, eg. because
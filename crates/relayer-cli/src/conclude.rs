@@ -80,6 +80,9 @@ pub fn exit_with(out: Output) -> ! {
         println!("{} {}", status, out.result);
     }
 
+    // Commands exiting this way skip the shutdown of the components
+    crate::components::shutdown_traces();
+
     // The return code
     if status == Status::Error {
         std::process::exit(1);
@@ -8,6 +8,7 @@ use alloc::collections::BTreeSet;
 use std::path::PathBuf;
 
 use flex_error::{define_error, TraceError};
+use ibc_relayer::chain::cosmos::authz::{self, Error as AuthzError};
 use ibc_relayer::config::{ChainConfig, Config, ModeConfig};
 use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 use tendermint_light_client_verifier::types::TrustThreshold;
@@ -73,6 +74,21 @@ define_error! {
                     e.chain_id, e.gas_adjustment, e.gas_multiplier
                 )
             },
+
+        InvalidAuthzGranter
+            { chain_id: ChainId }
+            [ AuthzError ]
+            |e| {
+                format!("config file specifies an invalid `authz_granter` for the chain '{0}'",
+                    e.chain_id)
+            },
+
+        TracesExporter
+            { endpoint: String, reason: String }
+            |e| {
+                format!("unable to set up the export of traces to the OTLP collector at '{0}', caused by: {1}",
+                    e.endpoint, e.reason)
+            },
     }
 }
 
@@ -96,6 +112,8 @@ pub fn validate_config(config: &Config) -> Result<(), Diagnostic<Error>> {
 
         // Validate gas-related settings
         validate_gas_settings(&c.id, c)?;
+
+        validate_authz_granter(&c.id, c)?;
     }
 
     // Check for invalid mode config
@@ -156,6 +174,17 @@ fn validate_trust_threshold(
     Ok(())
 }
 
+/// Check that the `authz_granter`, if any, is an address on the chain,
+/// on whose behalf the relayer would otherwise fail to execute messages.
+fn validate_authz_granter(id: &ChainId, config: &ChainConfig) -> Result<(), Diagnostic<Error>> {
+    if let Some(granter) = &config.authz_granter {
+        authz::parse_granter(granter, &config.account_prefix)
+            .map_err(|e| Diagnostic::Error(Error::invalid_authz_granter(id.clone(), e)))?;
+    }
+
+    Ok(())
+}
+
 fn validate_gas_settings(id: &ChainId, config: &ChainConfig) -> Result<(), Diagnostic<Error>> {
     // Check that the gas_adjustment option is not set
     if let Some(gas_adjustment) = config.gas_adjustment {
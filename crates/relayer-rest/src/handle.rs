@@ -1,18 +1,26 @@
+use alloc::sync::Arc;
 use core::fmt::Debug;
 
 use tracing::error;
 
 use crossbeam_channel as channel;
 
-use ibc_relayer::supervisor::dump_state::SupervisorState;
+use ibc_relayer::supervisor::{
+    control::{ClientUpdate, WorkerAction},
+    dump_state::{SupervisorState, WorkerDesc},
+    paths::PathStatus,
+    reload::ConfigDiff,
+};
+use ibc_relayer::worker::WorkerId;
 use ibc_relayer::{
     config::ChainConfig,
+    event::monitor::RelayerEvent,
     rest::{
         request::{reply_channel, ReplySender, Request, VersionInfo},
         RestApiError,
     },
 };
-use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
 
 pub const NAME: &str = env!(
     "CARGO_PKG_NAME",
@@ -64,6 +72,68 @@ pub fn supervisor_state(
     submit_request(sender, |reply_to| Request::State { reply_to })
 }
 
+pub fn reload_config(sender: &channel::Sender<Request>) -> Result<ConfigDiff, RestApiError> {
+    submit_request(sender, |reply_to| Request::ReloadConfig { reply_to })
+}
+
+pub fn all_paths(sender: &channel::Sender<Request>) -> Result<Vec<PathStatus>, RestApiError> {
+    submit_request(sender, |reply_to| Request::GetPaths { reply_to })
+}
+
+pub fn path_status(
+    sender: &channel::Sender<Request>,
+    chain_id: ChainId,
+    port_id: PortId,
+    channel_id: ChannelId,
+) -> Result<PathStatus, RestApiError> {
+    submit_request(sender, |reply_to| Request::GetPath {
+        chain_id,
+        port_id,
+        channel_id,
+        reply_to,
+    })
+}
+
+pub fn clear_packets(
+    sender: &channel::Sender<Request>,
+    chain_id: ChainId,
+    port_id: PortId,
+    channel_id: ChannelId,
+) -> Result<Vec<WorkerId>, RestApiError> {
+    submit_request(sender, |reply_to| Request::ClearPackets {
+        chain_id,
+        port_id,
+        channel_id,
+        reply_to,
+    })
+}
+
+pub fn update_client(
+    sender: &channel::Sender<Request>,
+    chain_id: ChainId,
+    client_id: ClientId,
+    force: bool,
+) -> Result<ClientUpdate, RestApiError> {
+    submit_request(sender, |reply_to| Request::UpdateClient {
+        chain_id,
+        client_id,
+        force,
+        reply_to,
+    })
+}
+
+pub fn control_worker(
+    sender: &channel::Sender<Request>,
+    id: WorkerId,
+    action: WorkerAction,
+) -> Result<WorkerDesc, RestApiError> {
+    submit_request(sender, |reply_to| Request::ControlWorker {
+        id,
+        action,
+        reply_to,
+    })
+}
+
 pub fn assemble_version_info(sender: &channel::Sender<Request>) -> Vec<VersionInfo> {
     // Fetch the relayer library version
     let lib_version = submit_request(sender, |reply_to| Request::Version { reply_to })
@@ -85,3 +155,9 @@ pub fn assemble_version_info(sender: &channel::Sender<Request>) -> Vec<VersionIn
 
     vec![lib_version, rest_api_version]
 }
+
+pub fn subscribe_events(
+    sender: &channel::Sender<Request>,
+) -> Result<channel::Receiver<Arc<RelayerEvent>>, RestApiError> {
+    submit_request(sender, |reply_to| Request::SubscribeEvents { reply_to })
+}
@@ -1,3 +1,5 @@
+extern crate alloc;
+
 mod handle;
 mod server;
 pub use server::spawn;
@@ -1,28 +1,60 @@
+use core::time::Duration;
 use std::{
     error::Error,
     net::{SocketAddr, ToSocketAddrs},
+    thread,
 };
 
-use axum::{extract::Path, response::IntoResponse, routing::get, Extension, Json, Router, Server};
-use crossbeam_channel as channel;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::AUTHORIZATION, Request as HttpRequest, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Extension, Json, Router, Server,
+};
+use crossbeam_channel::{self as channel, RecvTimeoutError};
+use futures::stream;
 use serde::{Deserialize, Serialize};
-use tokio::task::JoinHandle;
+use tokio::{
+    sync::mpsc::{self, error::TrySendError},
+    task::JoinHandle,
+};
+use tracing::warn;
 
 use ibc_relayer::{
     rest::{request::Request, RestApiError},
-    supervisor::dump_state::SupervisorState,
+    supervisor::{
+        control::{ClientUpdate, WorkerAction},
+        dump_state::{SupervisorState, WorkerDesc},
+        paths::PathStatus,
+        reload::ConfigDiff,
+    },
+    worker::WorkerId,
 };
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
 
-use crate::handle::{all_chain_ids, assemble_version_info, chain_config, supervisor_state};
+use crate::handle::{
+    all_chain_ids, all_paths, assemble_version_info, chain_config, clear_packets, control_worker,
+    path_status, reload_config, subscribe_events, supervisor_state, update_client,
+};
 
 pub type BoxError = Box<dyn Error + Send + Sync>;
 
+/// Spawn the REST server.
+///
+/// The `POST` endpoints, which act on the relayer, require the given token
+/// as a bearer token, and are disabled if there is none.
 pub fn spawn(
     addr: impl ToSocketAddrs,
     sender: channel::Sender<Request>,
+    token: Option<String>,
 ) -> Result<JoinHandle<()>, BoxError> {
     let addr = addr.to_socket_addrs()?.next().unwrap();
-    let handle = tokio::spawn(run(addr, sender));
+    let handle = tokio::spawn(run(addr, sender, token));
     Ok(handle)
 }
 
@@ -68,14 +100,178 @@ async fn get_state(
     Json(JsonResult::from(state))
 }
 
+async fn get_paths(
+    Extension(sender): Extension<Sender>,
+) -> Json<JsonResult<Vec<PathStatus>, RestApiError>> {
+    let paths = all_paths(&sender);
+    Json(JsonResult::from(paths))
+}
+
+async fn get_path(
+    Path((chain_id, port_id, channel_id)): Path<(ChainId, PortId, ChannelId)>,
+    Extension(sender): Extension<Sender>,
+) -> Json<JsonResult<PathStatus, RestApiError>> {
+    let path = path_status(&sender, chain_id, port_id, channel_id);
+    Json(JsonResult::from(path))
+}
+
+#[derive(Debug, Deserialize)]
+struct EventsQuery {
+    chain: Option<ChainId>,
+    channel: Option<ChannelId>,
+}
+
+/// How often the forwarding of the events to a client of the event stream
+/// checks whether that client is still connected, when there are no events.
+const EVENTS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum number of events waiting to be sent to a client of the event stream,
+/// beyond which the client is disconnected, as it does not keep up with the events.
+const EVENTS_BUFFER_SIZE: usize = 1024;
+
+/// Stream the activity of the relayer as server-sent events, optionally
+/// restricted to the events relating to the given chain and channel.
+async fn get_events(
+    Query(query): Query<EventsQuery>,
+    Extension(sender): Extension<Sender>,
+) -> Response {
+    let events = match subscribe_events(&sender) {
+        Ok(events) => events,
+        Err(e) => return Json(JsonResult::<(), _>::Error(e)).into_response(),
+    };
+
+    let (tx, rx) = mpsc::channel(EVENTS_BUFFER_SIZE);
+
+    // Forward the events received over the blocking channel of the subscription
+    // to the stream, until the client disconnects, falls behind, or the subscription ends
+    thread::spawn(move || loop {
+        match events.recv_timeout(EVENTS_POLL_INTERVAL) {
+            Ok(event) if event.matches(query.chain.as_ref(), query.channel.as_ref()) => {
+                match tx.try_send(event) {
+                    Ok(()) => continue,
+                    Err(TrySendError::Full(_)) => {
+                        warn!(
+                            "disconnecting client of the event stream, which is more than {} events behind",
+                            EVENTS_BUFFER_SIZE
+                        );
+                        break;
+                    }
+                    Err(TrySendError::Closed(_)) => break,
+                }
+            }
+            Ok(_) => continue,
+            Err(RecvTimeoutError::Timeout) if !tx.is_closed() => continue,
+            Err(_) => break,
+        }
+    });
+
+    let stream = stream::unfold(rx, |mut rx| async move {
+        let event = rx.recv().await?;
+        Some((Event::default().json_data(&*event), rx))
+    });
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+async fn post_reload(
+    Extension(sender): Extension<Sender>,
+) -> Json<JsonResult<ConfigDiff, RestApiError>> {
+    let diff = reload_config(&sender);
+    Json(JsonResult::from(diff))
+}
+
+#[derive(Debug, Deserialize)]
+struct ClearPacketsBody {
+    chain: ChainId,
+    port: PortId,
+    channel: ChannelId,
+}
+
+async fn post_clear_packets(
+    Extension(sender): Extension<Sender>,
+    Json(body): Json<ClearPacketsBody>,
+) -> Json<JsonResult<Vec<WorkerId>, RestApiError>> {
+    let workers = clear_packets(&sender, body.chain, body.port, body.channel);
+    Json(JsonResult::from(workers))
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateClientBody {
+    chain: ChainId,
+    client: ClientId,
+    #[serde(default)]
+    force: bool,
+}
+
+async fn post_update_client(
+    Extension(sender): Extension<Sender>,
+    Json(body): Json<UpdateClientBody>,
+) -> Json<JsonResult<ClientUpdate, RestApiError>> {
+    let update = update_client(&sender, body.chain, body.client, body.force);
+    Json(JsonResult::from(update))
+}
+
+async fn post_worker(
+    Path((id, action)): Path<(WorkerId, WorkerAction)>,
+    Extension(sender): Extension<Sender>,
+) -> Json<JsonResult<WorkerDesc, RestApiError>> {
+    let worker = control_worker(&sender, id, action);
+    Json(JsonResult::from(worker))
+}
+
 type Sender = channel::Sender<Request>;
 
-async fn run(addr: SocketAddr, sender: Sender) {
+type Token = Option<String>;
+
+/// Reject the requests which do not carry the expected bearer token.
+async fn authorize<B>(
+    State(token): State<Token>,
+    request: HttpRequest<B>,
+    next: Next<B>,
+) -> Response {
+    let bearer = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match (token.as_deref(), bearer) {
+        (Some(token), Some(bearer)) if constant_time_eq(token, bearer) => next.run(request).await,
+        _ => {
+            let result: JsonResult<(), _> = JsonResult::Error(RestApiError::Unauthorized);
+            (StatusCode::UNAUTHORIZED, Json(result)).into_response()
+        }
+    }
+}
+
+/// Compare two strings in a time which does not depend on where they differ.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+async fn run(addr: SocketAddr, sender: Sender, token: Token) {
+    let operations = Router::new()
+        .route("/reload", post(post_reload))
+        .route("/clear-packets", post(post_clear_packets))
+        .route("/update-client", post(post_update_client))
+        .route("/worker/:id/:action", post(post_worker))
+        .route_layer(middleware::from_fn_with_state(token, authorize));
+
     let app = Router::new()
         .route("/version", get(get_version))
         .route("/chains", get(get_chains))
         .route("/chain/:id", get(get_chain))
         .route("/state", get(get_state))
+        .route("/paths", get(get_paths))
+        .route("/path/:chain/:port/:channel", get(get_path))
+        .route("/events", get(get_events))
+        .merge(operations)
         .layer(Extension(sender));
 
     Server::bind(&addr)
@@ -1,13 +1,25 @@
-use std::{fmt::Debug, str::FromStr, time::Duration};
+use std::{fmt::Debug, str::FromStr, sync::Arc, time::Duration};
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use ibc_relayer::{
     config::ChainConfig,
+    event::monitor::{RelayerEvent, RelayerEventKind},
+    object::{Object, Wallet},
     rest::request::{Request, VersionInfo},
-    supervisor::dump_state::SupervisorState,
+    supervisor::{
+        control::{ClientUpdate, WorkerAction},
+        dump_state::{SupervisorState, WorkerDesc},
+        paths::{Backlog, ChannelEndStatus, ClientStatus, PathStatus},
+        reload::ConfigDiff,
+    },
+    worker::WorkerId,
 };
-use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use ibc_relayer_types::core::{
+    ics04_channel::{channel::State as ChannelState, packet::Sequence},
+    ics24_host::identifier::{ChainId, ChannelId},
+};
+use ibc_relayer_types::Height;
 
 use ibc_relayer_rest::spawn;
 
@@ -24,14 +36,43 @@ enum JsonResult<R, E> {
     Error(E),
 }
 
+const TOKEN: &str = "mock-token";
+
 async fn run_test<R, F>(port: u16, path: &str, expected: R, handler: F)
 where
     R: Serialize + DeserializeOwned + Debug + PartialEq,
     F: FnOnce(Request) -> TestResult + Send + 'static,
+{
+    run_request_test(reqwest::Method::GET, port, path, None, expected, handler).await
+}
+
+async fn run_post_test<R, F>(
+    port: u16,
+    path: &str,
+    body: Option<serde_json::Value>,
+    expected: R,
+    handler: F,
+) where
+    R: Serialize + DeserializeOwned + Debug + PartialEq,
+    F: FnOnce(Request) -> TestResult + Send + 'static,
+{
+    run_request_test(reqwest::Method::POST, port, path, body, expected, handler).await
+}
+
+async fn run_request_test<R, F>(
+    method: reqwest::Method,
+    port: u16,
+    path: &str,
+    body: Option<serde_json::Value>,
+    expected: R,
+    handler: F,
+) where
+    R: Serialize + DeserializeOwned + Debug + PartialEq,
+    F: FnOnce(Request) -> TestResult + Send + 'static,
 {
     let (tx, rx) = crossbeam_channel::unbounded();
 
-    let handle = spawn(("127.0.0.1", port), tx).unwrap();
+    let handle = spawn(("127.0.0.1", port), tx, Some(TOKEN.to_string())).unwrap();
 
     std::thread::spawn(move || match rx.recv() {
         Ok(r) => match handler(r) {
@@ -43,12 +84,15 @@ where
 
     tokio::time::sleep(Duration::from_millis(500)).await;
 
-    let response = reqwest::get(&format!("http://127.0.0.1:{port}{path}"))
-        .await
-        .unwrap()
-        .json::<R>()
-        .await
-        .unwrap();
+    let mut request = reqwest::Client::new()
+        .request(method, format!("http://127.0.0.1:{port}{path}"))
+        .bearer_auth(TOKEN);
+
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+
+    let response = request.send().await.unwrap().json::<R>().await.unwrap();
 
     assert_eq!(response, expected);
 
@@ -142,3 +186,335 @@ async fn state() {
     })
     .await;
 }
+
+#[tokio::test]
+async fn reload() {
+    let diff = ConfigDiff {
+        added_chains: vec!["mock-1".parse().unwrap()],
+        ..ConfigDiff::default()
+    };
+    let result: JsonResult<_, ()> = JsonResult::Success(diff.clone());
+
+    run_post_test(19105, "/reload", None, result, |req| match req {
+        Request::ReloadConfig { reply_to } => {
+            reply_to.send(Ok(diff)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn clear_packets() {
+    let workers = vec![WorkerId::new(3)];
+    let result: JsonResult<_, ()> = JsonResult::Success(workers.clone());
+
+    let body = serde_json::json!({
+        "chain": "mock-0",
+        "port": "transfer",
+        "channel": "channel-0",
+    });
+
+    run_post_test(
+        19106,
+        "/clear-packets",
+        Some(body),
+        result,
+        |req| match req {
+            Request::ClearPackets {
+                chain_id,
+                port_id,
+                channel_id,
+                reply_to,
+            } if chain_id.as_str() == "mock-0"
+                && port_id.as_str() == "transfer"
+                && channel_id.as_str() == "channel-0" =>
+            {
+                reply_to.send(Ok(workers)).unwrap();
+                TestResult::Success
+            }
+            req => TestResult::WrongRequest(req),
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn update_client() {
+    let update = ClientUpdate {
+        chain_id: "mock-0".parse().unwrap(),
+        client_id: "07-tendermint-0".parse().unwrap(),
+        src_chain_id: "mock-1".parse().unwrap(),
+        updated: true,
+    };
+    let result: JsonResult<_, ()> = JsonResult::Success(update.clone());
+
+    let body = serde_json::json!({
+        "chain": "mock-0",
+        "client": "07-tendermint-0",
+        "force": true,
+    });
+
+    run_post_test(
+        19107,
+        "/update-client",
+        Some(body),
+        result,
+        |req| match req {
+            Request::UpdateClient {
+                client_id,
+                force: true,
+                reply_to,
+                ..
+            } if client_id.as_str() == "07-tendermint-0" => {
+                reply_to.send(Ok(update)).unwrap();
+                TestResult::Success
+            }
+            req => TestResult::WrongRequest(req),
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn pause_worker() {
+    let object = Object::Wallet(Wallet {
+        chain_id: "mock-0".parse().unwrap(),
+    });
+    let desc = WorkerDesc::new(WorkerId::new(1), object, None, true);
+    let result: JsonResult<_, ()> = JsonResult::Success(desc.clone());
+
+    run_post_test(19108, "/worker/1/pause", None, result, |req| match req {
+        Request::ControlWorker {
+            id,
+            action: WorkerAction::Pause,
+            reply_to,
+        } if id == WorkerId::new(1) => {
+            reply_to.send(Ok(desc)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    })
+    .await;
+}
+
+fn mock_path_status() -> PathStatus {
+    let channel_end = |chain_id: &str| ChannelEndStatus {
+        chain_id: chain_id.parse().unwrap(),
+        port_id: "transfer".parse().unwrap(),
+        channel_id: "channel-0".parse().unwrap(),
+        state: ChannelState::Open,
+        connection_id: "connection-0".parse().unwrap(),
+        client: ClientStatus {
+            client_id: "07-tendermint-0".parse().unwrap(),
+            latest_height: Height::new(1, 10).unwrap(),
+            trusting_period_expiry: None,
+            frozen: false,
+        },
+    };
+
+    PathStatus {
+        channel: channel_end("mock-0"),
+        counterparty: channel_end("mock-1"),
+        unreceived_packets: Backlog {
+            count: 2,
+            oldest_sequence: Some(Sequence::from(5)),
+            oldest_age_secs: Some(42),
+        },
+        unreceived_acks: Backlog::default(),
+        last_relay: None,
+        last_error: None,
+    }
+}
+
+#[tokio::test]
+async fn paths() {
+    let paths = vec![mock_path_status()];
+    let result: JsonResult<_, ()> = JsonResult::Success(paths.clone());
+
+    run_test(19110, "/paths", result, |req| match req {
+        Request::GetPaths { reply_to } => {
+            reply_to.send(Ok(paths)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn path() {
+    let path = mock_path_status();
+    let result: JsonResult<_, ()> = JsonResult::Success(path.clone());
+
+    run_test(
+        19111,
+        "/path/mock-0/transfer/channel-0",
+        result,
+        |req| match req {
+            Request::GetPath {
+                chain_id,
+                port_id,
+                channel_id,
+                reply_to,
+            } if chain_id.as_str() == "mock-0"
+                && port_id.as_str() == "transfer"
+                && channel_id.as_str() == "channel-0" =>
+            {
+                reply_to.send(Ok(path)).unwrap();
+                TestResult::Success
+            }
+            req => TestResult::WrongRequest(req),
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    let handle = spawn(("127.0.0.1", 19109), tx, Some(TOKEN.to_string())).unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+
+    for token in [None, Some("wrong-token")] {
+        let mut request = client.post("http://127.0.0.1:19109/reload");
+
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let result = response
+            .json::<JsonResult<(), serde_json::Value>>()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            JsonResult::Error(serde_json::json!({
+                "name": "Unauthorized",
+                "msg": "missing or invalid bearer token",
+            }))
+        );
+    }
+
+    // The request never reached the relayer
+    assert!(rx.try_recv().is_err());
+
+    drop(handle);
+}
+
+#[tokio::test]
+async fn events() {
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    let handle = spawn(("127.0.0.1", 19112), tx, Some(TOKEN.to_string())).unwrap();
+
+    std::thread::spawn(move || match rx.recv() {
+        Ok(Request::SubscribeEvents { reply_to }) => {
+            let (events_tx, events_rx) = crossbeam_channel::unbounded();
+            reply_to.send(Ok(events_rx)).unwrap();
+
+            for (chain_id, channel_id, message) in [
+                ("mock-0", 0, "matching"),
+                ("mock-1", 0, "other chain"),
+                ("mock-0", 1, "other channel"),
+            ] {
+                let event = RelayerEvent::new(
+                    chain_id.parse().unwrap(),
+                    Some(ChannelId::new(channel_id)),
+                    RelayerEventKind::Error {
+                        message: message.to_string(),
+                    },
+                );
+
+                events_tx.send(Arc::new(event)).unwrap();
+            }
+
+            // Dropping the sender ends the stream
+        }
+        Ok(req) => panic!("got the wrong request: {req:?}"),
+        Err(e) => panic!("got an error: {e}"),
+    });
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let response = reqwest::get("http://127.0.0.1:19112/events?chain=mock-0&channel=channel-0")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    let events = response
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| serde_json::from_str::<serde_json::Value>(data).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["chain_id"], "mock-0");
+    assert_eq!(events[0]["channel_id"], "channel-0");
+    assert_eq!(events[0]["type"], "error");
+    assert_eq!(events[0]["message"], "matching");
+
+    drop(handle);
+}
+
+#[tokio::test]
+async fn events_slow_client_is_disconnected() {
+    const TOTAL: usize = 100_000;
+
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    let handle = spawn(("127.0.0.1", 19113), tx, Some(TOKEN.to_string())).unwrap();
+
+    std::thread::spawn(move || match rx.recv() {
+        Ok(Request::SubscribeEvents { reply_to }) => {
+            let (events_tx, events_rx) = crossbeam_channel::unbounded();
+            reply_to.send(Ok(events_rx)).unwrap();
+
+            for _ in 0..TOTAL {
+                let event = RelayerEvent::new(
+                    "mock-0".parse().unwrap(),
+                    Some(ChannelId::new(0)),
+                    RelayerEventKind::Error {
+                        message: "failed".to_string(),
+                    },
+                );
+
+                if events_tx.send(Arc::new(event)).is_err() {
+                    break;
+                }
+            }
+        }
+        Ok(req) => panic!("got the wrong request: {req:?}"),
+        Err(e) => panic!("got an error: {e}"),
+    });
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let response = reqwest::get("http://127.0.0.1:19113/events").await.unwrap();
+
+    // Do not read the events while they are published
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let response = response.text().await.unwrap();
+
+    let received = response
+        .lines()
+        .filter(|line| line.starts_with("data:"))
+        .count();
+
+    // The stream ended before all the events were received
+    assert!(received < TOTAL);
+
+    drop(handle);
+}
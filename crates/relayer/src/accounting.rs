@@ -0,0 +1,892 @@
+//! Accounting of the fees paid by the relayer for the transactions it submits,
+//! and of the ICS29 fees rewarded to it by these transactions, so that the
+//! profitability of relaying on each channel can be assessed.
+//!
+//! The fees of every transaction which the relayer submits are accounted for once the
+//! transaction passes `CheckTx`, and its rewards once the transaction is confirmed on
+//! the chain, which requires `tx_confirmation` to be enabled for the packet transactions.
+//! Both are exported as telemetry counters and, if enabled in the `[accounting]` section,
+//! recorded to an append-only ledger, which is summarized by `hermes report costs`.
+//!
+//! The fee of a transaction is split evenly between its messages, and the messages
+//! which do not relate to a channel, such as client updates, are attributed to the
+//! channel of the other messages of the transaction, if they all relate to the same one.
+//! The rewards are the ICS29 fees which the transaction distributes to the wallet
+//! which signed it, hence the fees distributed to a distinct payee are not accounted for.
+//! The messages which the relayer executes on behalf of an authz granter are unwrapped
+//! from their `MsgExec`, and accounted for as if the relayer had submitted them directly.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use alloc::collections::btree_map::BTreeMap;
+use flex_error::define_error;
+use ibc_proto::cosmos::tx::v1beta1::{AuthInfo, TxBody, TxRaw};
+use ibc_proto::google::protobuf::Any;
+use ibc_proto::ibc::core::channel::v1 as raw_channel;
+use ibc_proto::ibc::core::client::v1 as raw_client;
+use once_cell::sync::Lazy;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use tendermint::abci::Event as AbciEvent;
+use tendermint::Hash as TxHash;
+use tendermint_rpc::endpoint::tx::Response as TxResponse;
+use tracing::{debug, warn};
+
+use ibc_relayer_types::applications::ics29_fee::events::DistributionType;
+use ibc_relayer_types::core::ics02_client::msgs::update_client;
+use ibc_relayer_types::core::ics04_channel::msgs::{
+    acknowledgement, chan_close_confirm, chan_close_init, chan_open_ack, chan_open_confirm,
+    recv_packet, timeout, timeout_on_close,
+};
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId};
+use ibc_relayer_types::events::IbcEvent;
+
+use crate::chain::cosmos::authz::{MsgExec, MSG_EXEC_TYPE_URL};
+use crate::config::AccountingConfig;
+use crate::event::ibc_event_try_from_abci_event;
+use crate::util::append_log::{self, now, AppendLog, Error as AppendLogError, GlobalSink};
+
+/// The default location of the ledger, relative to the home directory.
+pub const LEDGER_DEFAULT_PATH: &str = ".hermes/accounting.jsonl";
+
+/// How long the hash of a transaction which was accounted for is remembered,
+/// so that a transaction which is queried several times is accounted for once.
+const ACCOUNTED_TX_LIFETIME: Duration = Duration::from_secs(3600);
+
+define_error! {
+    Error {
+        Ledger
+            [ AppendLogError ]
+            |_| { "error on accounting ledger" },
+
+        HomeLocationUnavailable
+            |_| { "home location is unavailable" },
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// A fee paid for a message
+    Cost,
+    /// An ICS29 fee rewarded to the wallet
+    Reward,
+}
+
+/// An amount paid or rewarded for a transaction submitted to a chain, as recorded in the ledger.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Seconds since the Unix epoch at which the transaction was accounted for
+    pub time: u64,
+    pub kind: EntryKind,
+    pub chain_id: ChainId,
+    /// The wallet which paid the fee or was rewarded
+    pub wallet: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<ChannelId>,
+    /// The type of the message the fee was paid for, for costs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub msg_type: Option<String>,
+    pub denom: String,
+    pub amount: u128,
+    pub tx_hash: String,
+}
+
+/// The entries accounting for the given transaction, confirmed on the given chain,
+/// if the transaction was signed by the given wallet.
+pub fn entries_from_tx(
+    chain_id: &ChainId,
+    wallet: &str,
+    response: &TxResponse,
+    time: u64,
+) -> Vec<LedgerEntry> {
+    let events = response
+        .tx_result
+        .code
+        .is_ok()
+        .then_some(response.tx_result.events.as_slice());
+
+    tx_entries(chain_id, wallet, &response.tx, &response.hash, events, time)
+}
+
+/// The entries accounting for the given encoded transaction, if it was signed by the
+/// given wallet: the costs of its messages and, given the events of the transaction
+/// once it succeeded, the rewards it distributed to the wallet.
+fn tx_entries(
+    chain_id: &ChainId,
+    wallet: &str,
+    tx: &[u8],
+    tx_hash: &TxHash,
+    events: Option<&[AbciEvent]>,
+    time: u64,
+) -> Vec<LedgerEntry> {
+    let Some((body, auth_info)) = decode_tx(tx) else {
+        return Vec::new();
+    };
+
+    let messages = body
+        .messages
+        .iter()
+        .flat_map(unwrap_msg_exec)
+        .collect::<Vec<_>>();
+
+    // Only account for the transactions submitted by the relayer
+    if !messages
+        .iter()
+        .any(|(_, _, signers)| signers.iter().any(|signer| signer == wallet))
+    {
+        return Vec::new();
+    }
+
+    let mut channels = messages
+        .iter()
+        .filter_map(|(_, channel_id, _)| channel_id.as_ref());
+
+    let tx_channel = match channels.next() {
+        Some(first) if channels.all(|channel_id| channel_id == first) => Some(first.clone()),
+        _ => None,
+    };
+
+    let entry = |kind, wallet: &str, channel_id, msg_type, denom: &str, amount| LedgerEntry {
+        time,
+        kind,
+        chain_id: chain_id.clone(),
+        wallet: wallet.to_string(),
+        channel_id,
+        msg_type,
+        denom: denom.to_string(),
+        amount,
+        tx_hash: tx_hash.to_string(),
+    };
+
+    let mut entries = Vec::new();
+
+    if let Some(fee) = auth_info.fee {
+        // The fee is paid by the granter of the fee allowance, if any
+        let payer = if fee.granter.is_empty() {
+            wallet
+        } else {
+            fee.granter.as_str()
+        };
+
+        let count = messages.len() as u128;
+
+        for coin in &fee.amount {
+            let Ok(amount) = coin.amount.parse::<u128>() else {
+                continue;
+            };
+
+            for (index, (msg_type, channel_id, _)) in messages.iter().enumerate() {
+                // The remainder of the even split is attributed to the first message
+                let share = if index == 0 {
+                    amount / count + amount % count
+                } else {
+                    amount / count
+                };
+
+                entries.push(entry(
+                    EntryKind::Cost,
+                    payer,
+                    channel_id.clone().or_else(|| tx_channel.clone()),
+                    Some(msg_type.clone()),
+                    &coin.denom,
+                    share,
+                ));
+            }
+        }
+    }
+
+    if let Some(events) = events {
+        for abci_event in events {
+            if let Ok(IbcEvent::DistributeFeePacket(dist)) =
+                ibc_event_try_from_abci_event(abci_event)
+            {
+                if dist.distribution_type == DistributionType::Reward
+                    && dist.receiver.as_ref() == wallet
+                {
+                    entries.push(entry(
+                        EntryKind::Reward,
+                        wallet,
+                        tx_channel.clone(),
+                        None,
+                        &dist.fee.denom,
+                        u128::try_from(dist.fee.amount.0).unwrap_or(u128::MAX),
+                    ));
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+fn decode_tx(tx: &[u8]) -> Option<(TxBody, AuthInfo)> {
+    let tx_raw = TxRaw::decode(tx).ok()?;
+    let body = TxBody::decode(tx_raw.body_bytes.as_slice()).ok()?;
+    let auth_info = AuthInfo::decode(tx_raw.auth_info_bytes.as_slice()).ok()?;
+
+    Some((body, auth_info))
+}
+
+/// The type of the given message, without the Protobuf package, eg. `MsgRecvPacket`.
+fn msg_type(msg: &Any) -> String {
+    msg.type_url
+        .rsplit('.')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The type of the given message, the channel which it relates to, if any, and the signers
+/// on whose behalf it is submitted. A `MsgExec` is unwrapped into the messages it executes,
+/// which are submitted on behalf of both the grantee which signs the `MsgExec`
+/// and the granter which signs each of these messages.
+fn unwrap_msg_exec(msg: &Any) -> Vec<(String, Option<ChannelId>, Vec<String>)> {
+    if msg.type_url == MSG_EXEC_TYPE_URL {
+        if let Ok(msg_exec) = MsgExec::decode(msg.value.as_slice()) {
+            if msg_exec.msgs.is_empty() {
+                return vec![(msg_type(msg), None, vec![msg_exec.grantee])];
+            }
+
+            return msg_exec
+                .msgs
+                .iter()
+                .map(|inner| {
+                    let (channel_id, granter) = channel_and_signer(inner);
+                    let signers = Some(msg_exec.grantee.clone()).into_iter().chain(granter);
+
+                    (msg_type(inner), channel_id, signers.collect())
+                })
+                .collect();
+        }
+    }
+
+    let (channel_id, signer) = channel_and_signer(msg);
+
+    vec![(msg_type(msg), channel_id, signer.into_iter().collect())]
+}
+
+/// The channel of the chain which the given message relates to, if any, along with
+/// the signer of the message, for the messages submitted by the relayer.
+fn channel_and_signer(msg: &Any) -> (Option<ChannelId>, Option<String>) {
+    fn decode<M: Message + Default>(msg: &Any) -> Option<M> {
+        M::decode(msg.value.as_slice()).ok()
+    }
+
+    let decoded =
+        match msg.type_url.as_str() {
+            recv_packet::TYPE_URL => decode::<raw_channel::MsgRecvPacket>(msg)
+                .map(|m| (m.packet.map(|p| p.destination_channel), m.signer)),
+            acknowledgement::TYPE_URL => decode::<raw_channel::MsgAcknowledgement>(msg)
+                .map(|m| (m.packet.map(|p| p.source_channel), m.signer)),
+            timeout::TYPE_URL => decode::<raw_channel::MsgTimeout>(msg)
+                .map(|m| (m.packet.map(|p| p.source_channel), m.signer)),
+            timeout_on_close::TYPE_URL => decode::<raw_channel::MsgTimeoutOnClose>(msg)
+                .map(|m| (m.packet.map(|p| p.source_channel), m.signer)),
+            chan_open_ack::TYPE_URL => decode::<raw_channel::MsgChannelOpenAck>(msg)
+                .map(|m| (Some(m.channel_id), m.signer)),
+            chan_open_confirm::TYPE_URL => decode::<raw_channel::MsgChannelOpenConfirm>(msg)
+                .map(|m| (Some(m.channel_id), m.signer)),
+            chan_close_init::TYPE_URL => decode::<raw_channel::MsgChannelCloseInit>(msg)
+                .map(|m| (Some(m.channel_id), m.signer)),
+            chan_close_confirm::TYPE_URL => decode::<raw_channel::MsgChannelCloseConfirm>(msg)
+                .map(|m| (Some(m.channel_id), m.signer)),
+            update_client::TYPE_URL => {
+                decode::<raw_client::MsgUpdateClient>(msg).map(|m| (None, m.signer))
+            }
+            _ => None,
+        };
+
+    match decoded {
+        Some((channel_id, signer)) => (channel_id.and_then(|c| c.parse().ok()), Some(signer)),
+        None => (None, None),
+    }
+}
+
+/// Account for the fees of the given encoded transaction, which passed `CheckTx` on
+/// the given chain, if it was signed by the given wallet. The fees are paid once the
+/// transaction is included in a block, even if it then fails, hence they are accounted
+/// for on submission, whether or not the transaction is confirmed afterwards.
+pub fn account_for_submitted_tx(chain_id: &ChainId, wallet: &str, tx: &[u8], tx_hash: &TxHash) {
+    if ACCOUNTED_TXS.contains_key(tx_hash) {
+        return;
+    }
+
+    let entries = tx_entries(chain_id, wallet, tx, tx_hash, None, now());
+
+    if entries.is_empty() {
+        return;
+    }
+
+    ACCOUNTED_TXS.insert(
+        *tx_hash,
+        AccountedTx {
+            entries: entries.clone(),
+            confirmed: false,
+        },
+    );
+
+    record(tx_hash, &entries);
+}
+
+/// Account for the given transaction, confirmed on the given chain, if it was
+/// signed by the given wallet: export its fees as telemetry counters, and record
+/// them to the ledger if enabled. Only the rewards of a transaction whose fees
+/// were accounted for on submission are accounted for.
+pub fn account_for_tx(chain_id: &ChainId, wallet: &str, response: &TxResponse) {
+    let submitted = match ACCOUNTED_TXS.get(&response.hash) {
+        Some(accounted) if accounted.confirmed => return,
+        submitted => submitted,
+    };
+
+    let mut entries = entries_from_tx(chain_id, wallet, response, now());
+
+    if submitted.is_some() {
+        entries.retain(|entry| entry.kind == EntryKind::Reward);
+    }
+
+    let accounted = AccountedTx {
+        entries: submitted
+            .map(|submitted| submitted.entries)
+            .unwrap_or_default()
+            .into_iter()
+            .chain(entries.iter().cloned())
+            .collect(),
+        confirmed: true,
+    };
+
+    if accounted.entries.is_empty() {
+        return;
+    }
+
+    ACCOUNTED_TXS.insert(response.hash, accounted);
+
+    record(&response.hash, &entries);
+}
+
+/// Export the given entries of a transaction as telemetry counters,
+/// and record them to the ledger if enabled.
+fn record(tx_hash: &TxHash, entries: &[LedgerEntry]) {
+    if entries.is_empty() {
+        return;
+    }
+
+    for entry in entries {
+        let _amount = u64::try_from(entry.amount).unwrap_or(u64::MAX);
+
+        match entry.kind {
+            EntryKind::Cost => {
+                add_fee_paid(entry);
+
+                crate::telemetry!(
+                    tx_fee_paid,
+                    &entry.chain_id,
+                    &entry.wallet,
+                    entry.channel_id.as_ref(),
+                    entry.msg_type.as_deref().unwrap_or_default(),
+                    &entry.denom,
+                    _amount
+                );
+            }
+            EntryKind::Reward => {
+                crate::telemetry!(
+                    tx_fee_rewarded,
+                    &entry.chain_id,
+                    &entry.wallet,
+                    entry.channel_id.as_ref(),
+                    &entry.denom,
+                    _amount
+                );
+            }
+        }
+    }
+
+    if let Some(ledger) = LEDGER.get() {
+        if let Err(e) = ledger.append(entries) {
+            warn!("failed to record the fees of tx {}: {}", tx_hash, e);
+        }
+    }
+}
+
+/// The share of the fee of the given transaction paid for each of its messages, per
+/// denomination, if the transaction was accounted for recently.
+pub fn fee_per_msg(tx_hash: &TxHash) -> Option<BTreeMap<String, u128>> {
+    let accounted = ACCOUNTED_TXS.get(tx_hash)?;
+
+    let mut totals = BTreeMap::<String, (u128, u128)>::new();
+
+    for entry in accounted
+        .entries
+        .iter()
+        .filter(|e| e.kind == EntryKind::Cost)
+    {
+        let (amount, count) = totals.entry(entry.denom.clone()).or_default();
+        *amount = amount.saturating_add(entry.amount);
+        *count += 1;
+    }
+
+    Some(
+        totals
+            .into_iter()
+            .map(|(denom, (amount, count))| (denom, amount / count))
+            .collect(),
+    )
+}
+
+/// The total fees paid in the given denomination by the given wallet, for the transactions
+/// submitted to the given chain which were accounted for since the relayer started.
+pub fn fees_paid(chain_id: &ChainId, wallet: &str, denom: &str) -> u128 {
+    FEES_PAID
+        .lock()
+        .unwrap()
+        .get(&(chain_id.clone(), wallet.to_string(), denom.to_string()))
+        .copied()
+        .unwrap_or_default()
+}
+
+fn add_fee_paid(entry: &LedgerEntry) {
+    let mut fees_paid = FEES_PAID.lock().unwrap();
+
+    let total = fees_paid
+        .entry((
+            entry.chain_id.clone(),
+            entry.wallet.clone(),
+            entry.denom.clone(),
+        ))
+        .or_default();
+
+    *total = total.saturating_add(entry.amount);
+}
+
+/// A wallet on a chain, and a denomination.
+type WalletDenom = (ChainId, String, String);
+
+/// The total fees paid, by chain, wallet and denomination.
+static FEES_PAID: Lazy<Mutex<HashMap<WalletDenom, u128>>> = Lazy::new(Default::default);
+
+/// A transaction accounted for, on submission or once confirmed.
+#[derive(Clone, Debug)]
+struct AccountedTx {
+    entries: Vec<LedgerEntry>,
+    confirmed: bool,
+}
+
+/// The transactions accounted for recently, by transaction hash.
+static ACCOUNTED_TXS: Lazy<moka::sync::Cache<TxHash, AccountedTx>> = Lazy::new(|| {
+    moka::sync::Cache::builder()
+        .time_to_live(ACCOUNTED_TX_LIFETIME)
+        .build()
+});
+
+static LEDGER: GlobalSink<AppendLog> = GlobalSink::new();
+
+/// Opens the ledger configured in the `[accounting]` section, if enabled,
+/// to which the fees of the transactions accounted for are then recorded.
+pub fn init(config: &AccountingConfig) -> Result<(), Error> {
+    if !config.enabled {
+        return Ok(());
+    }
+
+    LEDGER.init(|| {
+        let ledger = AppendLog::open(&ledger_path(config)?).map_err(Error::ledger)?;
+        debug!("recording fees to ledger {}", ledger.path().display());
+
+        Ok(ledger)
+    })
+}
+
+/// The path of the ledger configured in the `[accounting]` section.
+pub fn ledger_path(config: &AccountingConfig) -> Result<PathBuf, Error> {
+    match &config.path {
+        Some(path) => Ok(path.clone()),
+        None => Ok(dirs_next::home_dir()
+            .ok_or_else(Error::home_location_unavailable)?
+            .join(LEDGER_DEFAULT_PATH)),
+    }
+}
+
+/// Reads the entries recorded in the ledger at the given path, in the order they were recorded.
+pub fn read_ledger(path: &Path) -> Result<Vec<LedgerEntry>, Error> {
+    append_log::read_log(path).map_err(Error::ledger)
+}
+
+/// The fees paid and rewarded on a channel of a chain, by a wallet, per denom.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ChannelCosts {
+    pub chain_id: ChainId,
+    pub wallet: String,
+    pub channel_id: Option<ChannelId>,
+    pub fees_paid: BTreeMap<String, u128>,
+    pub fees_paid_per_msg_type: BTreeMap<String, BTreeMap<String, u128>>,
+    pub fees_rewarded: BTreeMap<String, u128>,
+    /// The fees rewarded minus the fees paid
+    pub net: BTreeMap<String, i128>,
+}
+
+/// A summary of the fees paid and rewarded since a point in time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CostReport {
+    /// Seconds since the Unix epoch from which the fees are summarized
+    pub since: u64,
+    pub channels: Vec<ChannelCosts>,
+}
+
+impl CostReport {
+    /// Summarizes the given entries which were recorded since the given time,
+    /// per chain, wallet and channel.
+    pub fn new(entries: &[LedgerEntry], since: u64) -> Self {
+        let mut channels: BTreeMap<(ChainId, String, Option<ChannelId>), ChannelCosts> =
+            BTreeMap::new();
+
+        for entry in entries.iter().filter(|entry| entry.time >= since) {
+            let costs = channels
+                .entry((
+                    entry.chain_id.clone(),
+                    entry.wallet.clone(),
+                    entry.channel_id.clone(),
+                ))
+                .or_insert_with(|| ChannelCosts {
+                    chain_id: entry.chain_id.clone(),
+                    wallet: entry.wallet.clone(),
+                    channel_id: entry.channel_id.clone(),
+                    ..ChannelCosts::default()
+                });
+
+            let amount = i128::try_from(entry.amount).unwrap_or(i128::MAX);
+            let net = costs.net.entry(entry.denom.clone()).or_default();
+
+            match entry.kind {
+                EntryKind::Cost => {
+                    *costs.fees_paid.entry(entry.denom.clone()).or_default() += entry.amount;
+                    *costs
+                        .fees_paid_per_msg_type
+                        .entry(entry.msg_type.clone().unwrap_or_default())
+                        .or_default()
+                        .entry(entry.denom.clone())
+                        .or_default() += entry.amount;
+                    *net = net.saturating_sub(amount);
+                }
+                EntryKind::Reward => {
+                    *costs.fees_rewarded.entry(entry.denom.clone()).or_default() += entry.amount;
+                    *net = net.saturating_add(amount);
+                }
+            }
+        }
+
+        Self {
+            since,
+            channels: channels.into_values().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc_proto::cosmos::base::v1beta1::Coin as RawCoin;
+    use ibc_proto::cosmos::tx::v1beta1::Fee;
+    use ibc_proto::ibc::core::channel::v1::Packet as RawPacket;
+    use tendermint::abci::{self, Event as AbciEvent, EventAttribute};
+
+    const WALLET: &str = "cosmos1relayer";
+
+    fn any<M: Message>(type_url: &str, msg: &M) -> Any {
+        Any {
+            type_url: type_url.to_string(),
+            value: msg.encode_to_vec(),
+        }
+    }
+
+    fn packet(source_channel: &str, destination_channel: &str) -> Option<RawPacket> {
+        Some(RawPacket {
+            sequence: 1,
+            source_port: "transfer".to_string(),
+            source_channel: source_channel.to_string(),
+            destination_port: "transfer".to_string(),
+            destination_channel: destination_channel.to_string(),
+            ..RawPacket::default()
+        })
+    }
+
+    fn tx_response(messages: Vec<Any>, fee: &str, events: Vec<AbciEvent>) -> TxResponse {
+        let body = TxBody {
+            messages,
+            ..TxBody::default()
+        };
+
+        let auth_info = AuthInfo {
+            fee: Some(Fee {
+                amount: vec![RawCoin {
+                    denom: "uatom".to_string(),
+                    amount: fee.to_string(),
+                }],
+                gas_limit: 200_000,
+                ..Fee::default()
+            }),
+            ..AuthInfo::default()
+        };
+
+        let tx = TxRaw {
+            body_bytes: body.encode_to_vec(),
+            auth_info_bytes: auth_info.encode_to_vec(),
+            signatures: vec![],
+        };
+
+        TxResponse {
+            hash: TxHash::Sha256([1; 32]),
+            height: 10u32.into(),
+            index: 0,
+            tx_result: abci::response::DeliverTx {
+                events,
+                ..Default::default()
+            },
+            tx: tx.encode_to_vec(),
+            proof: None,
+        }
+    }
+
+    fn distribute_fee(receiver: &str, fee: &str, distribution_type: &str) -> AbciEvent {
+        AbciEvent {
+            kind: "distribute_fee".to_string(),
+            attributes: vec![
+                EventAttribute {
+                    key: "receiver".to_string(),
+                    value: receiver.to_string(),
+                    index: true,
+                },
+                EventAttribute {
+                    key: "fee".to_string(),
+                    value: fee.to_string(),
+                    index: true,
+                },
+                EventAttribute {
+                    key: "distribution_type".to_string(),
+                    value: distribution_type.to_string(),
+                    index: true,
+                },
+            ],
+        }
+    }
+
+    fn update_client(signer: &str) -> Any {
+        any(
+            update_client::TYPE_URL,
+            &raw_client::MsgUpdateClient {
+                client_id: "07-tendermint-0".to_string(),
+                header: None,
+                signer: signer.to_string(),
+            },
+        )
+    }
+
+    fn acknowledgement(signer: &str) -> Any {
+        any(
+            acknowledgement::TYPE_URL,
+            &raw_channel::MsgAcknowledgement {
+                packet: packet("channel-0", "channel-1"),
+                signer: signer.to_string(),
+                ..raw_channel::MsgAcknowledgement::default()
+            },
+        )
+    }
+
+    #[test]
+    fn fees_are_split_between_messages() {
+        let chain_id = ChainId::from_string("chain-a");
+
+        let response = tx_response(
+            vec![
+                update_client(WALLET),
+                acknowledgement(WALLET),
+                acknowledgement(WALLET),
+            ],
+            "1001",
+            vec![
+                distribute_fee(WALLET, "300uatom", "reward"),
+                distribute_fee("cosmos1payer", "200uatom", "refund"),
+                distribute_fee("cosmos1other", "100uatom", "reward"),
+            ],
+        );
+
+        let entries = entries_from_tx(&chain_id, WALLET, &response, 42);
+
+        let costs = entries
+            .iter()
+            .filter(|entry| entry.kind == EntryKind::Cost)
+            .map(|entry| {
+                (
+                    entry.msg_type.as_deref().unwrap(),
+                    entry.channel_id.as_ref().unwrap().as_str(),
+                    entry.amount,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            costs,
+            vec![
+                ("MsgUpdateClient", "channel-0", 335),
+                ("MsgAcknowledgement", "channel-0", 333),
+                ("MsgAcknowledgement", "channel-0", 333),
+            ]
+        );
+
+        let rewards = entries
+            .iter()
+            .filter(|entry| entry.kind == EntryKind::Reward)
+            .collect::<Vec<_>>();
+
+        assert_eq!(rewards.len(), 1);
+        assert_eq!(rewards[0].amount, 300);
+        assert_eq!(rewards[0].denom, "uatom");
+        assert_eq!(rewards[0].channel_id, Some(ChannelId::new(0)));
+    }
+
+    #[test]
+    fn txs_of_other_wallets_are_ignored() {
+        let chain_id = ChainId::from_string("chain-a");
+        let response = tx_response(vec![acknowledgement("cosmos1other")], "1000", vec![]);
+
+        assert!(entries_from_tx(&chain_id, WALLET, &response, 42).is_empty());
+    }
+
+    #[test]
+    fn msgs_executed_on_behalf_of_granter() {
+        let chain_id = ChainId::from_string("chain-a");
+        let granter = "cosmos1granter";
+
+        let exec = |msg| {
+            any(
+                MSG_EXEC_TYPE_URL,
+                &MsgExec {
+                    grantee: WALLET.to_string(),
+                    msgs: vec![msg],
+                },
+            )
+        };
+
+        let response = tx_response(
+            vec![exec(update_client(granter)), exec(acknowledgement(granter))],
+            "1000",
+            vec![],
+        );
+
+        let costs = entries_from_tx(&chain_id, WALLET, &response, 42)
+            .into_iter()
+            .map(|entry| {
+                (
+                    entry.kind,
+                    entry.msg_type.unwrap(),
+                    entry.channel_id.unwrap(),
+                    entry.amount,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            costs,
+            vec![
+                (
+                    EntryKind::Cost,
+                    "MsgUpdateClient".to_string(),
+                    ChannelId::new(0),
+                    500
+                ),
+                (
+                    EntryKind::Cost,
+                    "MsgAcknowledgement".to_string(),
+                    ChannelId::new(0),
+                    500
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn fees_are_accounted_for_on_submission() {
+        let chain_id = ChainId::from_string("chain-submitted");
+
+        let mut response = tx_response(
+            vec![acknowledgement(WALLET)],
+            "1000",
+            vec![distribute_fee(WALLET, "300uatom", "reward")],
+        );
+        response.hash = TxHash::Sha256([2; 32]);
+
+        account_for_submitted_tx(&chain_id, WALLET, &response.tx, &response.hash);
+        account_for_submitted_tx(&chain_id, WALLET, &response.tx, &response.hash);
+        assert_eq!(fees_paid(&chain_id, WALLET, "uatom"), 1000);
+
+        // Once confirmed, only the rewards of the transaction are accounted for
+        account_for_tx(&chain_id, WALLET, &response);
+        account_for_tx(&chain_id, WALLET, &response);
+        assert_eq!(fees_paid(&chain_id, WALLET, "uatom"), 1000);
+        assert_eq!(fee_per_msg(&response.hash).unwrap()["uatom"], 1000);
+
+        let accounted = ACCOUNTED_TXS.get(&response.hash).unwrap();
+        assert!(accounted.confirmed);
+        assert_eq!(
+            accounted
+                .entries
+                .iter()
+                .map(|entry| entry.kind)
+                .collect::<Vec<_>>(),
+            vec![EntryKind::Cost, EntryKind::Reward]
+        );
+    }
+
+    #[test]
+    fn report_per_channel() {
+        let chain_id = ChainId::from_string("chain-a");
+
+        let entry = |time, kind, channel: Option<u64>, amount| LedgerEntry {
+            time,
+            kind,
+            chain_id: chain_id.clone(),
+            wallet: WALLET.to_string(),
+            channel_id: channel.map(ChannelId::new),
+            msg_type: (kind == EntryKind::Cost).then(|| "MsgRecvPacket".to_string()),
+            denom: "uatom".to_string(),
+            amount,
+            tx_hash: String::new(),
+        };
+
+        let entries = vec![
+            entry(10, EntryKind::Cost, Some(0), 1000),
+            entry(20, EntryKind::Cost, Some(0), 500),
+            entry(20, EntryKind::Reward, Some(0), 2000),
+            entry(20, EntryKind::Cost, None, 100),
+            entry(30, EntryKind::Cost, Some(1), 700),
+        ];
+
+        let report = CostReport::new(&entries, 20);
+
+        assert_eq!(report.channels.len(), 3);
+
+        let channel_0 = report
+            .channels
+            .iter()
+            .find(|costs| costs.channel_id == Some(ChannelId::new(0)))
+            .unwrap();
+
+        assert_eq!(channel_0.fees_paid["uatom"], 500);
+        assert_eq!(channel_0.fees_rewarded["uatom"], 2000);
+        assert_eq!(channel_0.net["uatom"], 1500);
+        assert_eq!(
+            channel_0.fees_paid_per_msg_type["MsgRecvPacket"]["uatom"],
+            500
+        );
+
+        let channel_1 = report
+            .channels
+            .iter()
+            .find(|costs| costs.channel_id == Some(ChannelId::new(1)))
+            .unwrap();
+
+        assert_eq!(channel_1.net["uatom"], -700);
+    }
+}
@@ -0,0 +1,214 @@
+//! Audit log of the packets relayed by the relayer, so that the relaying of a packet
+//! can be looked up after the logs of the relayer are rotated.
+//!
+//! If enabled in the `[audit]` section, a record is appended to the log for every
+//! packet message which the relayer submitted and which was confirmed on a chain,
+//! ie. for every packet received, acknowledged or timed out by the relayer.
+//! The log is looked up by `hermes query packet history`.
+//!
+//! Only the messages whose confirmation is awaited by the relayer are recorded,
+//! hence the log is written by `hermes start`, but not by the commands which
+//! relay packets once, such as `hermes tx packet-recv`.
+
+use std::path::{Path, PathBuf};
+
+use alloc::collections::btree_map::BTreeMap;
+use flex_error::define_error;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use ibc_relayer_types::core::ics04_channel::packet::{Packet, Sequence};
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer_types::events::IbcEvent;
+use ibc_relayer_types::Height;
+
+use crate::config::AuditConfig;
+use crate::util::append_log::{self, AppendLog, Error as AppendLogError, GlobalSink};
+
+/// The default location of the audit log, relative to the home directory.
+pub const AUDIT_LOG_DEFAULT_PATH: &str = ".hermes/audit.jsonl";
+
+define_error! {
+    Error {
+        Log
+            [ AppendLogError ]
+            |_| { "error on audit log" },
+
+        HomeLocationUnavailable
+            |_| { "home location is unavailable" },
+    }
+}
+
+/// The kind of packet message relayed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PacketMsgKind {
+    Recv,
+    Ack,
+    Timeout,
+    TimeoutOnClose,
+}
+
+impl PacketMsgKind {
+    /// The kind of packet message which emitted the given event, if any,
+    /// along with the packet it relates to.
+    pub fn from_event(event: &IbcEvent) -> Option<(Self, &Packet)> {
+        match event {
+            IbcEvent::ReceivePacket(ev) => Some((Self::Recv, &ev.packet)),
+            IbcEvent::AcknowledgePacket(ev) => Some((Self::Ack, &ev.packet)),
+            IbcEvent::TimeoutPacket(ev) => Some((Self::Timeout, &ev.packet)),
+            IbcEvent::TimeoutOnClosePacket(ev) => Some((Self::TimeoutOnClose, &ev.packet)),
+            _ => None,
+        }
+    }
+}
+
+/// A packet message relayed and confirmed on a chain, as recorded in the audit log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PacketRecord {
+    /// Seconds since the Unix epoch at which the message was confirmed
+    pub time: u64,
+    pub kind: PacketMsgKind,
+    pub src_chain_id: ChainId,
+    pub src_port_id: PortId,
+    pub src_channel_id: ChannelId,
+    pub dst_chain_id: ChainId,
+    pub dst_port_id: PortId,
+    pub dst_channel_id: ChannelId,
+    pub sequence: Sequence,
+    pub tx_hash: String,
+    pub height: Height,
+    pub signer: String,
+    /// The share of the fee of the transaction paid for the message, per denomination
+    #[serde(default)]
+    pub fee: BTreeMap<String, u128>,
+    pub tracking_id: String,
+}
+
+impl PacketRecord {
+    /// The chain to which the message was submitted.
+    pub fn chain_id(&self) -> &ChainId {
+        match self.kind {
+            PacketMsgKind::Recv => &self.dst_chain_id,
+            PacketMsgKind::Ack | PacketMsgKind::Timeout | PacketMsgKind::TimeoutOnClose => {
+                &self.src_chain_id
+            }
+        }
+    }
+
+    /// Whether the packet was sent or received on the given channel of the given chain.
+    pub fn involves(&self, chain_id: &ChainId, channel_id: &ChannelId) -> bool {
+        (&self.src_chain_id == chain_id && &self.src_channel_id == channel_id)
+            || (&self.dst_chain_id == chain_id && &self.dst_channel_id == channel_id)
+    }
+}
+
+static AUDIT_LOG: GlobalSink<AppendLog> = GlobalSink::new();
+
+/// Opens the audit log configured in the `[audit]` section, if enabled,
+/// to which the packet messages confirmed are then recorded.
+pub fn init(config: &AuditConfig) -> Result<(), Error> {
+    if !config.enabled {
+        return Ok(());
+    }
+
+    AUDIT_LOG.init(|| {
+        let log = AppendLog::open(&audit_log_path(config)?).map_err(Error::log)?;
+        debug!(
+            "recording packet messages to audit log {}",
+            log.path().display()
+        );
+
+        Ok(log)
+    })
+}
+
+/// Whether the audit log is open, ie. whether packet messages should be recorded.
+pub fn is_enabled() -> bool {
+    AUDIT_LOG.get().is_some()
+}
+
+/// Records the given packet messages to the audit log, if open.
+pub fn record(records: &[PacketRecord]) {
+    if records.is_empty() {
+        return;
+    }
+
+    if let Some(log) = AUDIT_LOG.get() {
+        if let Err(e) = log.append(records) {
+            warn!("failed to record {} packet messages: {}", records.len(), e);
+        }
+    }
+}
+
+/// The path of the audit log configured in the `[audit]` section.
+pub fn audit_log_path(config: &AuditConfig) -> Result<PathBuf, Error> {
+    match &config.path {
+        Some(path) => Ok(path.clone()),
+        None => Ok(dirs_next::home_dir()
+            .ok_or_else(Error::home_location_unavailable)?
+            .join(AUDIT_LOG_DEFAULT_PATH)),
+    }
+}
+
+/// Reads the records written to the audit log at the given path, in the order they were written.
+pub fn read_audit_log(path: &Path) -> Result<Vec<PacketRecord>, Error> {
+    append_log::read_log(path).map_err(Error::log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(kind: PacketMsgKind, sequence: u64) -> PacketRecord {
+        PacketRecord {
+            time: 1685577600,
+            kind,
+            src_chain_id: ChainId::new("ibc".to_string(), 0),
+            src_port_id: PortId::transfer(),
+            src_channel_id: ChannelId::new(0),
+            dst_chain_id: ChainId::new("ibc".to_string(), 1),
+            dst_port_id: PortId::transfer(),
+            dst_channel_id: ChannelId::new(3),
+            sequence: Sequence::from(sequence),
+            tx_hash: "A8F1".to_string(),
+            height: Height::new(1, 42).unwrap(),
+            signer: "cosmos1relayer".to_string(),
+            fee: BTreeMap::from([("stake".to_string(), 1000)]),
+            tracking_id: "cleared/1f2e3d4c".to_string(),
+        }
+    }
+
+    #[test]
+    fn involves_either_end_of_the_channel() {
+        let record = record(PacketMsgKind::Recv, 1);
+
+        assert_eq!(record.chain_id(), &record.dst_chain_id);
+        assert!(record.involves(&ChainId::new("ibc".to_string(), 0), &ChannelId::new(0)));
+        assert!(record.involves(&ChainId::new("ibc".to_string(), 1), &ChannelId::new(3)));
+        assert!(!record.involves(&ChainId::new("ibc".to_string(), 1), &ChannelId::new(0)));
+    }
+
+    #[test]
+    fn append_and_read_audit_log() {
+        let dir = std::env::temp_dir().join(format!("hermes-audit-{}", std::process::id()));
+        let path = dir.join("audit.jsonl");
+
+        let records = vec![
+            record(PacketMsgKind::Recv, 1),
+            record(PacketMsgKind::Ack, 1),
+            record(PacketMsgKind::TimeoutOnClose, 2),
+        ];
+
+        let log = AppendLog::open(&path).unwrap();
+        log.append(&records[..2]).unwrap();
+        log.append(&records[2..]).unwrap();
+
+        assert_eq!(read_audit_log(&path).unwrap(), records);
+
+        let lines = std::fs::read_to_string(&path).unwrap();
+        assert!(lines.starts_with(r#"{"time":1685577600,"kind":"recv","#));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -2,27 +2,29 @@
 //!
 //! Utilizes the [`moka`](https://docs.rs/moka) crate, which provides full
 //! concurrency of retrievals and a high expected concurrency for updates.
+//!
+//! The time-to-live and capacity of each sub-cache are configured per chain
+//! in the `[chains.cache]` section, see [`CacheConfig`].
+use alloc::sync::Arc;
 use core::fmt::Formatter;
+use core::hash::Hash;
+use core::sync::atomic::{AtomicU64, Ordering};
 use std::fmt;
 use std::time::Duration;
 
+use moka::notification::RemovalCause;
 use moka::sync::Cache as MokaCache;
 
 use ibc_relayer_types::core::ics02_client::height::Height;
 use ibc_relayer_types::core::ics03_connection::connection::ConnectionEnd;
 use ibc_relayer_types::core::ics04_channel::channel::ChannelEnd;
-use ibc_relayer_types::core::ics24_host::identifier::{ClientId, ConnectionId, PortChannelId};
+use ibc_relayer_types::core::ics24_host::identifier::{
+    ChainId, ClientId, ConnectionId, PortChannelId,
+};
 
 use crate::client_state::AnyClientState;
-
-const CHANNEL_CACHE_TTL: Duration = Duration::from_secs(60);
-const CONNECTION_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
-const CLIENT_STATE_CACHE_TTL: Duration = Duration::from_millis(500);
-const LATEST_HEIGHT_CACHE_TTL: Duration = Duration::from_millis(200);
-
-const CHANNEL_CACHE_CAPACITY: u64 = 10_000;
-const CONNECTION_CACHE_CAPACITY: u64 = 10_000;
-const CLIENT_STATE_CACHE_CAPACITY: u64 = 10_000;
+use crate::config::CacheConfig;
+use crate::telemetry;
 
 /// Whether or not a result was in cache (ie. a cache hit)
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -40,46 +42,52 @@ pub type CacheResult<A, E> = Result<(A, CacheStatus), E>;
 /// There should be one `Cache` instantiated per every chain runtime.
 #[derive(Clone)]
 pub struct Cache {
+    /// The chain whose queries are cached, for telemetry.
+    chain_id: ChainId,
     /// Cache storing [`ChannelEnd`]s keyed by their [`PortChannelId`]s.
-    channels: MokaCache<PortChannelId, ChannelEnd>,
+    channels: QueryCache<PortChannelId, ChannelEnd>,
     /// Cache storing [`ConnectionEnd`]s keyed by their [`ConnectionId`]s.
-    connections: MokaCache<ConnectionId, ConnectionEnd>,
+    connections: QueryCache<ConnectionId, ConnectionEnd>,
     /// Cache storing [`AnyClientState`]s keyed by their [`ClientId`]s.
-    client_states: MokaCache<ClientId, AnyClientState>,
+    client_states: QueryCache<ClientId, AnyClientState>,
     /// The latest `Height` associated with the chain runtime this `Cache` is associated with.
-    latest_height: MokaCache<(), Height>,
-}
-
-impl Default for Cache {
-    fn default() -> Self {
-        Self::new()
-    }
+    latest_height: QueryCache<(), Height>,
 }
 
 impl Cache {
-    /// Initializes a new empty [`Cache`] with default time-to-live values.
-    pub fn new() -> Cache {
-        let channels = MokaCache::builder()
-            .time_to_live(CHANNEL_CACHE_TTL)
-            .max_capacity(CHANNEL_CACHE_CAPACITY)
-            .build();
-
-        let connections = MokaCache::builder()
-            .time_to_live(CONNECTION_CACHE_TTL)
-            .max_capacity(CONNECTION_CACHE_CAPACITY)
-            .build();
-
-        let client_states = MokaCache::builder()
-            .time_to_live(CLIENT_STATE_CACHE_TTL)
-            .max_capacity(CLIENT_STATE_CACHE_CAPACITY)
-            .build();
-
-        let latest_height = MokaCache::builder()
-            .time_to_live(LATEST_HEIGHT_CACHE_TTL)
-            .max_capacity(1)
-            .build();
+    /// Initializes a new empty [`Cache`] for the given chain, with the time-to-live
+    /// values and capacities of the given configuration.
+    pub fn new(chain_id: ChainId, config: &CacheConfig) -> Cache {
+        let channels = QueryCache::new(
+            &chain_id,
+            "query_channel",
+            config.channel_ttl,
+            config.channel_capacity,
+        );
+
+        let connections = QueryCache::new(
+            &chain_id,
+            "query_connection",
+            config.connection_ttl,
+            config.connection_capacity,
+        );
+
+        let client_states = QueryCache::new(
+            &chain_id,
+            "query_client_state",
+            config.client_state_ttl,
+            config.client_state_capacity,
+        );
+
+        let latest_height = QueryCache::new(
+            &chain_id,
+            "query_latest_height",
+            config.latest_height_ttl,
+            1,
+        );
 
         Cache {
+            chain_id,
             channels,
             connections,
             client_states,
@@ -179,6 +187,172 @@ impl Cache {
 
 impl fmt::Debug for Cache {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("Cache").finish_non_exhaustive()
+        f.debug_struct("Cache")
+            .field("chain_id", &self.chain_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A sub-cache, along with the number of entries it holds, which is exported in telemetry
+/// whenever entries are inserted or removed, as the entry count of the underlying cache is
+/// only eventually consistent. The entries evicted because they expired or because the cache
+/// is full are counted in telemetry as well.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "telemetry"), allow(dead_code))]
+struct QueryCache<K, V> {
+    cache: MokaCache<K, V>,
+    chain_id: ChainId,
+    query_type: &'static str,
+    /// The entries inserted and not yet removed, each of which is removed
+    /// exactly once, when it is replaced, evicted, or rejected on insertion
+    size: Arc<AtomicU64>,
+}
+
+impl<K, V> QueryCache<K, V>
+where
+    K: Hash + Eq + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    /// Builds a sub-cache with the given time-to-live and capacity,
+    /// for the given query type of the given chain.
+    #[cfg_attr(not(feature = "telemetry"), allow(unused_variables))]
+    fn new(chain_id: &ChainId, query_type: &'static str, ttl: Duration, capacity: u64) -> Self {
+        let size = Arc::new(AtomicU64::new(0));
+
+        let cache = {
+            let chain_id = chain_id.clone();
+            let size = size.clone();
+
+            MokaCache::builder()
+                .time_to_live(ttl)
+                .max_capacity(capacity)
+                .eviction_listener(move |_, _, cause| {
+                    let _size = size.fetch_sub(1, Ordering::SeqCst) - 1;
+                    telemetry!(queries_cache_size, &chain_id, query_type, _size);
+
+                    let cause = match cause {
+                        RemovalCause::Expired => "expired",
+                        RemovalCause::Size => "size",
+                        RemovalCause::Explicit | RemovalCause::Replaced => return,
+                    };
+
+                    telemetry!(queries_cache_evictions, &chain_id, query_type, cause);
+                })
+                .build()
+        };
+
+        Self {
+            cache,
+            chain_id: chain_id.clone(),
+            query_type,
+            size,
+        }
+    }
+
+    fn get(&self, key: &K) -> Option<V> {
+        self.cache.get(key)
+    }
+
+    fn insert(&self, key: K, value: V) {
+        // Counted before it is inserted, as the entry it replaces, if any,
+        // may be removed before the insertion returns
+        let _size = self.size.fetch_add(1, Ordering::SeqCst) + 1;
+        telemetry!(queries_cache_size, &self.chain_id, self.query_type, _size);
+
+        self.cache.insert(key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use ibc_relayer_types::core::ics24_host::identifier::{ChannelId, PortId};
+    use moka::sync::ConcurrentCacheExt;
+
+    fn cache(config: CacheConfig) -> Cache {
+        Cache::new(ChainId::new("ibc".to_string(), 0), &config)
+    }
+
+    #[test]
+    fn latest_height_expires_after_configured_ttl() {
+        let cache = cache(CacheConfig {
+            latest_height_ttl: Duration::from_millis(50),
+            ..Default::default()
+        });
+
+        let height = Height::new(0, 10).unwrap();
+
+        let (_, status) = cache
+            .get_or_try_update_latest_height_with(|| Ok::<_, Infallible>(height))
+            .unwrap();
+        assert_eq!(status, CacheStatus::Miss);
+
+        let (cached, status) = cache
+            .get_or_try_update_latest_height_with(|| Ok::<_, Infallible>(height.increment()))
+            .unwrap();
+        assert_eq!((cached, status), (height, CacheStatus::Hit));
+
+        std::thread::sleep(Duration::from_millis(100));
+
+        let (fetched, status) = cache
+            .get_or_try_update_latest_height_with(|| Ok::<_, Infallible>(height.increment()))
+            .unwrap();
+        assert_eq!((fetched, status), (height.increment(), CacheStatus::Miss));
+    }
+
+    #[test]
+    fn channels_are_bounded_by_configured_capacity() {
+        let cache = cache(CacheConfig {
+            channel_capacity: 2,
+            ..Default::default()
+        });
+
+        for i in 0..10 {
+            let id = PortChannelId::new(ChannelId::new(i), PortId::transfer());
+            let mut chan = ChannelEnd::default();
+            chan.set_state(ibc_relayer_types::core::ics04_channel::channel::State::Open);
+
+            cache
+                .get_or_try_insert_channel_with(&id, || Ok::<_, Infallible>(chan))
+                .unwrap();
+        }
+
+        cache.channels.cache.sync();
+        assert!(cache.channels.cache.entry_count() <= 2);
+        assert_eq!(
+            cache.channels.size.load(Ordering::SeqCst),
+            cache.channels.cache.entry_count()
+        );
+    }
+
+    #[test]
+    fn size_accounts_for_replaced_and_expired_entries() {
+        let cache = cache(CacheConfig {
+            channel_ttl: Duration::from_millis(50),
+            ..Default::default()
+        });
+
+        let channels = &cache.channels;
+        let size = || channels.size.load(Ordering::SeqCst);
+
+        let id = PortChannelId::new(ChannelId::new(0), PortId::transfer());
+        let mut chan = ChannelEnd::default();
+        chan.set_state(ibc_relayer_types::core::ics04_channel::channel::State::Open);
+
+        cache
+            .get_or_try_insert_channel_with(&id, || Ok::<_, Infallible>(chan.clone()))
+            .unwrap();
+        channels.insert(id, chan);
+
+        channels.cache.sync();
+        assert_eq!(size(), 1);
+
+        std::thread::sleep(Duration::from_millis(100));
+
+        channels.cache.sync();
+        assert_eq!(size(), 0);
     }
 }
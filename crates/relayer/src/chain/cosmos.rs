@@ -64,23 +64,29 @@ use tendermint_rpc::endpoint::status;
 use tendermint_rpc::{Client, HttpClient, Order};
 
 use crate::account::Balance;
+use crate::accounting::account_for_tx;
 use crate::chain::client::ClientSettings;
 use crate::chain::cosmos::batch::{
     send_batched_messages_and_wait_check_tx, send_batched_messages_and_wait_commit,
     sequential_send_batched_messages_and_wait_commit,
 };
+use crate::chain::cosmos::compatibility::{BlockResultsLayout, CometCompat};
 use crate::chain::cosmos::encode::key_pair_to_signer;
+use crate::chain::cosmos::endpoint::track_request;
 use crate::chain::cosmos::fee::maybe_register_counterparty_payee;
 use crate::chain::cosmos::gas::{calculate_fee, mul_ceil};
 use crate::chain::cosmos::query::account::get_or_fetch_account;
 use crate::chain::cosmos::query::balance::{query_all_balances, query_balance};
+use crate::chain::cosmos::query::block_results::fetch_block_results;
+use crate::chain::cosmos::query::block_scan::BlockScanner;
 use crate::chain::cosmos::query::consensus_state::query_consensus_state_heights;
 use crate::chain::cosmos::query::custom::cross_chain_query_via_rpc;
 use crate::chain::cosmos::query::denom_trace::query_denom_trace;
 use crate::chain::cosmos::query::fee::query_incentivized_packet;
 use crate::chain::cosmos::query::status::query_status;
 use crate::chain::cosmos::query::tx::{
-    filter_matching_event, query_packets_from_block, query_packets_from_txs, query_txs,
+    all_ibc_events_from_tx_search_response, filter_matching_event, query_packets_from_block,
+    query_packets_from_txs, query_tx_response, query_txs,
 };
 use crate::chain::cosmos::query::{abci_query, fetch_version_specs, packet_query, QueryResponse};
 use crate::chain::cosmos::types::account::Account;
@@ -107,10 +113,12 @@ use crate::util::pretty::{
     PrettyIdentifiedChannel, PrettyIdentifiedClientState, PrettyIdentifiedConnection,
 };
 
+pub mod authz;
 pub mod batch;
 pub mod client;
 pub mod compatibility;
 pub mod encode;
+pub mod endpoint;
 pub mod estimate;
 pub mod fee;
 pub mod gas;
@@ -142,6 +150,7 @@ pub struct CosmosSdkChain {
     tx_config: TxConfig,
     rpc_client: HttpClient,
     compat_mode: CompatMode,
+    block_results_layout: BlockResultsLayout,
     grpc_addr: Uri,
     light_client: TmLightClient,
     rt: Arc<TokioRuntime>,
@@ -232,7 +241,11 @@ impl CosmosSdkChain {
 
         // Check on the configured max_tx_size against the consensus parameters at latest height
         let result = self
-            .block_on(self.rpc_client.consensus_params(latest_height))
+            .block_on(track_request(
+                self.id(),
+                &self.config.rpc_addr,
+                self.rpc_client.consensus_params(latest_height),
+            ))
             .map_err(|e| {
                 Error::config_validation_json_rpc(
                     self.id().clone(),
@@ -302,12 +315,37 @@ impl CosmosSdkChain {
                 url.clone(),
                 self.compat_mode,
                 *batch_delay,
+                self.rpc_client.clone(),
+                self.block_results_layout,
                 self.rt.clone(),
             ),
-            Mode::Pull { interval } => EventSource::rpc(
+            Mode::Pull {
+                interval,
+                start_height,
+            } => EventSource::rpc(
                 self.config.id.clone(),
                 self.rpc_client.clone(),
+                self.block_results_layout,
+                *interval,
+                *start_height,
+                self.rt.clone(),
+            ),
+            Mode::Hybrid {
+                url,
+                batch_delay,
+                interval,
+                stall_timeout,
+                start_height,
+            } => EventSource::hybrid(
+                self.config.id.clone(),
+                url.clone(),
+                self.compat_mode,
+                *batch_delay,
+                self.rpc_client.clone(),
+                self.block_results_layout,
                 *interval,
+                *stall_timeout,
+                *start_height,
                 self.rt.clone(),
             ),
         }
@@ -344,7 +382,11 @@ impl CosmosSdkChain {
         );
 
         let response = self
-            .block_on(client.query_params(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.query_params(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_ccv_consumer_chain_params".to_owned()))?;
 
         let params = response
@@ -380,7 +422,11 @@ impl CosmosSdkChain {
             tonic::Request::new(ibc_proto::cosmos::staking::v1beta1::QueryParamsRequest {});
 
         let response = self
-            .block_on(client.params(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.params(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_staking_params".to_owned()))?;
 
         let params = response
@@ -433,7 +479,11 @@ impl CosmosSdkChain {
 
         let request = tonic::Request::new(ibc_proto::cosmos::base::node::v1beta1::ConfigRequest {});
 
-        match self.block_on(client.config(request)) {
+        match self.block_on(track_request(
+            self.id(),
+            &self.grpc_addr,
+            client.config(request),
+        )) {
             Ok(response) => {
                 let params = response.into_inner();
 
@@ -536,6 +586,7 @@ impl CosmosSdkChain {
         }
 
         let response = self.block_on(abci_query(
+            self.id(),
             &self.rpc_client,
             &self.config.rpc_addr,
             IBC_QUERY_PATH.to_string(),
@@ -575,6 +626,7 @@ impl CosmosSdkChain {
         let path = SDK_UPGRADE_QUERY_PATH.into();
 
         let response: QueryResponse = self.block_on(abci_query(
+            self.id(),
             &self.rpc_client,
             &self.config.rpc_addr,
             path,
@@ -602,7 +654,11 @@ impl CosmosSdkChain {
         crate::telemetry!(query, self.id(), "status");
 
         let status = self
-            .block_on(self.rpc_client.status())
+            .block_on(track_request(
+                self.id(),
+                &self.config.rpc_addr,
+                self.rpc_client.status(),
+            ))
             .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
 
         if status.sync_info.catching_up {
@@ -659,8 +715,13 @@ impl CosmosSdkChain {
         let key_pair = self.key()?;
         let key_account = key_pair.account();
 
-        let account =
-            get_or_fetch_account(&self.grpc_addr, &key_account, &mut self.account).await?;
+        let account = get_or_fetch_account(
+            &self.config.id,
+            &self.grpc_addr,
+            &key_account,
+            &mut self.account,
+        )
+        .await?;
 
         if self.config.sequential_batch_tx {
             sequential_send_batched_messages_and_wait_commit(
@@ -710,8 +771,13 @@ impl CosmosSdkChain {
         let key_pair = self.key()?;
         let key_account = key_pair.account();
 
-        let account =
-            get_or_fetch_account(&self.grpc_addr, &key_account, &mut self.account).await?;
+        let account = get_or_fetch_account(
+            &self.config.id,
+            &self.grpc_addr,
+            &key_account,
+            &mut self.account,
+        )
+        .await?;
 
         send_batched_messages_and_wait_check_tx(
             &self.rpc_client,
@@ -745,7 +811,11 @@ impl CosmosSdkChain {
             tendermint::block::Height::try_from(block_height.revision_height()).unwrap();
 
         let response = self
-            .block_on(self.rpc_client.block_results(tm_height))
+            .block_on(track_request(
+                self.id(),
+                &self.config.rpc_addr,
+                fetch_block_results(&self.rpc_client, self.block_results_layout, tm_height),
+            ))
             .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
 
         let response_height = ICSHeight::new(self.id().version(), u64::from(response.height))
@@ -754,7 +824,6 @@ impl CosmosSdkChain {
         begin_block_events.append(
             &mut response
                 .begin_block_events
-                .unwrap_or_default()
                 .iter()
                 .filter_map(|ev| filter_matching_event(ev, request, seqs))
                 .map(|ev| IbcEventWithHeight::new(ev, response_height))
@@ -764,7 +833,6 @@ impl CosmosSdkChain {
         end_block_events.append(
             &mut response
                 .end_block_events
-                .unwrap_or_default()
                 .iter()
                 .filter_map(|ev| filter_matching_event(ev, request, seqs))
                 .map(|ev| IbcEventWithHeight::new(ev, response_height))
@@ -791,23 +859,27 @@ impl CosmosSdkChain {
 
         for seq in request.sequences.iter().copied() {
             let response = self
-                .block_on(self.rpc_client.block_search(
-                    packet_query(request, seq),
-                    // We only need the first page
-                    1,
-                    // There should only be a single match for this query, but due to
-                    // the fact that the indexer treat the query as a disjunction over
-                    // all events in a block rather than a conjunction over a single event,
-                    // we may end up with partial matches and therefore have to account for
-                    // that by fetching multiple results and filter it down after the fact.
-                    // In the worst case we get N blocks where N is the number of channels,
-                    // but 10 seems to work well enough in practice while keeping the response
-                    // size, and therefore pressure on the node, fairly low.
-                    10,
-                    // We could pick either ordering here, since matching blocks may be at pretty
-                    // much any height relative to the target blocks, so we went with most recent
-                    // blocks first.
-                    Order::Descending,
+                .block_on(track_request(
+                    self.id(),
+                    &self.config.rpc_addr,
+                    self.rpc_client.block_search(
+                        packet_query(request, seq),
+                        // We only need the first page
+                        1,
+                        // There should only be a single match for this query, but due to
+                        // the fact that the indexer treat the query as a disjunction over
+                        // all events in a block rather than a conjunction over a single event,
+                        // we may end up with partial matches and therefore have to account for
+                        // that by fetching multiple results and filter it down after the fact.
+                        // In the worst case we get N blocks where N is the number of channels,
+                        // but 10 seems to work well enough in practice while keeping the response
+                        // size, and therefore pressure on the node, fairly low.
+                        10,
+                        // We could pick either ordering here, since matching blocks may be at pretty
+                        // much any height relative to the target blocks, so we went with most recent
+                        // blocks first.
+                        Order::Descending,
+                    ),
                 ))
                 .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
 
@@ -850,10 +922,10 @@ impl ChainEndpoint for CosmosSdkChain {
 
         let node_info = rt.block_on(fetch_node_info(&rpc_client, &config))?;
 
-        let compat_mode = CompatMode::from_version(node_info.version).unwrap_or_else(|e| {
-            warn!("Unsupported tendermint version, will use v0.37 compatibility mode but relaying might not work as desired: {e}");
-            CompatMode::V0_37
-        });
+        let CometCompat {
+            compat_mode,
+            block_results_layout,
+        } = compatibility::comet_compat_or_default(&node_info.version);
         rpc_client.set_compat_mode(compat_mode);
 
         let light_client = TmLightClient::from_config(&config, node_info.id)?;
@@ -870,7 +942,23 @@ impl ChainEndpoint for CosmosSdkChain {
         let grpc_addr = Uri::from_str(&config.grpc_addr.to_string())
             .map_err(|e| Error::invalid_uri(config.grpc_addr.to_string(), e))?;
 
-        let tx_config = TxConfig::try_from(&config)?;
+        let mut tx_config = TxConfig::try_from(&config)?;
+
+        if node_info.other.tx_index != TxIndexStatus::On {
+            warn!(
+                "transaction indexing is disabled on the node at {}, the transactions and packet \
+                events will be looked for by scanning the latest {} blocks instead",
+                config.rpc_addr, config.block_scan_window
+            );
+
+            tx_config.block_scanner = Some(BlockScanner::new(
+                config.id.clone(),
+                rpc_client.clone(),
+                config.rpc_addr.clone(),
+                block_results_layout,
+                config.block_scan_window,
+            ));
+        }
 
         // Retrieve the version specification of this chain
 
@@ -878,6 +966,7 @@ impl ChainEndpoint for CosmosSdkChain {
             config,
             rpc_client,
             compat_mode,
+            block_results_layout,
             grpc_addr,
             light_client,
             rt,
@@ -925,7 +1014,7 @@ impl ChainEndpoint for CosmosSdkChain {
     ///
     /// Currently this checks that:
     ///     - the node responds OK to `/health` RPC call;
-    ///     - the node has transaction indexing enabled;
+    ///     - the node has transaction indexing enabled, warns otherwise;
     ///     - the SDK & IBC versions are supported;
     ///
     /// Emits a log warning in case anything is amiss.
@@ -1049,7 +1138,7 @@ impl ChainEndpoint for CosmosSdkChain {
         let account = key.account();
 
         let denom = denom.unwrap_or(&self.config.gas_price.denom);
-        let balance = self.block_on(query_balance(&self.grpc_addr, &account, denom))?;
+        let balance = self.block_on(query_balance(self.id(), &self.grpc_addr, &account, denom))?;
 
         Ok(balance)
     }
@@ -1063,13 +1152,13 @@ impl ChainEndpoint for CosmosSdkChain {
         };
         let account = key.account();
 
-        let balance = self.block_on(query_all_balances(&self.grpc_addr, &account))?;
+        let balance = self.block_on(query_all_balances(self.id(), &self.grpc_addr, &account))?;
 
         Ok(balance)
     }
 
     fn query_denom_trace(&self, hash: String) -> Result<DenomTrace, Error> {
-        let denom_trace = self.block_on(query_denom_trace(&self.grpc_addr, &hash))?;
+        let denom_trace = self.block_on(query_denom_trace(self.id(), &self.grpc_addr, &hash))?;
 
         Ok(denom_trace)
     }
@@ -1102,12 +1191,20 @@ impl ChainEndpoint for CosmosSdkChain {
         // Instead, we need to pull block height via `/abci_info` and then fetch block
         // metadata at the given height via `/blockchain` endpoint.
         let abci_info = self
-            .block_on(self.rpc_client.abci_info())
+            .block_on(track_request(
+                self.id(),
+                &self.config.rpc_addr,
+                self.rpc_client.abci_info(),
+            ))
             .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
 
         // Query `/header` endpoint to pull the latest block that the application committed.
         let response = self
-            .block_on(self.rpc_client.header(abci_info.last_block_height))
+            .block_on(track_request(
+                self.id(),
+                &self.config.rpc_addr,
+                self.rpc_client.header(abci_info.last_block_height),
+            ))
             .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
 
         let height = ICSHeight::new(
@@ -1145,7 +1242,11 @@ impl ChainEndpoint for CosmosSdkChain {
 
         let request = tonic::Request::new(request.into());
         let response = self
-            .block_on(client.client_states(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.client_states(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_clients".to_owned()))?
             .into_inner();
 
@@ -1336,7 +1437,11 @@ impl ChainEndpoint for CosmosSdkChain {
 
         let request = tonic::Request::new(request.into());
 
-        let response = match self.block_on(client.client_connections(request)) {
+        let response = match self.block_on(track_request(
+            self.id(),
+            &self.grpc_addr,
+            client.client_connections(request),
+        )) {
             Ok(res) => res.into_inner(),
             Err(e) if e.code() == tonic::Code::NotFound => return Ok(vec![]),
             Err(e) => return Err(Error::grpc_status(e, "query_client_connections".to_owned())),
@@ -1381,7 +1486,11 @@ impl ChainEndpoint for CosmosSdkChain {
         let request = tonic::Request::new(request.into());
 
         let response = self
-            .block_on(client.connections(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.connections(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_connections".to_owned()))?
             .into_inner();
 
@@ -1445,13 +1554,15 @@ impl ChainEndpoint for CosmosSdkChain {
                 .metadata_mut()
                 .insert("x-cosmos-block-height", height_param);
 
-            let response = client.connection(request).await.map_err(|e| {
-                if e.code() == tonic::Code::NotFound {
-                    Error::connection_not_found(connection_id.clone())
-                } else {
-                    Error::grpc_status(e, "query_connection".to_owned())
-                }
-            })?;
+            let response = track_request(chain.id(), &chain.grpc_addr, client.connection(request))
+                .await
+                .map_err(|e| {
+                    if e.code() == tonic::Code::NotFound {
+                        Error::connection_not_found(connection_id.clone())
+                    } else {
+                        Error::grpc_status(e, "query_connection".to_owned())
+                    }
+                })?;
 
             match response.into_inner().connection {
                 Some(raw_connection) => {
@@ -1518,7 +1629,11 @@ impl ChainEndpoint for CosmosSdkChain {
         let request = tonic::Request::new(request.into());
 
         let response = self
-            .block_on(client.connection_channels(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.connection_channels(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_connection_channels".to_owned()))?
             .into_inner();
 
@@ -1566,7 +1681,11 @@ impl ChainEndpoint for CosmosSdkChain {
         let request = tonic::Request::new(request.into());
 
         let response = self
-            .block_on(client.channels(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.channels(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_channels".to_owned()))?
             .into_inner();
 
@@ -1645,7 +1764,11 @@ impl ChainEndpoint for CosmosSdkChain {
         let request = tonic::Request::new(request.into());
 
         let response = self
-            .block_on(client.channel_client_state(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.channel_client_state(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_channel_client_state".to_owned()))?
             .into_inner();
 
@@ -1708,7 +1831,11 @@ impl ChainEndpoint for CosmosSdkChain {
         let request = tonic::Request::new(request.into());
 
         let response = self
-            .block_on(client.packet_commitments(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.packet_commitments(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_packet_commitments".to_owned()))?
             .into_inner();
 
@@ -1779,7 +1906,11 @@ impl ChainEndpoint for CosmosSdkChain {
         let request = tonic::Request::new(request.into());
 
         let mut response = self
-            .block_on(client.unreceived_packets(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.unreceived_packets(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_unreceived_packets".to_owned()))?
             .into_inner();
 
@@ -1847,7 +1978,11 @@ impl ChainEndpoint for CosmosSdkChain {
         let request = tonic::Request::new(request.into());
 
         let response = self
-            .block_on(client.packet_acknowledgements(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.packet_acknowledgements(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_packet_acknowledgements".to_owned()))?
             .into_inner();
 
@@ -1892,7 +2027,11 @@ impl ChainEndpoint for CosmosSdkChain {
         let request = tonic::Request::new(request.into());
 
         let mut response = self
-            .block_on(client.unreceived_acks(request))
+            .block_on(track_request(
+                self.id(),
+                &self.grpc_addr,
+                client.unreceived_acks(request),
+            ))
             .map_err(|e| Error::grpc_status(e, "query_unreceived_acknowledgements".to_owned()))?
             .into_inner();
 
@@ -1952,7 +2091,11 @@ impl ChainEndpoint for CosmosSdkChain {
                 let request = tonic::Request::new(request.into());
 
                 let response = self
-                    .block_on(client.next_sequence_receive(request))
+                    .block_on(track_request(
+                        self.id(),
+                        &self.grpc_addr,
+                        client.next_sequence_receive(request),
+                    ))
                     .map_err(|e| Error::grpc_status(e, "query_next_sequence_receive".to_owned()))?
                     .into_inner();
 
@@ -1971,10 +2114,34 @@ impl ChainEndpoint for CosmosSdkChain {
         });
         crate::telemetry!(query, self.id(), "query_txs");
 
+        // The transactions are queried by hash to confirm the transactions submitted
+        // by the relayer, whose fees are then accounted for
+        if let QueryTxRequest::Transaction(tx) = &request {
+            let response = self.block_on(query_tx_response(
+                self.id(),
+                &self.rpc_client,
+                &self.config.rpc_addr,
+                self.tx_config.block_scanner.as_ref(),
+                &tx.0,
+            ))?;
+
+            return Ok(match response {
+                Some(response) => {
+                    if let Ok(key_pair) = self.key() {
+                        account_for_tx(self.id(), &key_pair.account(), &response);
+                    }
+
+                    all_ibc_events_from_tx_search_response(self.id(), response)
+                }
+                None => Vec::new(),
+            });
+        }
+
         self.block_on(query_txs(
             self.id(),
             &self.rpc_client,
             &self.config.rpc_addr,
+            self.tx_config.block_scanner.as_ref(),
             request,
         ))
     }
@@ -2008,9 +2175,18 @@ impl ChainEndpoint for CosmosSdkChain {
                 self.id(),
                 &self.rpc_client,
                 &self.config.rpc_addr,
+                self.block_results_layout,
                 &request,
             )),
             Qualified::SmallerEqual(_) => {
+                // Without transaction indexing, neither `tx_search` nor `block_search` work,
+                // so all the events are looked for by scanning the latest blocks
+                if let Some(block_scanner) = &self.tx_config.block_scanner {
+                    let mut events = self.block_on(block_scanner.find_packet_events(&request))?;
+                    sort_events_by_sequence(&mut events);
+                    return Ok(events);
+                }
+
                 let tx_events = self.block_on(query_packets_from_txs(
                     self.id(),
                     &self.rpc_client,
@@ -2071,17 +2247,23 @@ impl ChainEndpoint for CosmosSdkChain {
 
         let header = if height.value() == 0 {
             self.block_on(async {
-                self.rpc_client
-                    .latest_block()
-                    .await
-                    .map(|response| response.block.header)
+                track_request(
+                    self.id(),
+                    &self.config.rpc_addr,
+                    self.rpc_client.latest_block(),
+                )
+                .await
+                .map(|response| response.block.header)
             })
         } else {
             self.block_on(async {
-                self.rpc_client
-                    .header(height)
-                    .await
-                    .map(|response| response.header)
+                track_request(
+                    self.id(),
+                    &self.config.rpc_addr,
+                    self.rpc_client.header(height),
+                )
+                .await
+                .map(|response| response.header)
             })
         };
 
@@ -2205,8 +2387,11 @@ impl ChainEndpoint for CosmosSdkChain {
         &self,
         request: QueryIncentivizedPacketRequest,
     ) -> Result<QueryIncentivizedPacketResponse, Error> {
-        let incentivized_response =
-            self.block_on(query_incentivized_packet(&self.grpc_addr, request))?;
+        let incentivized_response = self.block_on(query_incentivized_packet(
+            self.id(),
+            &self.grpc_addr,
+            request,
+        ))?;
         Ok(incentivized_response)
     }
 }
@@ -2230,8 +2415,7 @@ async fn fetch_node_info(
         "src_chain": config.id.to_string(),
     });
 
-    rpc_client
-        .status()
+    track_request(&config.id, &config.rpc_addr, rpc_client.status())
         .await
         .map(|s| s.node_info)
         .map_err(|e| Error::rpc(config.rpc_addr.clone(), e))
@@ -2255,7 +2439,7 @@ fn client_id_suffix(client_id: &ClientId) -> Option<u64> {
 /// 2. Checks that the staking module maintains some historical entries such
 ///    that local header information is stored in the IBC state and thus
 ///    client proofs that are part of the connection handshake can be verified.
-/// 3. Checks that transaction indexing is enabled.
+/// 3. Checks that transaction indexing is enabled, warns otherwise.
 /// 4. Checks that the chain identifier matches the network name.
 /// 5. Checks that the underlying SDK and ibc-go versions are compatible.
 /// 6. Checks that the `gas_price` parameter in Hermes is >= the `min_gas_price`
@@ -2265,19 +2449,31 @@ fn do_health_check(chain: &CosmosSdkChain) -> Result<(), Error> {
     let grpc_address = chain.grpc_addr.to_string();
     let rpc_address = chain.config.rpc_addr.to_string();
 
-    chain.block_on(chain.rpc_client.health()).map_err(|e| {
-        Error::health_check_json_rpc(
-            chain_id.clone(),
-            rpc_address.clone(),
-            "/health".to_string(),
-            e,
-        )
-    })?;
+    chain
+        .block_on(track_request(
+            chain_id,
+            &chain.config.rpc_addr,
+            chain.rpc_client.health(),
+        ))
+        .map_err(|e| {
+            Error::health_check_json_rpc(
+                chain_id.clone(),
+                rpc_address.clone(),
+                "/health".to_string(),
+                e,
+            )
+        })?;
 
     let status = chain.chain_status()?;
 
     if status.node_info.other.tx_index != TxIndexStatus::On {
-        return Err(Error::tx_indexing_disabled(chain_id.clone()));
+        // Log the error, continue with the block scanning fallback
+        warn!(
+            "{}, the latest blocks will be scanned instead, which is slower \
+            and limited to the latest {} blocks (see `block_scan_window`)",
+            Error::tx_indexing_disabled(chain_id.clone()),
+            chain.config.block_scan_window
+        );
     }
 
     if status.node_info.network.as_str() != chain_id.as_str() {
@@ -0,0 +1,90 @@
+use flex_error::define_error;
+use ibc_proto::google::protobuf::Any;
+use ibc_relayer_types::signer::Signer;
+use prost::Message;
+
+pub const MSG_EXEC_TYPE_URL: &str = "/cosmos.authz.v1beta1.MsgExec";
+
+define_error! {
+    Error {
+        InvalidGranter
+            { granter: String, reason: String }
+            |e| { format!("invalid `authz_granter` '{}': {}", e.granter, e.reason) },
+    }
+}
+
+/// `MsgExec` attempts to execute the provided messages using authorizations
+/// granted to the grantee, as defined in `cosmos/authz/v1beta1/tx.proto`.
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgExec {
+    #[prost(string, tag = "1")]
+    pub grantee: String,
+    #[prost(message, repeated, tag = "2")]
+    pub msgs: Vec<Any>,
+}
+
+/// Wraps the given message into a `MsgExec`, to be executed by `grantee` on behalf
+/// of the signer of the message, which must have granted `grantee` the authorization to do so.
+pub fn wrap_in_msg_exec(grantee: &Signer, msg: Any) -> Any {
+    let msg_exec = MsgExec {
+        grantee: grantee.to_string(),
+        msgs: vec![msg],
+    };
+
+    Any {
+        type_url: MSG_EXEC_TYPE_URL.to_string(),
+        value: msg_exec.encode_to_vec(),
+    }
+}
+
+/// Parses the `authz_granter` configured for a chain, which must be
+/// a bech32 address with the account prefix of the chain.
+pub fn parse_granter(granter: &str, account_prefix: &str) -> Result<Signer, Error> {
+    let invalid = |reason: String| Error::invalid_granter(granter.to_string(), reason);
+
+    let (prefix, _, _) = bech32::decode(granter).map_err(|e| invalid(e.to_string()))?;
+
+    if prefix != account_prefix {
+        return Err(invalid(format!(
+            "expected the account prefix '{account_prefix}' of the chain, got '{prefix}'"
+        )));
+    }
+
+    granter.parse().map_err(|e| invalid(format!("{e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_valid_granter() {
+        let granter = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu";
+
+        assert_eq!(parse_granter(granter, "cosmos").unwrap().as_ref(), granter);
+    }
+
+    #[test]
+    fn parse_invalid_granter() {
+        assert!(parse_granter("", "cosmos").is_err());
+        assert!(parse_granter("cosmos1grantee", "cosmos").is_err());
+        assert!(parse_granter("osmo1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5helwsw", "cosmos").is_err());
+    }
+
+    #[test]
+    fn wrap_msg_in_msg_exec() {
+        let grantee: Signer = "cosmos1grantee".parse().unwrap();
+
+        let msg = Any {
+            type_url: "/ibc.core.channel.v1.MsgRecvPacket".to_string(),
+            value: vec![1, 2, 3],
+        };
+
+        let wrapped = wrap_in_msg_exec(&grantee, msg.clone());
+        assert_eq!(wrapped.type_url, MSG_EXEC_TYPE_URL);
+
+        let msg_exec = MsgExec::decode(wrapped.value.as_slice()).unwrap();
+        assert_eq!(msg_exec.grantee, "cosmos1grantee");
+        assert_eq!(msg_exec.msgs, vec![msg]);
+    }
+}
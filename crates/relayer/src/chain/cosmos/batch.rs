@@ -19,7 +19,7 @@ use crate::chain::cosmos::wait::wait_for_block_commits;
 use crate::config::types::Memo;
 use crate::error::Error;
 use crate::event::IbcEventWithHeight;
-use crate::keyring::Secp256k1KeyPair;
+use crate::keyring::{Secp256k1KeyPair, SigningKeyPair};
 
 /**
    Broadcast messages as multiple batched transactions to the chain all at once,
@@ -45,8 +45,10 @@ pub async fn send_batched_messages_and_wait_commit(
 
     wait_for_block_commits(
         &config.chain_id,
+        &key_pair.account(),
         rpc_client,
         &config.rpc_address,
+        config.block_scanner.as_ref(),
         &config.rpc_timeout,
         &mut tx_sync_results,
     )
@@ -198,8 +200,10 @@ async fn sequential_send_messages_as_batches(
 
         wait_for_block_commits(
             &config.chain_id,
+            &key_pair.account(),
             rpc_client,
             &config.rpc_address,
+            config.block_scanner.as_ref(),
             &config.rpc_timeout,
             &mut tx_sync_results,
         )
@@ -310,6 +314,7 @@ fn batch_messages(
 #[cfg(test)]
 mod tests {
     use super::batch_messages;
+    use crate::chain::cosmos::authz::wrap_in_msg_exec;
     use crate::chain::cosmos::encode::sign_and_encode_tx;
     use crate::chain::cosmos::gas::gas_amount_to_fee;
     use crate::chain::cosmos::types::account::{
@@ -416,6 +421,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn batch_of_msg_exec_does_not_exceed_max_tx_size() {
+        const MAX_TX_SIZE: usize = 1000;
+
+        let (config, key_pair, account) = test_fixture();
+        let max_fee = gas_amount_to_fee(&config.gas_config, config.gas_config.max_gas);
+        let memo = Memo::new("").unwrap();
+        let grantee = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu"
+            .parse()
+            .unwrap();
+
+        let messages = (0..20)
+            .map(|_| {
+                wrap_in_msg_exec(
+                    &grantee,
+                    Any {
+                        type_url: "/ibc.core.channel.v1.MsgRecvPacket".into(),
+                        value: vec![0; 100],
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut limited_config = config.clone();
+        limited_config.max_msg_num = MaxMsgNum::new(100).unwrap();
+        limited_config.max_tx_size = MaxTxSize::new(MAX_TX_SIZE).unwrap();
+
+        let batches =
+            batch_messages(&limited_config, &key_pair, &account, &memo, messages).unwrap();
+
+        assert!(batches.len() > 1);
+
+        for batch in batches {
+            let tx_bytes =
+                sign_and_encode_tx(&config, &key_pair, &account, &memo, &batch, &max_fee).unwrap();
+            assert!(tx_bytes.len() <= MAX_TX_SIZE);
+        }
+    }
+
     #[test]
     fn batch_error_on_oversized_message() {
         const MAX_TX_SIZE: usize = 203;
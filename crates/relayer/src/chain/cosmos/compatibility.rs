@@ -1,7 +1,9 @@
 //! Cosmos-SDK compatibility constants and diagnostic methods.
 
+use tendermint::Version as CometVersion;
+use tendermint_rpc::client::CompatMode;
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use super::version;
 
@@ -31,6 +33,76 @@ pub enum Diagnostic {
 
     #[error("Ibc-Go module at version '{found}' does not meet compatibility requirements {requirements}")]
     MismatchingIbcGoModuleVersion { requirements: String, found: String },
+
+    #[error("CometBFT version '{found}' is not supported")]
+    UnsupportedCometVersion { found: String },
+}
+
+/// The layout of the events in the `/block_results` RPC responses,
+/// which depends on the CometBFT version of the node.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BlockResultsLayout {
+    /// Up to CometBFT 0.37, the events are split into
+    /// `begin_block_events`, `txs_results` and `end_block_events`.
+    #[default]
+    BeginEndBlock,
+
+    /// From CometBFT 0.38, the `begin_block_events` and `end_block_events`
+    /// are replaced by `finalize_block_events`.
+    FinalizeBlock,
+}
+
+/// How to talk to a node over RPC, given its CometBFT version.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CometCompat {
+    pub compat_mode: CompatMode,
+    pub block_results_layout: BlockResultsLayout,
+}
+
+impl Default for CometCompat {
+    fn default() -> Self {
+        Self {
+            compat_mode: CompatMode::V0_37,
+            block_results_layout: BlockResultsLayout::BeginEndBlock,
+        }
+    }
+}
+
+/// Determines the RPC compatibility mode and the layout of the block results
+/// from the CometBFT version reported by the node.
+///
+/// CometBFT 0.38 is served with the 0.37 compatibility mode, which it is compatible with,
+/// apart from the layout of the block results.
+pub fn comet_compat(version: &CometVersion) -> Result<CometCompat, Diagnostic> {
+    let raw_version = version.to_string();
+
+    let unsupported = || Diagnostic::UnsupportedCometVersion {
+        found: raw_version.clone(),
+    };
+
+    let version =
+        semver::Version::parse(raw_version.trim_start_matches('v')).map_err(|_| unsupported())?;
+
+    let (compat_mode, block_results_layout) = match (version.major, version.minor) {
+        (0, 34) => (CompatMode::V0_34, BlockResultsLayout::BeginEndBlock),
+        (0, 37) => (CompatMode::V0_37, BlockResultsLayout::BeginEndBlock),
+        (0, 38) => (CompatMode::V0_37, BlockResultsLayout::FinalizeBlock),
+        _ => return Err(unsupported()),
+    };
+
+    Ok(CometCompat {
+        compat_mode,
+        block_results_layout,
+    })
+}
+
+/// Same as [`comet_compat`], but falls back to the 0.37 compatibility mode,
+/// with a warning, if the CometBFT version is not supported.
+pub fn comet_compat_or_default(version: &CometVersion) -> CometCompat {
+    comet_compat(version).unwrap_or_else(|e| {
+        warn!("{e}, will use v0.37 compatibility mode but relaying might not work as desired");
+        CometCompat::default()
+    })
 }
 
 /// Runs a diagnostic check on the provided [`VersionInfo`]
@@ -87,3 +159,39 @@ fn ibc_go_diagnostic(version_info: Option<&semver::Version>) -> Result<(), Diagn
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compat(version: &str) -> Result<CometCompat, Diagnostic> {
+        comet_compat(&serde_json::from_value(serde_json::json!(version)).unwrap())
+    }
+
+    #[test]
+    fn comet_compat_from_version() {
+        assert_eq!(
+            compat("v0.34.27").unwrap(),
+            CometCompat {
+                compat_mode: CompatMode::V0_34,
+                block_results_layout: BlockResultsLayout::BeginEndBlock,
+            }
+        );
+        assert_eq!(
+            compat("0.37.2").unwrap(),
+            CometCompat {
+                compat_mode: CompatMode::V0_37,
+                block_results_layout: BlockResultsLayout::BeginEndBlock,
+            }
+        );
+        assert_eq!(
+            compat("0.38.0-rc3").unwrap(),
+            CometCompat {
+                compat_mode: CompatMode::V0_37,
+                block_results_layout: BlockResultsLayout::FinalizeBlock,
+            }
+        );
+        assert!(compat("0.39.0").is_err());
+        assert!(compat("not-a-version").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! The latency and errors of the requests to the RPC and gRPC endpoints of a chain,
+//! as reported by the `endpoint_latency` and `endpoint_errors` metrics.
+//!
+//! The tendermint RPC client does not check the HTTP status of the responses,
+//! hence an HTML page returned by a proxy along with a `5xx` or `429` status
+//! is reported as a `decode` error.
+
+use core::fmt::Display;
+use core::future::Future;
+use std::time::Instant;
+
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use tendermint_rpc::error::ErrorDetail as RpcErrorDetail;
+use tendermint_rpc::Code as RpcCode;
+
+pub const TIMEOUT: &str = "timeout";
+pub const SERVER_ERROR: &str = "5xx";
+pub const DECODE: &str = "decode";
+pub const RATE_LIMITED: &str = "rate_limited";
+pub const OTHER: &str = "other";
+
+/// An error returned by an endpoint, which falls into one of the classes
+/// of errors reported by the `endpoint_errors` metric.
+pub trait EndpointError {
+    fn class(&self) -> &'static str;
+}
+
+impl EndpointError for tendermint_rpc::Error {
+    fn class(&self) -> &'static str {
+        match self.detail() {
+            RpcErrorDetail::Timeout(_) | RpcErrorDetail::WebSocketTimeout(_) => TIMEOUT,
+            RpcErrorDetail::Io(_) | RpcErrorDetail::Http(_) | RpcErrorDetail::Hyper(_) => {
+                if is_timeout(&self.to_string()) {
+                    TIMEOUT
+                } else {
+                    OTHER
+                }
+            }
+            RpcErrorDetail::Response(e) => {
+                let response = &e.source;
+                let text = format!("{} {}", response.message(), response.data().unwrap_or(""));
+
+                if is_rate_limited(&text) {
+                    RATE_LIMITED
+                } else if matches!(
+                    response.code(),
+                    RpcCode::InternalError | RpcCode::ServerError
+                ) {
+                    SERVER_ERROR
+                } else {
+                    OTHER
+                }
+            }
+            RpcErrorDetail::Server(e) => {
+                if is_rate_limited(&e.reason) {
+                    RATE_LIMITED
+                } else {
+                    SERVER_ERROR
+                }
+            }
+            RpcErrorDetail::Parse(_)
+            | RpcErrorDetail::Serde(_)
+            | RpcErrorDetail::MalformedJson(_)
+            | RpcErrorDetail::Tendermint(_) => DECODE,
+            _ => OTHER,
+        }
+    }
+}
+
+impl EndpointError for tonic::Status {
+    fn class(&self) -> &'static str {
+        use tonic::Code;
+
+        match self.code() {
+            Code::DeadlineExceeded => TIMEOUT,
+            Code::ResourceExhausted => RATE_LIMITED,
+            // tonic reports the `429`, `502`, `503` and `504` HTTP statuses as `Unavailable`,
+            // along with the HTTP status in the message
+            Code::Unavailable if self.message().contains("429") => RATE_LIMITED,
+            Code::Internal if self.message().to_lowercase().contains("decode") => DECODE,
+            Code::Unavailable | Code::Internal | Code::Unknown => SERVER_ERROR,
+            _ => OTHER,
+        }
+    }
+}
+
+fn is_timeout(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("timed out") || message.contains("timeout")
+}
+
+fn is_rate_limited(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("rate limit") || message.contains("too many requests")
+}
+
+/// Perform a request to the given endpoint of the given chain, and record
+/// its latency along with the class of its error if it fails.
+#[cfg_attr(not(feature = "telemetry"), allow(unused_variables))]
+pub async fn track_request<T, E>(
+    chain_id: &ChainId,
+    endpoint: &impl Display,
+    request: impl Future<Output = Result<T, E>>,
+) -> Result<T, E>
+where
+    E: EndpointError,
+{
+    let start = Instant::now();
+    let result = request.await;
+    let latency = start.elapsed();
+
+    crate::telemetry!(
+        endpoint_request,
+        chain_id,
+        &endpoint.to_string(),
+        latency,
+        result.as_ref().err().map(EndpointError::class)
+    );
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grpc_error_class() {
+        let cases = [
+            (tonic::Status::deadline_exceeded("deadline"), TIMEOUT),
+            (tonic::Status::resource_exhausted("quota"), RATE_LIMITED),
+            (
+                tonic::Status::unavailable(
+                    "grpc-status header missing, mapped from HTTP status code 429",
+                ),
+                RATE_LIMITED,
+            ),
+            (
+                tonic::Status::unavailable(
+                    "grpc-status header missing, mapped from HTTP status code 503",
+                ),
+                SERVER_ERROR,
+            ),
+            (
+                tonic::Status::internal("failed to decode Protobuf message"),
+                DECODE,
+            ),
+            (tonic::Status::not_found("client not found"), OTHER),
+        ];
+
+        for (status, class) in cases {
+            assert_eq!(status.class(), class, "{status}");
+        }
+    }
+
+    #[test]
+    fn rpc_error_class() {
+        use core::time::Duration;
+        use tendermint_rpc::ResponseError;
+
+        let cases = [
+            (
+                tendermint_rpc::Error::timeout(Duration::from_secs(1)),
+                TIMEOUT,
+            ),
+            (
+                tendermint_rpc::Error::response(ResponseError::new(
+                    RpcCode::InternalError,
+                    Some("height 10 is not available".to_string()),
+                )),
+                SERVER_ERROR,
+            ),
+            (
+                tendermint_rpc::Error::response(ResponseError::new(
+                    RpcCode::ServerError,
+                    Some("Too Many Requests".to_string()),
+                )),
+                RATE_LIMITED,
+            ),
+            (
+                tendermint_rpc::Error::parse("invalid type".to_string()),
+                DECODE,
+            ),
+            (tendermint_rpc::Error::malformed_json(), DECODE),
+            (
+                tendermint_rpc::Error::response(ResponseError::new(RpcCode::InvalidParams, None)),
+                OTHER,
+            ),
+        ];
+
+        for (error, class) in cases {
+            assert_eq!(error.class(), class, "{error}");
+        }
+    }
+}
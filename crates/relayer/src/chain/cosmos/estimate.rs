@@ -64,7 +64,7 @@ async fn estimate_fee_with_tx(
             }
 
         );
-        estimate_gas_with_tx(gas_config, grpc_address, tx).await
+        estimate_gas_with_tx(gas_config, grpc_address, chain_id, tx).await
     }?;
 
     if estimated_gas > gas_config.max_gas {
@@ -103,9 +103,10 @@ async fn estimate_fee_with_tx(
 async fn estimate_gas_with_tx(
     gas_config: &GasConfig,
     grpc_address: &Uri,
+    chain_id: &ChainId,
     tx: Tx,
 ) -> Result<u64, Error> {
-    let simulated_gas = send_tx_simulate(grpc_address, tx)
+    let simulated_gas = send_tx_simulate(chain_id, grpc_address, tx)
         .await
         .map(|sr| sr.gas_info);
 
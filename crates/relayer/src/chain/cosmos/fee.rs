@@ -26,10 +26,21 @@ pub async fn maybe_register_counterparty_payee(
     counterparty_payee: &Signer,
 ) -> Result<(), Error> {
     let key_account = key_pair.account();
-    let account = get_or_fetch_account(&tx_config.grpc_address, &key_account, m_account).await?;
+    let account = get_or_fetch_account(
+        &tx_config.chain_id,
+        &tx_config.grpc_address,
+        &key_account,
+        m_account,
+    )
+    .await?;
 
-    let current_counterparty_payee =
-        query_counterparty_payee(&tx_config.grpc_address, channel_id, address).await?;
+    let current_counterparty_payee = query_counterparty_payee(
+        &tx_config.chain_id,
+        &tx_config.grpc_address,
+        channel_id,
+        address,
+    )
+    .await?;
 
     match &current_counterparty_payee {
         Some(current_counterparty_payee)
@@ -57,8 +68,10 @@ pub async fn maybe_register_counterparty_payee(
             .await?;
 
             wait_tx_succeed(
+                &tx_config.chain_id,
                 rpc_client,
                 &tx_config.rpc_address,
+                tx_config.block_scanner.as_ref(),
                 &tx_config.rpc_timeout,
                 &response.hash,
             )
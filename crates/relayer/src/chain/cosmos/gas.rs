@@ -25,6 +25,15 @@ pub fn gas_amount_to_fee(config: &GasConfig, gas_amount: u64) -> Fee {
     }
 }
 
+/// The fee paid by a transaction using the default amount of gas, in the gas price denomination.
+pub fn default_tx_fee(config: &GasConfig) -> u128 {
+    gas_amount_to_fee(config, config.default_gas)
+        .amount
+        .first()
+        .and_then(|coin| coin.amount.parse().ok())
+        .unwrap_or_default()
+}
+
 pub fn calculate_fee(adjusted_gas_amount: u64, gas_price: &GasPrice) -> Coin {
     let fee_amount = mul_ceil(adjusted_gas_amount, gas_price.price);
 
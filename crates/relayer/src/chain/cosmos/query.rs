@@ -10,12 +10,15 @@ use tendermint::block::Height;
 use tendermint_rpc::query::Query;
 use tendermint_rpc::{Client, HttpClient, Url};
 
+use crate::chain::cosmos::endpoint::track_request;
 use crate::chain::cosmos::version::Specs;
 use crate::chain::requests::{QueryClientEventRequest, QueryPacketEventDataRequest, QueryTxHash};
 use crate::error::Error;
 
 pub mod account;
 pub mod balance;
+pub mod block_results;
+pub mod block_scan;
 pub mod consensus_state;
 pub mod custom;
 pub mod denom_trace;
@@ -75,6 +78,7 @@ pub fn tx_hash_query(request: &QueryTxHash) -> Query {
 
 /// Perform a generic `abci_query`, and return the corresponding deserialized response data.
 pub async fn abci_query(
+    chain_id: &ChainId,
     rpc_client: &HttpClient,
     rpc_address: &Url,
     path: String,
@@ -89,10 +93,13 @@ pub async fn abci_query(
     };
 
     // Use the Tendermint-rs RPC client to do the query.
-    let response = rpc_client
-        .abci_query(Some(path), data.into_bytes(), height, prove)
-        .await
-        .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+    let response = track_request(
+        chain_id,
+        rpc_address,
+        rpc_client.abci_query(Some(path), data.into_bytes(), height, prove),
+    )
+    .await
+    .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
 
     if !response.code.is_ok() {
         // Fail with response log.
@@ -137,14 +144,16 @@ pub async fn fetch_version_specs(chain_id: &ChainId, grpc_address: &Uri) -> Resu
 
     let request = tonic::Request::new(GetNodeInfoRequest {});
 
-    let response = client.get_node_info(request).await.map_err(|e| {
-        Error::fetch_version_grpc_status(
-            chain_id.clone(),
-            grpc_addr_string.clone(),
-            "tendermint::ServiceClient".to_string(),
-            e,
-        )
-    })?;
+    let response = track_request(chain_id, grpc_address, client.get_node_info(request))
+        .await
+        .map_err(|e| {
+            Error::fetch_version_grpc_status(
+                chain_id.clone(),
+                grpc_addr_string.clone(),
+                "tendermint::ServiceClient".to_string(),
+                e,
+            )
+        })?;
 
     let version = response.into_inner().application_version.ok_or_else(|| {
         Error::fetch_version_invalid_version_response(
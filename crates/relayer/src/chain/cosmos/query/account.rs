@@ -4,6 +4,9 @@ use ibc_proto::cosmos::auth::v1beta1::{BaseAccount, EthAccount, QueryAccountRequ
 use prost::Message;
 use tracing::info;
 
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+
+use crate::chain::cosmos::endpoint::track_request;
 use crate::chain::cosmos::types::account::Account;
 use crate::config::default::max_grpc_decoding_size;
 use crate::error::Error;
@@ -12,6 +15,7 @@ use crate::error::Error;
 /// Otherwise query for the account information, update the `Option` to `Some`,
 /// and return the underlying `&mut` reference.
 pub async fn get_or_fetch_account<'a>(
+    chain_id: &ChainId,
     grpc_address: &'a Uri,
     account_address: &'a str,
     m_account: &'a mut Option<Account>,
@@ -19,7 +23,7 @@ pub async fn get_or_fetch_account<'a>(
     match m_account {
         Some(account) => Ok(account),
         None => {
-            let account = query_account(grpc_address, account_address).await?;
+            let account = query_account(chain_id, grpc_address, account_address).await?;
             *m_account = Some(account.into());
 
             Ok(m_account
@@ -32,11 +36,12 @@ pub async fn get_or_fetch_account<'a>(
 /// Refresh the account sequence behind the `&mut Account` by refetching the
 /// account and updating the `&mut` reference.
 pub async fn refresh_account<'a>(
+    chain_id: &ChainId,
     grpc_address: &Uri,
     account_address: &str,
     m_account: &'a mut Account,
 ) -> Result<(), Error> {
-    let account = query_account(grpc_address, account_address).await?;
+    let account = query_account(chain_id, grpc_address, account_address).await?;
 
     info!(
         sequence = %account.sequence,
@@ -51,6 +56,7 @@ pub async fn refresh_account<'a>(
 
 /// Uses the GRPC client to retrieve the account sequence
 pub async fn query_account(
+    chain_id: &ChainId,
     grpc_address: &Uri,
     account_address: &str,
 ) -> Result<BaseAccount, Error> {
@@ -64,7 +70,7 @@ pub async fn query_account(
         address: account_address.to_string(),
     });
 
-    let response = client.account(request).await;
+    let response = track_request(chain_id, grpc_address, client.account(request)).await;
 
     // Querying for an account might fail, i.e. if the account doesn't actually exist
     let resp_account = match response
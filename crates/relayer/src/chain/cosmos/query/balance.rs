@@ -3,13 +3,16 @@ use http::uri::Uri;
 use ibc_proto::cosmos::bank::v1beta1::{
     query_client::QueryClient, QueryAllBalancesRequest, QueryBalanceRequest,
 };
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 
 use crate::account::Balance;
+use crate::chain::cosmos::endpoint::track_request;
 use crate::config::default::max_grpc_decoding_size;
 use crate::error::Error;
 
 /// Uses the GRPC client to retrieve the account balance for a specific denom
 pub async fn query_balance(
+    chain_id: &ChainId,
     grpc_address: &Uri,
     account_address: &str,
     denom: &str,
@@ -25,8 +28,7 @@ pub async fn query_balance(
         denom: denom.to_string(),
     });
 
-    let response = client
-        .balance(request)
+    let response = track_request(chain_id, grpc_address, client.balance(request))
         .await
         .map(|r| r.into_inner())
         .map_err(|e| Error::grpc_status(e, "query_balance".to_owned()))?;
@@ -44,6 +46,7 @@ pub async fn query_balance(
 
 /// Uses the GRPC client to retrieve the account balance for all denom
 pub async fn query_all_balances(
+    chain_id: &ChainId,
     grpc_address: &Uri,
     account_address: &str,
 ) -> Result<Vec<Balance>, Error> {
@@ -58,8 +61,7 @@ pub async fn query_all_balances(
         pagination: None,
     });
 
-    let response = client
-        .all_balances(request)
+    let response = track_request(chain_id, grpc_address, client.all_balances(request))
         .await
         .map(|r| r.into_inner())
         .map_err(|e| Error::grpc_status(e, "query_all_balances".to_owned()))?;
@@ -0,0 +1,224 @@
+//! Query for the results of a block, whose layout depends on the CometBFT version of the node.
+//!
+//! From CometBFT 0.38, the events emitted outside of the transactions are all returned
+//! in `finalize_block_events`, which `tendermint-rpc` does not know about. They are split
+//! back into the events emitted before and after the transactions, using the `mode`
+//! attribute added by the Cosmos SDK, so that they can be handled as on older versions.
+
+use serde::{Deserialize, Serialize};
+use tendermint::abci::{self, Event};
+use tendermint::block::Height as TmHeight;
+use tendermint_rpc::dialect::{v0_37, DeliverTx};
+use tendermint_rpc::request::RequestMessage;
+use tendermint_rpc::{Client, Error as RpcError, HttpClient, Method};
+
+use crate::chain::cosmos::compatibility::BlockResultsLayout;
+
+/// Key of the attribute set by the Cosmos SDK on the events emitted in `FinalizeBlock`,
+/// whose value tells whether they were emitted before or after the transactions.
+const MODE_ATTRIBUTE_KEY: &str = "mode";
+
+/// Value of the `mode` attribute of the events emitted before the transactions.
+const BEGIN_BLOCK_MODE: &str = "BeginBlock";
+
+/// The results of a block, regardless of the layout of the RPC response.
+#[derive(Clone, Debug)]
+pub struct BlockResults {
+    pub height: TmHeight,
+    /// The events emitted before the transactions
+    pub begin_block_events: Vec<Event>,
+    pub txs_results: Vec<abci::response::DeliverTx>,
+    /// The events emitted after the transactions
+    pub end_block_events: Vec<Event>,
+}
+
+impl BlockResults {
+    /// All the events of the block, in the order they were emitted,
+    /// skipping the events of the transactions which failed.
+    pub fn into_events(self) -> Vec<Event> {
+        let mut events = self.begin_block_events;
+
+        for tx_result in self.txs_results {
+            if tx_result.code.is_ok() {
+                events.extend(tx_result.events);
+            }
+        }
+
+        events.extend(self.end_block_events);
+        events
+    }
+}
+
+impl From<tendermint_rpc::endpoint::block_results::Response> for BlockResults {
+    fn from(response: tendermint_rpc::endpoint::block_results::Response) -> Self {
+        Self {
+            height: response.height,
+            begin_block_events: response.begin_block_events.unwrap_or_default(),
+            txs_results: response.txs_results.unwrap_or_default(),
+            end_block_events: response.end_block_events.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<FinalizeBlockResponse> for BlockResults {
+    fn from(response: FinalizeBlockResponse) -> Self {
+        let (begin_block_events, end_block_events) = response
+            .finalize_block_events
+            .unwrap_or_default()
+            .into_iter()
+            .map(Event::from)
+            .partition(|event| {
+                event
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.key == MODE_ATTRIBUTE_KEY && attr.value == BEGIN_BLOCK_MODE)
+            });
+
+        Self {
+            height: response.height,
+            begin_block_events,
+            txs_results: response
+                .txs_results
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            end_block_events,
+        }
+    }
+}
+
+/// Fetches the results of the block at the given height, as laid out by the node.
+pub async fn fetch_block_results(
+    rpc_client: &HttpClient,
+    layout: BlockResultsLayout,
+    height: TmHeight,
+) -> Result<BlockResults, RpcError> {
+    match layout {
+        BlockResultsLayout::BeginEndBlock => rpc_client.block_results(height).await.map(Into::into),
+        BlockResultsLayout::FinalizeBlock => rpc_client
+            .perform(FinalizeBlockRequest { height })
+            .await
+            .map(Into::into),
+    }
+}
+
+/// A `/block_results` request to a CometBFT 0.38 node.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FinalizeBlockRequest {
+    height: TmHeight,
+}
+
+impl RequestMessage for FinalizeBlockRequest {
+    fn method(&self) -> Method {
+        Method::BlockResults
+    }
+}
+
+impl tendermint_rpc::Request for FinalizeBlockRequest {
+    type Response = FinalizeBlockResponse;
+}
+
+impl tendermint_rpc::SimpleRequest for FinalizeBlockRequest {
+    type Output = FinalizeBlockResponse;
+}
+
+/// A `/block_results` response from a CometBFT 0.38 node,
+/// whose events are encoded as with CometBFT 0.37.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FinalizeBlockResponse {
+    height: TmHeight,
+    txs_results: Option<Vec<DeliverTx<v0_37::Event>>>,
+    finalize_block_events: Option<Vec<v0_37::Event>>,
+}
+
+impl tendermint_rpc::Response for FinalizeBlockResponse {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r#"{
+        "jsonrpc": "2.0",
+        "id": -1,
+        "result": {
+            "height": "42",
+            "txs_results": [
+                {
+                    "code": 0,
+                    "data": null,
+                    "log": "",
+                    "info": "",
+                    "gas_wanted": "100000",
+                    "gas_used": "50000",
+                    "events": [
+                        {
+                            "type": "send_packet",
+                            "attributes": [
+                                { "key": "packet_sequence", "value": "1", "index": true }
+                            ]
+                        }
+                    ],
+                    "codespace": ""
+                },
+                {
+                    "code": 5,
+                    "data": null,
+                    "log": "insufficient funds",
+                    "info": "",
+                    "gas_wanted": "100000",
+                    "gas_used": "50000",
+                    "events": [
+                        {
+                            "type": "send_packet",
+                            "attributes": [
+                                { "key": "packet_sequence", "value": "2", "index": true }
+                            ]
+                        }
+                    ],
+                    "codespace": "sdk"
+                }
+            ],
+            "finalize_block_events": [
+                {
+                    "type": "coin_received",
+                    "attributes": [
+                        { "key": "amount", "value": "10stake", "index": true },
+                        { "key": "mode", "value": "BeginBlock", "index": true }
+                    ]
+                },
+                {
+                    "type": "timeout_packet",
+                    "attributes": [
+                        { "key": "packet_sequence", "value": "3", "index": true },
+                        { "key": "mode", "value": "EndBlock", "index": true }
+                    ]
+                }
+            ],
+            "validator_updates": [],
+            "consensus_param_updates": null,
+            "app_hash": "AAAA"
+        }
+    }"#;
+
+    #[test]
+    fn split_finalize_block_events() {
+        let response = <FinalizeBlockResponse as tendermint_rpc::Response>::from_string(RESPONSE)
+            .expect("failed to parse response");
+
+        let results = BlockResults::from(response);
+        assert_eq!(results.height.value(), 42);
+        assert_eq!(results.txs_results.len(), 2);
+
+        let kinds = |events: &[Event]| events.iter().map(|e| e.kind.clone()).collect::<Vec<_>>();
+
+        assert_eq!(kinds(&results.begin_block_events), ["coin_received"]);
+        assert_eq!(kinds(&results.end_block_events), ["timeout_packet"]);
+
+        // The events of the failed transaction are skipped
+        assert_eq!(
+            kinds(&results.into_events()),
+            ["coin_received", "send_packet", "timeout_packet"]
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Fallback for the queries which rely on the transaction indexer of the node, ie. `tx_search`,
+//! for nodes on which indexing is disabled (`indexer = "null"`).
+//!
+//! Instead of searching the index, the results of the latest blocks are scanned,
+//! within a bounded window of heights, configured per chain by `block_scan_window`.
+//! The scanned blocks are cached, so that subsequent queries, eg. when waiting for
+//! a transaction to be committed, only need to fetch the new blocks.
+
+use core::fmt::{self, Debug, Formatter};
+use core::future::Future;
+use std::sync::Arc;
+
+use futures::future::try_join_all;
+use itertools::Itertools;
+use moka::sync::Cache as MokaCache;
+use sha2::{Digest, Sha256};
+use tendermint::abci::Event;
+use tendermint::block::Height as TmHeight;
+use tendermint::Hash as TxHash;
+use tendermint_rpc::endpoint::tx::Response as TxResponse;
+use tendermint_rpc::{Client, HttpClient, Url};
+use tracing::{debug, warn};
+
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use ibc_relayer_types::Height as ICSHeight;
+
+use crate::chain::cosmos::compatibility::BlockResultsLayout;
+use crate::chain::cosmos::endpoint::track_request;
+use crate::chain::cosmos::query::block_results::fetch_block_results;
+use crate::chain::cosmos::query::tx::{
+    filter_matching_event, update_client_from_tx_search_response,
+};
+use crate::chain::requests::{QueryClientEventRequest, QueryHeight, QueryPacketEventDataRequest};
+use crate::error::Error;
+use crate::event::IbcEventWithHeight;
+
+/// Maximum number of blocks scanned for a transaction, starting from the latest height.
+///
+/// Transactions are looked up shortly after they were broadcast, eg. while waiting for
+/// them to be committed, hence only the latest blocks need to be scanned, which bounds
+/// the number of RPC requests of the first lookup.
+const TX_SCAN_WINDOW: u64 = 50;
+
+/// Maximum number of blocks fetched concurrently.
+const MAX_CONCURRENT_FETCHES: usize = 10;
+
+/// The results of a block, as needed to answer the queries.
+#[derive(Clone, Debug)]
+pub struct ScannedBlock {
+    pub height: TmHeight,
+    pub begin_block_events: Vec<Event>,
+    /// The transactions of the block, in the same form as returned by `tx_search`
+    pub txs: Vec<TxResponse>,
+    pub end_block_events: Vec<Event>,
+}
+
+impl ScannedBlock {
+    /// All the events of the block, in the order they were emitted.
+    fn events(&self) -> impl Iterator<Item = &Event> {
+        self.begin_block_events
+            .iter()
+            .chain(self.txs.iter().flat_map(|tx| tx.tx_result.events.iter()))
+            .chain(self.end_block_events.iter())
+    }
+}
+
+/// Answers the queries relying on the transaction indexer by scanning the latest blocks.
+#[derive(Clone)]
+pub struct BlockScanner {
+    chain_id: ChainId,
+    rpc_client: HttpClient,
+    rpc_address: Url,
+    block_results_layout: BlockResultsLayout,
+    /// Maximum number of blocks scanned for packet events and client updates,
+    /// starting from the query height.
+    window: u64,
+    blocks: MokaCache<u64, Arc<ScannedBlock>>,
+}
+
+impl Debug for BlockScanner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockScanner")
+            .field("chain_id", &self.chain_id)
+            .field("rpc_address", &self.rpc_address)
+            .field("window", &self.window)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BlockScanner {
+    pub fn new(
+        chain_id: ChainId,
+        rpc_client: HttpClient,
+        rpc_address: Url,
+        block_results_layout: BlockResultsLayout,
+        window: u64,
+    ) -> Self {
+        let window = window.max(1);
+
+        // Keep the blocks of two windows, so that a scan for the latest
+        // blocks does not evict those of a scan below the latest height
+        let blocks = MokaCache::builder().max_capacity(2 * window).build();
+
+        Self {
+            chain_id,
+            rpc_client,
+            rpc_address,
+            block_results_layout,
+            window,
+            blocks,
+        }
+    }
+
+    /// Looks for the transaction with the given hash in the latest blocks.
+    pub async fn find_tx(&self, hash: &TxHash) -> Result<Option<TxResponse>, Error> {
+        let from = self.start_height(QueryHeight::Latest).await?;
+
+        let mut found = None;
+
+        let outcome = self
+            .scan(from, TX_SCAN_WINDOW.min(self.window), |block| {
+                found = block.txs.iter().find(|tx| &tx.hash == hash).cloned();
+                found.is_some()
+            })
+            .await?;
+
+        if let ScanOutcome::Exhausted { lowest } = outcome {
+            debug!(
+                chain = %self.chain_id,
+                "tx {hash} not found in blocks {lowest} to {from}, it may not be committed yet"
+            );
+        }
+
+        Ok(found)
+    }
+
+    /// Looks for a client update event matching the request, in the blocks below the query height.
+    pub async fn find_update_client(
+        &self,
+        request: &QueryClientEventRequest,
+    ) -> Result<Option<IbcEventWithHeight>, Error> {
+        let from = self.start_height(request.query_height).await?;
+
+        let mut found = None;
+        let mut error = None;
+
+        // All the updates for the same client and consensus height must have been
+        // performed with the same header, see `query_txs`, so the first one found will do
+        let outcome = self
+            .scan(from, self.window, |block| {
+                for tx in &block.txs {
+                    match update_client_from_tx_search_response(&self.chain_id, request, tx.clone())
+                    {
+                        Ok(Some(event)) => {
+                            found = Some(event);
+                            return true;
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            error = Some(e);
+                            return true;
+                        }
+                    }
+                }
+
+                false
+            })
+            .await?;
+
+        if let ScanOutcome::Exhausted { lowest } = outcome {
+            warn!(
+                chain = %self.chain_id,
+                client = %request.client_id,
+                consensus_height = %request.consensus_height,
+                "no client update found in blocks {lowest} to {from}, older blocks are not \
+                 scanned, consider raising `block_scan_window` or enabling tx indexing on the node"
+            );
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(found),
+        }
+    }
+
+    /// Looks for the latest packet event matching the request for each requested sequence,
+    /// in the blocks below the query height.
+    pub async fn find_packet_events(
+        &self,
+        request: &QueryPacketEventDataRequest,
+    ) -> Result<Vec<IbcEventWithHeight>, Error> {
+        let from = self.start_height(request.height.get()).await?;
+
+        let mut remaining = request.sequences.clone();
+        let mut events = vec![];
+
+        let outcome = self
+            .scan(from, self.window, |block| {
+                let height = ICSHeight::new(self.chain_id.version(), block.height.value())
+                    .expect("block height is never zero");
+
+                for event in block.events() {
+                    if let Some(event) = filter_matching_event(event, request, &remaining) {
+                        if let Some(packet) = event.packet() {
+                            remaining.retain(|seq| seq != &packet.sequence);
+                        }
+
+                        events.push(IbcEventWithHeight::new(event, height));
+                    }
+                }
+
+                remaining.is_empty()
+            })
+            .await?;
+
+        if let ScanOutcome::Exhausted { lowest } = outcome {
+            if !remaining.is_empty() {
+                warn!(
+                    chain = %self.chain_id,
+                    "no {} event found in blocks {lowest} to {from} for {} of the requested \
+                     sequences, older blocks are not scanned, consider raising \
+                     `block_scan_window` or enabling tx indexing on the node",
+                    request.event_id.as_str(),
+                    remaining.len()
+                );
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// The height to start scanning from, ie. the query height, but not above the latest height.
+    async fn start_height(&self, query_height: QueryHeight) -> Result<u64, Error> {
+        let status = track_request(&self.chain_id, &self.rpc_address, self.rpc_client.status())
+            .await
+            .map_err(|e| Error::rpc(self.rpc_address.clone(), e))?;
+
+        let latest = status.sync_info.latest_block_height.value();
+
+        match query_height {
+            QueryHeight::Latest => Ok(latest),
+            QueryHeight::Specific(height) => Ok(height.revision_height().min(latest)),
+        }
+    }
+
+    /// Visits the blocks from the given height downwards, until `visit` returns `true`
+    /// or `window` blocks have been visited.
+    async fn scan(
+        &self,
+        from: u64,
+        window: u64,
+        visit: impl FnMut(&ScannedBlock) -> bool,
+    ) -> Result<ScanOutcome, Error> {
+        scan_blocks(
+            &scan_heights(from, window),
+            |height| self.fetch_block(height),
+            visit,
+        )
+        .await
+    }
+
+    async fn fetch_block(&self, height: u64) -> Result<Arc<ScannedBlock>, Error> {
+        if let Some(block) = self.blocks.get(&height) {
+            return Ok(block);
+        }
+
+        let tm_height =
+            TmHeight::try_from(height).map_err(|_| Error::invalid_height_no_source())?;
+
+        let (block, block_results) = futures::try_join!(
+            track_request(
+                &self.chain_id,
+                &self.rpc_address,
+                self.rpc_client.block(tm_height)
+            ),
+            track_request(
+                &self.chain_id,
+                &self.rpc_address,
+                fetch_block_results(&self.rpc_client, self.block_results_layout, tm_height)
+            )
+        )
+        .map_err(|e| Error::rpc(self.rpc_address.clone(), e))?;
+
+        let txs = block
+            .block
+            .data
+            .into_iter()
+            .zip(block_results.txs_results)
+            .enumerate()
+            .map(|(index, (tx, tx_result))| TxResponse {
+                hash: TxHash::Sha256(Sha256::digest(&tx).into()),
+                height: tm_height,
+                index: index as u32,
+                tx_result,
+                tx,
+                proof: None,
+            })
+            .collect();
+
+        let block = Arc::new(ScannedBlock {
+            height: tm_height,
+            begin_block_events: block_results.begin_block_events,
+            txs,
+            end_block_events: block_results.end_block_events,
+        });
+
+        self.blocks.insert(height, block.clone());
+
+        Ok(block)
+    }
+}
+
+/// The outcome of a scan of the blocks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ScanOutcome {
+    /// The visitor found what it was looking for
+    Found,
+    /// All the blocks of the window were visited, down to the given height
+    Exhausted { lowest: u64 },
+}
+
+/// The heights of the window of `window` blocks ending at the given height, from the highest.
+fn scan_heights(from: u64, window: u64) -> Vec<u64> {
+    let lowest = from.saturating_sub(window.saturating_sub(1)).max(1);
+
+    (lowest..=from).rev().collect_vec()
+}
+
+/// Visits the blocks at the given heights in order, until `visit` returns `true`,
+/// fetching up to [`MAX_CONCURRENT_FETCHES`] blocks at a time.
+async fn scan_blocks<F, Fut>(
+    heights: &[u64],
+    fetch: F,
+    mut visit: impl FnMut(&ScannedBlock) -> bool,
+) -> Result<ScanOutcome, Error>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = Result<Arc<ScannedBlock>, Error>>,
+{
+    for chunk in heights.chunks(MAX_CONCURRENT_FETCHES) {
+        let blocks = try_join_all(chunk.iter().map(|height| fetch(*height))).await?;
+
+        for block in blocks {
+            if visit(&block) {
+                return Ok(ScanOutcome::Found);
+            }
+        }
+    }
+
+    Ok(ScanOutcome::Exhausted {
+        lowest: heights.last().copied().unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    fn block(height: u64) -> Arc<ScannedBlock> {
+        Arc::new(ScannedBlock {
+            height: TmHeight::try_from(height).unwrap(),
+            begin_block_events: vec![],
+            txs: vec![],
+            end_block_events: vec![],
+        })
+    }
+
+    #[test]
+    fn scan_heights_window() {
+        assert_eq!(scan_heights(1000, 3), vec![1000, 999, 998]);
+        assert_eq!(scan_heights(1000, 1), vec![1000]);
+        assert_eq!(scan_heights(1000, 500).len(), 500);
+        assert_eq!(scan_heights(1000, 500).last(), Some(&501));
+    }
+
+    #[test]
+    fn scan_heights_window_stops_at_first_block() {
+        assert_eq!(scan_heights(3, 500), vec![3, 2, 1]);
+        assert_eq!(scan_heights(1, 500), vec![1]);
+    }
+
+    #[test]
+    fn scan_stops_when_found() {
+        let fetched = Mutex::new(vec![]);
+
+        let outcome = futures::executor::block_on(scan_blocks(
+            &scan_heights(100, 50),
+            |height| {
+                fetched.lock().unwrap().push(height);
+                async move { Ok(block(height)) }
+            },
+            |block| block.height.value() == 85,
+        ))
+        .unwrap();
+
+        assert_eq!(outcome, ScanOutcome::Found);
+
+        // The blocks are fetched in chunks, hence the whole chunk of the block found
+        let fetched = fetched.into_inner().unwrap();
+        assert_eq!(fetched.len(), 2 * MAX_CONCURRENT_FETCHES);
+        assert_eq!(fetched.first(), Some(&100));
+        assert_eq!(fetched.last(), Some(&81));
+    }
+
+    #[test]
+    fn scan_is_exhausted_at_window_boundary() {
+        let mut visited = vec![];
+
+        let outcome = futures::executor::block_on(scan_blocks(
+            &scan_heights(100, 25),
+            |height| async move { Ok(block(height)) },
+            |block| {
+                visited.push(block.height.value());
+                // Below the window
+                block.height.value() == 75
+            },
+        ))
+        .unwrap();
+
+        assert_eq!(outcome, ScanOutcome::Exhausted { lowest: 76 });
+        assert_eq!(visited, (76..=100).rev().collect_vec());
+    }
+
+    #[test]
+    fn scan_fails_if_a_block_cannot_be_fetched() {
+        let outcome = futures::executor::block_on(scan_blocks(
+            &scan_heights(100, 50),
+            |height| async move {
+                if height == 90 {
+                    Err(Error::invalid_height_no_source())
+                } else {
+                    Ok(block(height))
+                }
+            },
+            |_| false,
+        ));
+
+        assert!(outcome.is_err());
+    }
+}
@@ -3,6 +3,7 @@ use tracing::{debug, warn};
 
 use ibc_relayer_types::{core::ics24_host::identifier::ChainId, Height};
 
+use crate::chain::cosmos::endpoint::track_request;
 use crate::chain::requests::{QueryConsensusStateHeightsRequest, QueryConsensusStatesRequest};
 use crate::config::default::max_grpc_decoding_size;
 use crate::consensus_state::AnyConsensusStateWithHeight;
@@ -42,7 +43,12 @@ pub async fn query_consensus_state_heights(
     client = client.max_decoding_message_size(max_grpc_decoding_size().get_bytes() as usize);
 
     let grpc_request = tonic::Request::new(request.clone().into());
-    let grpc_response = client.consensus_state_heights(grpc_request).await;
+    let grpc_response = track_request(
+        chain_id,
+        grpc_addr,
+        client.consensus_state_heights(grpc_request),
+    )
+    .await;
 
     if let Err(ref e) = grpc_response {
         if is_unsupported(e) {
@@ -108,11 +114,14 @@ pub async fn query_consensus_states(
 
     client = client.max_decoding_message_size(max_grpc_decoding_size().get_bytes() as usize);
 
-    let response = client
-        .consensus_states(tonic::Request::new(request.into()))
-        .await
-        .map_err(|e| Error::grpc_status(e, "query_consensus_states".to_owned()))?
-        .into_inner();
+    let response = track_request(
+        chain_id,
+        grpc_addr,
+        client.consensus_states(tonic::Request::new(request.into())),
+    )
+    .await
+    .map_err(|e| Error::grpc_status(e, "query_consensus_states".to_owned()))?
+    .into_inner();
 
     let mut consensus_states: Vec<_> = response
         .consensus_states
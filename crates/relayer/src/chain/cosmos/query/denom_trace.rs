@@ -3,13 +3,19 @@ use http::uri::Uri;
 use ibc_proto::ibc::applications::transfer::v1::{
     query_client::QueryClient, QueryDenomTraceRequest,
 };
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 
+use crate::chain::cosmos::endpoint::track_request;
 use crate::config::default::max_grpc_decoding_size;
 use crate::denom::DenomTrace;
 use crate::error::Error;
 
 // Uses the GRPC client to retrieve the denom trace for a specific hash
-pub async fn query_denom_trace(grpc_address: &Uri, hash: &str) -> Result<DenomTrace, Error> {
+pub async fn query_denom_trace(
+    chain_id: &ChainId,
+    grpc_address: &Uri,
+    hash: &str,
+) -> Result<DenomTrace, Error> {
     let mut client = QueryClient::connect(grpc_address.clone())
         .await
         .map_err(Error::grpc_transport)?;
@@ -20,8 +26,7 @@ pub async fn query_denom_trace(grpc_address: &Uri, hash: &str) -> Result<DenomTr
         hash: hash.to_owned(),
     });
 
-    let response = client
-        .denom_trace(request)
+    let response = track_request(chain_id, grpc_address, client.denom_trace(request))
         .await
         .map(|r| r.into_inner())
         .map_err(|e| Error::grpc_status(e, "query_denom_trace".to_owned()))?;
@@ -7,14 +7,16 @@ use ibc_proto::ibc::apps::fee::v1::{
     QueryIncentivizedPacketRequest, QueryIncentivizedPacketResponse,
 };
 use ibc_relayer_types::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
-use ibc_relayer_types::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc_relayer_types::signer::Signer;
 use tonic::Code;
 
+use crate::chain::cosmos::endpoint::track_request;
 use crate::config::default::max_grpc_decoding_size;
 use crate::error::Error;
 
 pub async fn query_counterparty_payee(
+    chain_id: &ChainId,
     grpc_address: &Uri,
     channel_id: &ChannelId,
     address: &Signer,
@@ -30,7 +32,7 @@ pub async fn query_counterparty_payee(
         relayer: address.to_string(),
     };
 
-    let result = client.counterparty_payee(request).await;
+    let result = track_request(chain_id, grpc_address, client.counterparty_payee(request)).await;
 
     match result {
         Ok(response) => {
@@ -49,6 +51,7 @@ pub async fn query_counterparty_payee(
 }
 
 pub async fn query_incentivized_packets(
+    chain_id: &ChainId,
     grpc_address: &Uri,
     channel_id: &ChannelId,
     port_id: &PortId,
@@ -66,10 +69,13 @@ pub async fn query_incentivized_packets(
         query_height: 0,
     };
 
-    let response = client
-        .incentivized_packets_for_channel(request)
-        .await
-        .map_err(|e| Error::grpc_status(e, "query_incentivized_packets".to_owned()))?;
+    let response = track_request(
+        chain_id,
+        grpc_address,
+        client.incentivized_packets_for_channel(request),
+    )
+    .await
+    .map_err(|e| Error::grpc_status(e, "query_incentivized_packets".to_owned()))?;
 
     let raw_packets = response.into_inner().incentivized_packets;
 
@@ -84,6 +90,7 @@ pub async fn query_incentivized_packets(
 
 /// Query the incentivized packet for a specific packet at a specific height.
 pub async fn query_incentivized_packet(
+    chain_id: &ChainId,
     grpc_address: &Uri,
     request: QueryIncentivizedPacketRequest,
 ) -> Result<QueryIncentivizedPacketResponse, Error> {
@@ -93,10 +100,13 @@ pub async fn query_incentivized_packet(
 
     client = client.max_decoding_message_size(max_grpc_decoding_size().get_bytes() as usize);
 
-    let response = client
-        .incentivized_packet(tonic::Request::new(request))
-        .await
-        .map_err(|e| Error::grpc_status(e, "query_incentivized_packet".to_owned()))?;
+    let response = track_request(
+        chain_id,
+        grpc_address,
+        client.incentivized_packet(tonic::Request::new(request)),
+    )
+    .await
+    .map_err(|e| Error::grpc_status(e, "query_incentivized_packet".to_owned()))?;
 
     Ok(response.into_inner())
 }
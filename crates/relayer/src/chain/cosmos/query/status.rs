@@ -2,6 +2,7 @@ use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 use ibc_relayer_types::Height;
 use tendermint_rpc::{Client, HttpClient, Url};
 
+use crate::chain::cosmos::endpoint::track_request;
 use crate::chain::endpoint::ChainStatus;
 use crate::error::Error;
 
@@ -14,8 +15,7 @@ pub async fn query_status(
     rpc_client: &HttpClient,
     rpc_address: &Url,
 ) -> Result<ChainStatus, Error> {
-    let response = rpc_client
-        .status()
+    let response = track_request(chain_id, rpc_address, rpc_client.status())
         .await
         .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
 
@@ -9,6 +9,10 @@ use tendermint_rpc::endpoint::tx::Response as TxResponse;
 use tendermint_rpc::{Client, HttpClient, Order, Url};
 use tracing::warn;
 
+use crate::chain::cosmos::compatibility::BlockResultsLayout;
+use crate::chain::cosmos::endpoint::track_request;
+use crate::chain::cosmos::query::block_results::fetch_block_results;
+use crate::chain::cosmos::query::block_scan::BlockScanner;
 use crate::chain::cosmos::query::{header_query, packet_query, tx_hash_query};
 use crate::chain::cosmos::types::events;
 use crate::chain::requests::{
@@ -20,10 +24,14 @@ use crate::event::{ibc_event_try_from_abci_event, IbcEventWithHeight};
 /// This function queries transactions for events matching certain criteria.
 /// 1. Client Update request - returns a vector with at most one update client event
 /// 2. Transaction event request - returns all IBC events resulted from a Tx execution
+///
+/// If a [`BlockScanner`] is given, ie. if the node does not index transactions,
+/// the latest blocks are scanned instead of relying on `tx_search`.
 pub async fn query_txs(
     chain_id: &ChainId,
     rpc_client: &HttpClient,
     rpc_address: &Url,
+    block_scanner: Option<&BlockScanner>,
     request: QueryTxRequest,
 ) -> Result<Vec<IbcEventWithHeight>, Error> {
     crate::time!("query_txs",
@@ -41,22 +49,30 @@ pub async fn query_txs(
                 }
             );
 
+            if let Some(block_scanner) = block_scanner {
+                let event = block_scanner.find_update_client(&request).await?;
+                return Ok(event.into_iter().collect());
+            }
+
             // query the first Tx that includes the event matching the client request
             // Note: it is possible to have multiple Tx-es for same client and consensus height.
             // In this case it must be true that the client updates were performed with tha
             // same header as the first one, otherwise a subsequent transaction would have
             // failed on chain. Therefore only one Tx is of interest and current API returns
             // the first one.
-            let mut response = rpc_client
-                .tx_search(
+            let mut response = track_request(
+                chain_id,
+                rpc_address,
+                rpc_client.tx_search(
                     header_query(&request),
                     false,
                     1,
                     1, // get only the first Tx matching the query
                     Order::Ascending,
-                )
-                .await
-                .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+                ),
+            )
+            .await
+            .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
 
             if response.txs.is_empty() {
                 return Ok(vec![]);
@@ -75,16 +91,27 @@ pub async fn query_txs(
         }
 
         QueryTxRequest::Transaction(tx) => {
-            let mut response = rpc_client
-                .tx_search(
+            if let Some(block_scanner) = block_scanner {
+                let response = block_scanner.find_tx(&tx.0).await?;
+
+                return Ok(response
+                    .map(|tx| all_ibc_events_from_tx_search_response(chain_id, tx))
+                    .unwrap_or_default());
+            }
+
+            let mut response = track_request(
+                chain_id,
+                rpc_address,
+                rpc_client.tx_search(
                     tx_hash_query(&tx),
                     false,
                     1,
                     1, // get only the first Tx matching the query
                     Order::Ascending,
-                )
-                .await
-                .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+                ),
+            )
+            .await
+            .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
 
             if response.txs.is_empty() {
                 Ok(vec![])
@@ -124,10 +151,13 @@ pub async fn query_packets_from_txs(
 
     for seq in &request.sequences {
         // Query the latest 10 txs which include the event specified in the query request
-        let response = rpc_client
-            .tx_search(packet_query(request, *seq), false, 1, 10, Order::Descending)
-            .await
-            .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+        let response = track_request(
+            chain_id,
+            rpc_address,
+            rpc_client.tx_search(packet_query(request, *seq), false, 1, 10, Order::Descending),
+        )
+        .await
+        .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
 
         if response.txs.is_empty() {
             continue;
@@ -173,6 +203,7 @@ pub async fn query_packets_from_block(
     chain_id: &ChainId,
     rpc_client: &HttpClient,
     rpc_address: &Url,
+    block_results_layout: BlockResultsLayout,
     request: &QueryPacketEventDataRequest,
 ) -> Result<Vec<IbcEventWithHeight>, Error> {
     crate::time!(
@@ -193,32 +224,32 @@ pub async fn query_packets_from_block(
     let height = Height::new(chain_id.version(), u64::from(tm_height))
         .map_err(|_| Error::invalid_height_no_source())?;
 
-    let block_results = rpc_client
-        .block_results(tm_height)
-        .await
-        .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+    let block_results = track_request(
+        chain_id,
+        rpc_address,
+        fetch_block_results(rpc_client, block_results_layout, tm_height),
+    )
+    .await
+    .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
 
     let mut tx_events = vec![];
     let mut begin_block_events = vec![];
     let mut end_block_events = vec![];
 
-    if let Some(txs) = block_results.txs_results {
-        for tx in txs {
-            tx_events.append(
-                &mut tx
-                    .events
-                    .iter()
-                    .filter_map(|ev| filter_matching_event(ev, request, &request.sequences))
-                    .map(|ev| IbcEventWithHeight::new(ev, height))
-                    .collect(),
-            )
-        }
+    for tx in block_results.txs_results {
+        tx_events.append(
+            &mut tx
+                .events
+                .iter()
+                .filter_map(|ev| filter_matching_event(ev, request, &request.sequences))
+                .map(|ev| IbcEventWithHeight::new(ev, height))
+                .collect(),
+        )
     }
 
     begin_block_events.append(
         &mut block_results
             .begin_block_events
-            .unwrap_or_default()
             .iter()
             .filter_map(|ev| filter_matching_event(ev, request, &request.sequences))
             .map(|ev| IbcEventWithHeight::new(ev, height))
@@ -228,7 +259,6 @@ pub async fn query_packets_from_block(
     end_block_events.append(
         &mut block_results
             .end_block_events
-            .unwrap_or_default()
             .iter()
             .filter_map(|ev| filter_matching_event(ev, request, &request.sequences))
             .map(|ev| IbcEventWithHeight::new(ev, height))
@@ -250,7 +280,7 @@ pub async fn query_packets_from_block(
 // for client Y at consensus height H'. This is the reason the code iterates all event fields in the
 // returned Tx to retrieve the relevant ones.
 // Returns `None` if no matching event was found.
-fn update_client_from_tx_search_response(
+pub(super) fn update_client_from_tx_search_response(
     chain_id: &ChainId,
     request: &QueryClientEventRequest,
     response: TxResponse,
@@ -353,20 +383,29 @@ pub fn filter_matching_event(
 }
 
 pub async fn query_tx_response(
+    chain_id: &ChainId,
     rpc_client: &HttpClient,
     rpc_address: &Url,
+    block_scanner: Option<&BlockScanner>,
     tx_hash: &TxHash,
 ) -> Result<Option<TxResponse>, Error> {
-    let response = rpc_client
-        .tx_search(
+    if let Some(block_scanner) = block_scanner {
+        return block_scanner.find_tx(tx_hash).await;
+    }
+
+    let response = track_request(
+        chain_id,
+        rpc_address,
+        rpc_client.tx_search(
             tx_hash_query(&QueryTxHash(*tx_hash)),
             false,
             1,
             1, // get only the first Tx matching the query
             Order::Ascending,
-        )
-        .await
-        .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+        ),
+    )
+    .await
+    .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
 
     Ok(response.txs.into_iter().next())
 }
@@ -1,11 +1,17 @@
 use ibc_proto::cosmos::tx::v1beta1::service_client::ServiceClient;
 use ibc_proto::cosmos::tx::v1beta1::{SimulateRequest, SimulateResponse, Tx};
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 use tonic::codegen::http::Uri;
 
+use crate::chain::cosmos::endpoint::track_request;
 use crate::config::default::max_grpc_decoding_size;
 use crate::error::Error;
 
-pub async fn send_tx_simulate(grpc_address: &Uri, tx: Tx) -> Result<SimulateResponse, Error> {
+pub async fn send_tx_simulate(
+    chain_id: &ChainId,
+    grpc_address: &Uri,
+    tx: Tx,
+) -> Result<SimulateResponse, Error> {
     let mut tx_bytes = vec![];
     prost::Message::encode(&tx, &mut tx_bytes)
         .map_err(|e| Error::protobuf_encode(String::from("Transaction"), e))?;
@@ -22,8 +28,7 @@ pub async fn send_tx_simulate(grpc_address: &Uri, tx: Tx) -> Result<SimulateResp
     client = client.max_decoding_message_size(max_grpc_decoding_size().get_bytes() as usize);
 
     let request = tonic::Request::new(req);
-    let response = client
-        .simulate(request)
+    let response = track_request(chain_id, grpc_address, client.simulate(request))
         .await
         .map_err(|e| Error::grpc_status(e, "send_tx_simulate".to_owned()))?
         .into_inner();
@@ -1,10 +1,15 @@
+use futures::stream::{FuturesUnordered, StreamExt};
 use ibc_proto::cosmos::tx::v1beta1::Fee;
 use ibc_proto::google::protobuf::Any;
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 use ibc_relayer_types::events::IbcEvent;
 use tendermint_rpc::endpoint::broadcast::tx_sync::Response;
 use tendermint_rpc::{Client, HttpClient, Url};
+use tracing::{debug, warn};
 
+use crate::accounting::account_for_submitted_tx;
 use crate::chain::cosmos::encode::sign_and_encode_tx;
+use crate::chain::cosmos::endpoint::track_request;
 use crate::chain::cosmos::estimate::estimate_tx_fees;
 use crate::chain::cosmos::query::account::query_account;
 use crate::chain::cosmos::query::tx::all_ibc_events_from_tx_search_response;
@@ -45,25 +50,150 @@ async fn send_tx_with_fee(
 ) -> Result<Response, Error> {
     let tx_bytes = sign_and_encode_tx(config, key_pair, account, tx_memo, messages, fee)?;
 
-    let response = broadcast_tx_sync(rpc_client, &config.rpc_address, tx_bytes).await?;
+    let response = if config.broadcast_addresses.is_empty() {
+        broadcast_tx_sync(
+            &config.chain_id,
+            rpc_client,
+            &config.rpc_address,
+            tx_bytes.clone(),
+        )
+        .await?
+    } else {
+        broadcast_tx_sync_to_all(rpc_client, config, tx_bytes.clone()).await?
+    };
+
+    if response.code.is_ok() {
+        account_for_submitted_tx(
+            &config.chain_id,
+            &key_pair.account(),
+            &tx_bytes,
+            &response.hash,
+        );
+    }
 
     Ok(response)
 }
 
 /// Perform a `broadcast_tx_sync`, and return the corresponding deserialized response data.
 pub async fn broadcast_tx_sync(
+    chain_id: &ChainId,
     rpc_client: &HttpClient,
     rpc_address: &Url,
     data: Vec<u8>,
 ) -> Result<Response, Error> {
-    let response = rpc_client
-        .broadcast_tx_sync(data)
+    let response = track_request(chain_id, rpc_address, rpc_client.broadcast_tx_sync(data))
         .await
         .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
 
     Ok(response)
 }
 
+/**
+ Perform a `broadcast_tx_sync` concurrently against the chain's RPC node
+ and all of the configured `broadcast_addresses`.
+
+ - The first response whose CheckTx succeeded is returned, without waiting
+   for the remaining nodes. Their results are still logged once they arrive.
+ - If no node accepted the transaction, the first CheckTx response received
+   is returned, so that the caller can handle the error code as usual.
+ - If no node replied at all, the error of the primary RPC node is returned.
+*/
+pub async fn broadcast_tx_sync_to_all(
+    rpc_client: &HttpClient,
+    config: &TxConfig,
+    data: Vec<u8>,
+) -> Result<Response, Error> {
+    let mut nodes = vec![(config.rpc_address.clone(), rpc_client.clone())];
+
+    for address in &config.broadcast_addresses {
+        match HttpClient::new(address.clone()) {
+            Ok(client) => nodes.push((address.clone(), client)),
+            Err(e) => warn!(
+                chain = %config.chain_id,
+                %address,
+                "failed to create RPC client for broadcast node, skipping it: {e}"
+            ),
+        }
+    }
+
+    let mut broadcasts = nodes
+        .into_iter()
+        .map(|(address, client)| {
+            let chain_id = config.chain_id.clone();
+            let data = data.clone();
+            async move {
+                let result = broadcast_tx_sync(&chain_id, &client, &address, data).await;
+                (address, result)
+            }
+        })
+        .collect::<FuturesUnordered<_>>();
+
+    let mut check_tx_failure = None;
+    let mut rpc_error = None;
+
+    while let Some((address, result)) = broadcasts.next().await {
+        log_broadcast_result(&config.chain_id, &address, &result);
+
+        match result {
+            Ok(response) if response.code.is_ok() => {
+                // Let the pending broadcasts run to completion in the background,
+                // so that the outcome for every node gets recorded.
+                if !broadcasts.is_empty() {
+                    let chain_id = config.chain_id.clone();
+
+                    tokio::spawn(async move {
+                        while let Some((address, result)) = broadcasts.next().await {
+                            log_broadcast_result(&chain_id, &address, &result);
+                        }
+                    });
+                }
+
+                return Ok(response);
+            }
+            Ok(response) => {
+                check_tx_failure.get_or_insert(response);
+            }
+            Err(e) => {
+                // Prefer reporting the error of the primary RPC node
+                if rpc_error.is_none() || address == config.rpc_address {
+                    rpc_error = Some(e);
+                }
+            }
+        }
+    }
+
+    match (check_tx_failure, rpc_error) {
+        (Some(response), _) => Ok(response),
+        (None, Some(e)) => Err(e),
+        // The primary RPC node is always broadcast to, so there is at least one result
+        (None, None) => unreachable!(),
+    }
+}
+
+fn log_broadcast_result(chain_id: &ChainId, address: &Url, result: &Result<Response, Error>) {
+    match result {
+        Ok(response) if response.code.is_ok() => debug!(
+            chain = %chain_id,
+            %address,
+            tx_hash = %response.hash,
+            "broadcast_tx_sync to node succeeded"
+        ),
+        Ok(response) => warn!(
+            chain = %chain_id,
+            %address,
+            tx_hash = %response.hash,
+            code = ?response.code,
+            log = %response.log,
+            "broadcast_tx_sync to node failed at CheckTx"
+        ),
+        Err(e) => warn!(
+            chain = %chain_id,
+            %address,
+            "broadcast_tx_sync to node failed: {e}"
+        ),
+    }
+}
+
 /**
  A simplified version of send_tx that does not depend on `ChainHandle`.
 
@@ -83,7 +213,7 @@ pub async fn simple_send_tx(
     messages: Vec<Any>,
 ) -> Result<Vec<IbcEventWithHeight>, Error> {
     let key_account = key_pair.account();
-    let account = query_account(&config.grpc_address, &key_account)
+    let account = query_account(&config.chain_id, &config.grpc_address, &key_account)
         .await?
         .into();
 
@@ -102,8 +232,10 @@ pub async fn simple_send_tx(
     }
 
     let response = wait_tx_succeed(
+        &config.chain_id,
         rpc_client,
         &config.rpc_address,
+        config.block_scanner.as_ref(),
         &config.rpc_timeout,
         &response.hash,
     )
@@ -121,7 +253,7 @@ pub async fn batched_send_tx(
     messages: Vec<Any>,
 ) -> Result<Vec<IbcEventWithHeight>, Error> {
     let key_account = key_pair.account();
-    let mut account = query_account(&config.grpc_address, &key_account)
+    let mut account = query_account(&config.chain_id, &config.grpc_address, &key_account)
         .await?
         .into();
 
@@ -143,3 +275,129 @@ pub async fn batched_send_tx(
 
     Ok(events)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use tendermint_rpc::{HttpClient, Url};
+
+    use super::broadcast_tx_sync_to_all;
+    use crate::chain::cosmos::types::config::TxConfig;
+    use crate::config;
+    use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+
+    const TX_HASH: &str = "7E4F5B3B7E1C1F5C6B8A7A5D0C1F0E2F3A4B5C6D7E8F9A0B1C2D3E4F5A6B7C8D";
+
+    /// Spawn a mock RPC node which answers every `broadcast_tx_sync`
+    /// request with the given CheckTx code, and return its address.
+    fn spawn_mock_rpc_node(code: u32) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let response = format!(
+                    r#"{{"jsonrpc":"2.0","id":"","result":{{"code":{code},"data":"","log":"","codespace":"","hash":"{TX_HASH}"}}}}"#
+                );
+
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+            }
+        });
+
+        format!("http://{address}").parse().unwrap()
+    }
+
+    /// Return the address of a port on which nothing is listening.
+    fn unreachable_rpc_node() -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        format!("http://{address}").parse().unwrap()
+    }
+
+    fn tx_config(rpc_address: Url, broadcast_addresses: Vec<Url>) -> TxConfig {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/config/fixtures/relayer_conf_example.toml"
+        );
+        let config = config::load(path).expect("could not parse config");
+        let chain_id = ChainId::from_string("chain_A");
+        let chain_config = config.find_chain(&chain_id).unwrap();
+
+        let mut tx_config = TxConfig::try_from(chain_config).expect("could not obtain tx config");
+        tx_config.rpc_address = rpc_address;
+        tx_config.broadcast_addresses = broadcast_addresses;
+        tx_config
+    }
+
+    fn broadcast(config: &TxConfig) -> tendermint_rpc::endpoint::broadcast::tx_sync::Response {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let rpc_client = HttpClient::new(config.rpc_address.clone()).unwrap();
+
+        rt.block_on(broadcast_tx_sync_to_all(&rpc_client, config, vec![1, 2, 3]))
+            .expect("broadcast should succeed")
+    }
+
+    #[test]
+    fn broadcast_accepts_first_successful_check_tx() {
+        let config = tx_config(
+            spawn_mock_rpc_node(5),
+            vec![spawn_mock_rpc_node(5), spawn_mock_rpc_node(0)],
+        );
+
+        let response = broadcast(&config);
+
+        assert!(response.code.is_ok());
+        assert_eq!(response.hash.to_string(), TX_HASH);
+    }
+
+    #[test]
+    fn broadcast_succeeds_when_primary_node_is_down() {
+        let config = tx_config(unreachable_rpc_node(), vec![spawn_mock_rpc_node(0)]);
+
+        let response = broadcast(&config);
+
+        assert!(response.code.is_ok());
+    }
+
+    #[test]
+    fn broadcast_returns_check_tx_failure_when_no_node_accepts() {
+        let config = tx_config(
+            spawn_mock_rpc_node(32),
+            vec![unreachable_rpc_node(), spawn_mock_rpc_node(32)],
+        );
+
+        let response = broadcast(&config);
+
+        assert_eq!(response.code.value(), 32);
+    }
+}
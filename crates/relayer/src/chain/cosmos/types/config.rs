@@ -5,6 +5,7 @@ use ibc_proto::google::protobuf::Any;
 use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 use tendermint_rpc::Url;
 
+use crate::chain::cosmos::query::block_scan::BlockScanner;
 use crate::chain::cosmos::types::gas::GasConfig;
 use crate::config::types::{MaxMsgNum, MaxTxSize};
 use crate::config::{AddressType, ChainConfig};
@@ -15,12 +16,15 @@ pub struct TxConfig {
     pub chain_id: ChainId,
     pub gas_config: GasConfig,
     pub rpc_address: Url,
+    pub broadcast_addresses: Vec<Url>,
     pub grpc_address: Uri,
     pub rpc_timeout: Duration,
     pub address_type: AddressType,
     pub max_msg_num: MaxMsgNum,
     pub max_tx_size: MaxTxSize,
     pub extension_options: Vec<Any>,
+    /// Used to look for the committed transactions when the node does not index them
+    pub block_scanner: Option<BlockScanner>,
 }
 
 impl<'a> TryFrom<&'a ChainConfig> for TxConfig {
@@ -42,12 +46,14 @@ impl<'a> TryFrom<&'a ChainConfig> for TxConfig {
             chain_id: config.id.clone(),
             gas_config,
             rpc_address: config.rpc_addr.clone(),
+            broadcast_addresses: config.broadcast_addrs.clone(),
             grpc_address,
             rpc_timeout: config.rpc_timeout,
             address_type: config.address_type.clone(),
             max_msg_num: config.max_msg_num,
             max_tx_size: config.max_tx_size,
             extension_options,
+            block_scanner: None,
         })
     }
 }
@@ -11,6 +11,8 @@ use tendermint_rpc::{HttpClient, Url};
 use tokio::time::sleep;
 use tracing::{debug, debug_span, trace};
 
+use crate::accounting::account_for_tx;
+use crate::chain::cosmos::query::block_scan::BlockScanner;
 use crate::chain::cosmos::query::tx::query_tx_response;
 use crate::chain::cosmos::types::events::from_tx_response_event;
 use crate::chain::cosmos::types::tx::{TxStatus, TxSyncResult};
@@ -24,8 +26,10 @@ const WAIT_BACKOFF: Duration = Duration::from_millis(300);
 /// with the transaction hashes to get the list of IbcEvents included in those transactions.
 pub async fn wait_for_block_commits(
     chain_id: &ChainId,
+    wallet: &str,
     rpc_client: &HttpClient,
     rpc_address: &Url,
+    block_scanner: Option<&BlockScanner>,
     rpc_timeout: &Duration,
     tx_sync_results: &mut [TxSyncResult],
 ) -> Result<(), Error> {
@@ -68,8 +72,15 @@ pub async fn wait_for_block_commits(
             thread::sleep(WAIT_BACKOFF);
 
             for tx_sync_result in tx_sync_results.iter_mut() {
-                let res =
-                    update_tx_sync_result(chain_id, rpc_client, rpc_address, tx_sync_result).await;
+                let res = update_tx_sync_result(
+                    chain_id,
+                    wallet,
+                    rpc_client,
+                    rpc_address,
+                    block_scanner,
+                    tx_sync_result,
+                )
+                .await;
                 if let Err(e) = res {
                     debug!("update_tx_sync_result failed: {e}");
                 }
@@ -80,17 +91,27 @@ pub async fn wait_for_block_commits(
 
 async fn update_tx_sync_result(
     chain_id: &ChainId,
+    wallet: &str,
     rpc_client: &HttpClient,
     rpc_address: &Url,
+    block_scanner: Option<&BlockScanner>,
     tx_sync_result: &mut TxSyncResult,
 ) -> Result<(), Error> {
     if let TxStatus::Pending { message_count } = tx_sync_result.status {
-        let response =
-            query_tx_response(rpc_client, rpc_address, &tx_sync_result.response.hash).await?;
+        let response = query_tx_response(
+            chain_id,
+            rpc_client,
+            rpc_address,
+            block_scanner,
+            &tx_sync_result.response.hash,
+        )
+        .await?;
 
         if let Some(response) = response {
             tx_sync_result.status = TxStatus::ReceivedResponse;
 
+            account_for_tx(chain_id, wallet, &response);
+
             let height = Height::new(chain_id.version(), u64::from(response.height)).unwrap();
             if response.tx_result.code.is_err() {
                 tx_sync_result.events = vec![
@@ -124,12 +145,22 @@ fn all_tx_results_found(tx_sync_results: &[TxSyncResult]) -> bool {
 }
 
 pub async fn wait_tx_succeed(
+    chain_id: &ChainId,
     rpc_client: &HttpClient,
     rpc_address: &Url,
+    block_scanner: Option<&BlockScanner>,
     timeout: &Duration,
     tx_hash: &TxHash,
 ) -> Result<TxResponse, Error> {
-    let response = wait_tx_hash(rpc_client, rpc_address, timeout, tx_hash).await?;
+    let response = wait_tx_hash(
+        chain_id,
+        rpc_client,
+        rpc_address,
+        block_scanner,
+        timeout,
+        tx_hash,
+    )
+    .await?;
 
     let response_code = response.tx_result.code;
     if response_code.is_err() {
@@ -140,15 +171,18 @@ pub async fn wait_tx_succeed(
 }
 
 pub async fn wait_tx_hash(
+    chain_id: &ChainId,
     rpc_client: &HttpClient,
     rpc_address: &Url,
+    block_scanner: Option<&BlockScanner>,
     timeout: &Duration,
     tx_hash: &TxHash,
 ) -> Result<TxResponse, Error> {
     let start_time = Instant::now();
 
     loop {
-        let response = query_tx_response(rpc_client, rpc_address, tx_hash).await?;
+        let response =
+            query_tx_response(chain_id, rpc_client, rpc_address, block_scanner, tx_hash).await?;
 
         match response {
             None => {
@@ -1,6 +1,8 @@
+use alloc::sync::Arc;
 use core::fmt::{Display, Error as FmtError, Formatter};
 use crossbeam_channel as channel;
-use tracing::Span;
+use once_cell::sync::OnceCell;
+use tracing::{warn, Span};
 
 use ibc_proto::ibc::apps::fee::v1::QueryIncentivizedPacketRequest;
 use ibc_proto::ibc::apps::fee::v1::QueryIncentivizedPacketResponse;
@@ -45,14 +47,17 @@ use crate::telemetry;
 #[derive(Debug, Clone)]
 pub struct CachingChainHandle<Handle> {
     inner: Handle,
-    cache: Cache,
+    /// Shared by the clones of the handle, and initialized on first use with the
+    /// `[chains.cache]` configuration of the chain, as the chain runtime does not
+    /// yet serve requests when the handle is created.
+    cache: Arc<OnceCell<Cache>>,
 }
 
 impl<Handle> CachingChainHandle<Handle> {
     pub fn new(handle: Handle) -> Self {
         Self {
             inner: handle,
-            cache: Cache::new(),
+            cache: Arc::new(OnceCell::new()),
         }
     }
 
@@ -61,6 +66,36 @@ impl<Handle> CachingChainHandle<Handle> {
     }
 }
 
+impl<Handle: ChainHandle> CachingChainHandle<Handle> {
+    fn cache(&self) -> &Cache {
+        self.cache.get_or_init(|| {
+            let config = self.inner().config().map(|config| config.cache);
+
+            let config = config.unwrap_or_else(|e| {
+                warn!(
+                    chain = %self.id(),
+                    "failed to get the cache configuration of the chain, using the defaults: {e}"
+                );
+                Default::default()
+            });
+
+            Cache::new(self.id(), &config)
+        })
+    }
+
+    #[cfg_attr(not(feature = "telemetry"), allow(unused_variables))]
+    fn record_cache_status(&self, status: CacheStatus, query_type: &'static str) {
+        match status {
+            CacheStatus::Hit => {
+                telemetry!(queries_cache_hits, &self.id(), query_type);
+            }
+            CacheStatus::Miss => {
+                telemetry!(queries_cache_misses, &self.id(), query_type);
+            }
+        }
+    }
+}
+
 impl<Handle: ChainHandle> Display for CachingChainHandle<Handle> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         write!(
@@ -149,12 +184,10 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
     fn query_latest_height(&self) -> Result<Height, Error> {
         let handle = self.inner();
         let (result, in_cache) = self
-            .cache
+            .cache()
             .get_or_try_update_latest_height_with(|| handle.query_latest_height())?;
 
-        if in_cache == CacheStatus::Hit {
-            telemetry!(queries_cache_hits, &self.id(), "query_latest_height");
-        }
+        self.record_cache_status(in_cache, "query_latest_height");
 
         Ok(result)
     }
@@ -177,7 +210,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
             IncludeProof::Yes => handle.query_client_state(request, IncludeProof::Yes),
             IncludeProof::No => {
                 if matches!(request.height, QueryHeight::Latest) {
-                    let (result, in_cache) = self.cache.get_or_try_insert_client_state_with(
+                    let (result, in_cache) = self.cache().get_or_try_insert_client_state_with(
                         &request.client_id,
                         || {
                             handle
@@ -186,9 +219,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
                         },
                     )?;
 
-                    if in_cache == CacheStatus::Hit {
-                        telemetry!(queries_cache_hits, &self.id(), "query_client_state");
-                    }
+                    self.record_cache_status(in_cache, "query_client_state");
 
                     Ok((result, None))
                 } else {
@@ -252,7 +283,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
             IncludeProof::Yes => handle.query_connection(request, IncludeProof::Yes),
             IncludeProof::No => {
                 if matches!(request.height, QueryHeight::Latest) {
-                    let (result, in_cache) = self.cache.get_or_try_insert_connection_with(
+                    let (result, in_cache) = self.cache().get_or_try_insert_connection_with(
                         &request.connection_id,
                         || {
                             handle
@@ -261,9 +292,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
                         },
                     )?;
 
-                    if in_cache == CacheStatus::Hit {
-                        telemetry!(queries_cache_hits, &self.id(), "query_connection");
-                    }
+                    self.record_cache_status(in_cache, "query_connection");
 
                     Ok((result, None))
                 } else {
@@ -313,7 +342,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
             IncludeProof::Yes => handle.query_channel(request, IncludeProof::Yes),
             IncludeProof::No => {
                 if matches!(request.height, QueryHeight::Latest) {
-                    let (result, in_cache) = self.cache.get_or_try_insert_channel_with(
+                    let (result, in_cache) = self.cache().get_or_try_insert_channel_with(
                         &PortChannelId::new(request.channel_id.clone(), request.port_id.clone()),
                         || {
                             handle
@@ -322,9 +351,7 @@ impl<Handle: ChainHandle> ChainHandle for CachingChainHandle<Handle> {
                         },
                     )?;
 
-                    if in_cache == CacheStatus::Hit {
-                        telemetry!(queries_cache_hits, &self.id(), "query_channel");
-                    }
+                    self.record_cache_status(in_cache, "query_channel");
 
                     Ok((result, None))
                 } else {
@@ -1,8 +1,17 @@
 use core::fmt::{Display, Error as FmtError, Formatter};
 
 use ibc_proto::google::protobuf::Any;
+use ibc_relayer_types::core::ics04_channel::packet::Sequence;
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use sha2::{Digest, Sha256};
+use tracing::Span;
 use uuid::Uuid;
 
+/// The target of the spans which follow an event batch along the relaying pipeline,
+/// from the events received to the transactions confirmed, and which are exported
+/// as OpenTelemetry traces when the `[traces]` section of the configuration is enabled.
+pub const RELAY_SPAN_TARGET: &str = "hermes::relay";
+
 /// Identifier used to track an `EventBatch` along
 /// the relaying pipeline until the corresponding
 /// transactions are submitted and/or confirmed.
@@ -32,6 +41,59 @@ impl TrackingId {
     pub fn new_cleared_uuid() -> Self {
         Self::ClearedUuid(Uuid::new_v4())
     }
+
+    /// The UUID of the event batch, if any.
+    pub fn uuid(&self) -> Option<Uuid> {
+        match self {
+            TrackingId::Uuid(u) | TrackingId::ClearedUuid(u) => Some(*u),
+            TrackingId::Static(_) => None,
+        }
+    }
+
+    /// Make the given span part of the trace of the event batch, if any,
+    /// whose identifier is the UUID of the batch.
+    #[cfg_attr(not(feature = "telemetry"), allow(unused_variables))]
+    pub fn trace(&self, span: Span) -> Span {
+        if let Some(uuid) = self.uuid() {
+            crate::telemetry!(ibc_telemetry::traces::set_trace_id(&span, uuid.as_u128()));
+        }
+
+        span
+    }
+}
+
+/// The identifier of the trace of a packet, derived from the chain, port and channel
+/// the packet was sent from, and its sequence.
+///
+/// The spans relaying a packet are part of the traces of the event batches they
+/// originate from, which differ for the relaying of the packet and the relaying of
+/// its acknowledgement, hence they are also linked to the trace of the packet,
+/// from which all of them can be found, see [`link_packet_traces`].
+pub fn packet_trace_id(
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    sequence: Sequence,
+) -> u128 {
+    let digest = Sha256::digest(format!("{chain_id}/{port_id}/{channel_id}/{sequence}"));
+
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&digest[..16]);
+
+    u128::from_be_bytes(bytes)
+}
+
+/// Link the given span to the traces of the packets with the given identifiers,
+/// as given by [`packet_trace_id`].
+#[cfg_attr(not(feature = "telemetry"), allow(unused_variables))]
+pub fn link_packet_traces(span: &Span, trace_ids: impl IntoIterator<Item = u128>) {
+    if span.is_disabled() {
+        return;
+    }
+
+    for trace_id in trace_ids {
+        crate::telemetry!(ibc_telemetry::traces::add_link(span, trace_id));
+    }
 }
 
 impl Display for TrackingId {
@@ -108,3 +170,36 @@ impl TrackedMsgs {
         self.tracking_id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packet_trace_id_identifies_the_packet() {
+        let chain_id = ChainId::from_string("ibc-0");
+        let port_id = PortId::transfer();
+        let channel_id = ChannelId::new(0);
+
+        let trace_id = |chain_id: &ChainId, channel_id: &ChannelId, sequence: u64| {
+            packet_trace_id(chain_id, &port_id, channel_id, Sequence::from(sequence))
+        };
+
+        assert_eq!(
+            trace_id(&chain_id, &channel_id, 1),
+            trace_id(&chain_id, &channel_id, 1)
+        );
+        assert_ne!(
+            trace_id(&chain_id, &channel_id, 1),
+            trace_id(&chain_id, &channel_id, 2)
+        );
+        assert_ne!(
+            trace_id(&chain_id, &channel_id, 1),
+            trace_id(&chain_id, &ChannelId::new(1), 1)
+        );
+        assert_ne!(
+            trace_id(&chain_id, &channel_id, 1),
+            trace_id(&ChainId::from_string("ibc-1"), &channel_id, 1)
+        );
+    }
+}
@@ -94,6 +94,15 @@ impl AnyClientState {
         }
     }
 
+    pub fn trusting_period(&self) -> Duration {
+        match self {
+            AnyClientState::Tendermint(state) => state.trusting_period,
+
+            #[cfg(test)]
+            AnyClientState::Mock(_) => Duration::new(0, 0),
+        }
+    }
+
     pub fn client_type(&self) -> ClientType {
         match self {
             Self::Tendermint(state) => state.client_type(),
@@ -148,6 +148,60 @@ impl Display for ExtensionOption {
     }
 }
 
+/// The minimum balance that the relayer wallet on a chain should hold,
+/// in the denomination of the chain's gas price.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum MinBalance {
+    /// An absolute amount, eg. `{ amount = '5000000' }`
+    Amount(#[serde(with = "self::u128_string")] u128),
+
+    /// The fee paid by the given number of transactions using `default_gas`, eg. `{ txs = 100 }`
+    Txs(u64),
+}
+
+impl MinBalance {
+    /// Returns the minimum balance, given the fee paid by a single transaction.
+    pub fn amount(&self, tx_fee: u128) -> u128 {
+        match self {
+            Self::Amount(amount) => *amount,
+            Self::Txs(count) => tx_fee.saturating_mul(u128::from(*count)),
+        }
+    }
+}
+
+impl Display for MinBalance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Amount(amount) => write!(f, "{amount}"),
+            Self::Txs(count) => write!(f, "{count} txs"),
+        }
+    }
+}
+
+/// (De)serialize a `u128` as a string, since TOML integers are limited to 64 bits.
+mod u128_string {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &u128, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u128, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        value
+            .parse()
+            .map_err(|e| D::Error::custom(format!("invalid amount '{value}': {e}")))
+    }
+}
+
 /// Defaults for various fields
 pub mod default {
     use super::*;
@@ -184,6 +238,10 @@ pub mod default {
         Duration::from_millis(500)
     }
 
+    pub fn stall_timeout() -> Duration {
+        Duration::from_secs(30)
+    }
+
     pub fn clock_drift() -> Duration {
         Duration::from_secs(5)
     }
@@ -208,6 +266,62 @@ pub mod default {
         Byte::from_bytes(33554432)
     }
 
+    pub fn scan_concurrency() -> usize {
+        4
+    }
+
+    pub fn traces_endpoint() -> String {
+        "http://127.0.0.1:4317".to_string()
+    }
+
+    pub fn traces_service_name() -> String {
+        "hermes".to_string()
+    }
+
+    pub fn notifications_dedup_window() -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    pub fn notifications_max_per_minute() -> u32 {
+        10
+    }
+
+    pub fn client_expiry_warning() -> Duration {
+        Duration::from_secs(24 * 60 * 60)
+    }
+
+    pub fn stuck_packets_age() -> Duration {
+        Duration::from_secs(30 * 60)
+    }
+
+    pub fn tx_failures() -> u32 {
+        5
+    }
+
+    pub fn channel_cache_ttl() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    pub fn connection_cache_ttl() -> Duration {
+        Duration::from_secs(10 * 60)
+    }
+
+    pub fn client_state_cache_ttl() -> Duration {
+        Duration::from_millis(500)
+    }
+
+    pub fn latest_height_cache_ttl() -> Duration {
+        Duration::from_millis(200)
+    }
+
+    pub fn cache_capacity() -> u64 {
+        10_000
+    }
+
+    pub fn block_scan_window() -> u64 {
+        500
+    }
+
     pub fn latency_submitted() -> HistogramConfig {
         HistogramConfig {
             range: Range {
@@ -240,6 +354,18 @@ pub struct Config {
     pub rest: RestConfig,
     #[serde(default)]
     pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub store: StoreConfig,
+    #[serde(default)]
+    pub scan: ScanConfig,
+    #[serde(default)]
+    pub traces: TracesConfig,
+    #[serde(default)]
+    pub accounting: AccountingConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
     #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
     pub chains: Vec<ChainConfig>,
 }
@@ -484,6 +610,10 @@ pub struct RestConfig {
     pub enabled: bool,
     pub host: String,
     pub port: u16,
+    /// The bearer token required by the endpoints which act on the relayer,
+    /// which are disabled when no token is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
 }
 
 impl Default for RestConfig {
@@ -492,6 +622,124 @@ impl Default for RestConfig {
             enabled: false,
             host: "127.0.0.1".to_string(),
             port: 3000,
+            token: None,
+        }
+    }
+}
+
+/// The persistent store for the in-flight state of the relayer,
+/// see [`crate::store`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StoreConfig {
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+/// Settings for the scan of the chains performed by the supervisor on start,
+/// see [`crate::supervisor::scan`].
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScanConfig {
+    /// Maximum number of chains scanned concurrently
+    #[serde(default = "default::scan_concurrency")]
+    pub concurrency: usize,
+    /// Maximum number of queries per second made to each RPC endpoint while scanning,
+    /// shared by the chains served by the same endpoint, or no limit if zero
+    #[serde(default)]
+    pub max_queries_per_second: u32,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: default::scan_concurrency(),
+            max_queries_per_second: 0,
+        }
+    }
+}
+
+/// The export of the spans which follow the event batches along the relaying pipeline
+/// as OpenTelemetry traces, over OTLP/gRPC.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TracesConfig {
+    pub enabled: bool,
+    /// The endpoint of the OTLP/gRPC collector to which the traces are exported
+    #[serde(default = "default::traces_endpoint")]
+    pub endpoint: String,
+    /// The name of the service under which the traces are reported
+    #[serde(default = "default::traces_service_name")]
+    pub service_name: String,
+}
+
+impl Default for TracesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default::traces_endpoint(),
+            service_name: default::traces_service_name(),
+        }
+    }
+}
+
+/// The ledger of the fees paid and rewarded for the transactions submitted
+/// by the relayer, see [`crate::accounting`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountingConfig {
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+/// The audit log of the packet messages relayed by the relayer, see [`crate::audit`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditConfig {
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+/// The notifications posted to webhooks when incidents occur while relaying,
+/// see [`crate::notifications`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationsConfig {
+    pub enabled: bool,
+    /// The URLs to which the notifications are posted as JSON
+    #[serde(default)]
+    pub webhooks: Vec<String>,
+    /// How long an incident is not notified again after it was notified
+    #[serde(default = "default::notifications_dedup_window", with = "humantime_serde")]
+    pub dedup_window: Duration,
+    /// Maximum number of notifications posted per minute, beyond which they are deferred,
+    /// or no limit if zero
+    #[serde(default = "default::notifications_max_per_minute")]
+    pub max_per_minute: u32,
+    /// How long before its expiry a client is reported as approaching expiry
+    #[serde(default = "default::client_expiry_warning", with = "humantime_serde")]
+    pub client_expiry_warning: Duration,
+    /// How long packets can be pending on a path before they are reported as stuck
+    #[serde(default = "default::stuck_packets_age", with = "humantime_serde")]
+    pub stuck_packets_age: Duration,
+    /// Number of consecutive failures to relay on a path before they are reported
+    #[serde(default = "default::tx_failures")]
+    pub tx_failures: u32,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            webhooks: Vec::new(),
+            dedup_window: default::notifications_dedup_window(),
+            max_per_minute: default::notifications_max_per_minute(),
+            client_expiry_warning: default::client_expiry_warning(),
+            stuck_packets_age: default::stuck_packets_age(),
+            tx_failures: default::tx_failures(),
         }
     }
 }
@@ -551,7 +799,60 @@ pub enum EventSourceMode {
         /// The polling interval
         #[serde(default = "default::poll_interval", with = "humantime_serde")]
         interval: Duration,
+
+        /// The height from which to start collecting events, instead of the latest height.
+        /// Not read from the configuration file, but set on start from the event source
+        /// checkpoint kept in the state store or from the `--from-height` option.
+        #[serde(skip)]
+        start_height: Option<BlockHeight>,
     },
+
+    /// Push-based event source, via WebSocket, which falls back to pulling
+    /// events via RPC /block_results while the WebSocket connection is unreliable
+    Hybrid {
+        /// The WebSocket URL to connect to
+        url: WebSocketClientUrl,
+
+        /// Maximum amount of time to wait for a NewBlock event before emitting the event batch
+        #[serde(default = "default::batch_delay", with = "humantime_serde")]
+        batch_delay: Duration,
+
+        /// The polling interval, while falling back to pulling events
+        #[serde(default = "default::poll_interval", with = "humantime_serde")]
+        interval: Duration,
+
+        /// Maximum amount of time without any event received over the WebSocket
+        /// connection, after which the connection is considered stalled
+        #[serde(default = "default::stall_timeout", with = "humantime_serde")]
+        stall_timeout: Duration,
+
+        /// The height from which to start collecting events, instead of the latest height.
+        /// Not read from the configuration file, but set on start from the event source
+        /// checkpoint kept in the state store or from the `--from-height` option.
+        #[serde(skip)]
+        start_height: Option<BlockHeight>,
+    },
+}
+
+impl EventSourceMode {
+    /// The height from which the event source starts collecting events, if set.
+    pub fn start_height(&self) -> Option<BlockHeight> {
+        match self {
+            Self::Push { .. } => None,
+            Self::Pull { start_height, .. } | Self::Hybrid { start_height, .. } => *start_height,
+        }
+    }
+
+    /// The height from which the event source starts collecting events, if it
+    /// supports starting from a past height, ie. if it is a pull or hybrid one.
+    pub fn start_height_mut(&mut self) -> Option<&mut Option<BlockHeight>> {
+        match self {
+            Self::Push { .. } => None,
+            Self::Pull { start_height, .. } | Self::Hybrid { start_height, .. } => {
+                Some(start_height)
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -562,6 +863,41 @@ pub struct ChainTransport{
     pub grpc_addr: Url,
 }
 
+/// The time-to-live and capacity of the caches of the queries made to a chain,
+/// see [`crate::cache`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheConfig {
+    #[serde(default = "default::channel_cache_ttl", with = "humantime_serde")]
+    pub channel_ttl: Duration,
+    #[serde(default = "default::cache_capacity")]
+    pub channel_capacity: u64,
+    #[serde(default = "default::connection_cache_ttl", with = "humantime_serde")]
+    pub connection_ttl: Duration,
+    #[serde(default = "default::cache_capacity")]
+    pub connection_capacity: u64,
+    #[serde(default = "default::client_state_cache_ttl", with = "humantime_serde")]
+    pub client_state_ttl: Duration,
+    #[serde(default = "default::cache_capacity")]
+    pub client_state_capacity: u64,
+    #[serde(default = "default::latest_height_cache_ttl", with = "humantime_serde")]
+    pub latest_height_ttl: Duration,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            channel_ttl: default::channel_cache_ttl(),
+            channel_capacity: default::cache_capacity(),
+            connection_ttl: default::connection_cache_ttl(),
+            connection_capacity: default::cache_capacity(),
+            client_state_ttl: default::client_state_cache_ttl(),
+            client_state_capacity: default::cache_capacity(),
+            latest_height_ttl: default::latest_height_cache_ttl(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct ChainConfig {
@@ -578,6 +914,11 @@ pub struct ChainConfig {
     /// The gRPC URL to connect to
     pub grpc_addr: Url,
 
+    /// Additional RPC URLs to which signed transactions are broadcast,
+    /// concurrently with `rpc_addr`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub broadcast_addrs: Vec<Url>,
+
     /// The type of event source and associated settings
     pub event_source: EventSourceMode,
 
@@ -610,6 +951,9 @@ pub struct ChainConfig {
     pub gas_multiplier: Option<GasMultiplier>,
 
     pub fee_granter: Option<String>,
+    /// The account on whose behalf the relay messages are executed, wrapped
+    /// in a `MsgExec`, using the authorization it granted to the relayer account
+    pub authz_granter: Option<String>,
     #[serde(default)]
     pub max_msg_num: MaxMsgNum,
     #[serde(default)]
@@ -617,6 +961,11 @@ pub struct ChainConfig {
     #[serde(default = "default::max_grpc_decoding_size")]
     pub max_grpc_decoding_size: Byte,
 
+    /// The number of latest blocks scanned for packet events and client updates
+    /// when transaction indexing is disabled on the node.
+    #[serde(default = "default::block_scan_window")]
+    pub block_scan_window: u64,
+
     /// A correction parameter that helps deal with clocks that are only approximately synchronized
     /// between the source and destination chains for a client.
     /// This parameter is used when deciding to accept or reject a new header
@@ -649,6 +998,11 @@ pub struct ChainConfig {
     #[serde(default)]
     pub sequential_batch_tx: bool,
 
+    /// Pause the periodic clearing of pending packets on this chain while
+    /// the relayer wallet is below its `critical_balance`
+    #[serde(default)]
+    pub pause_on_critical_balance: bool,
+
     // Note: These last few need to be last otherwise we run into `ValueAfterTable` error when serializing to TOML.
     //       That's because these are all tables and have to come last when serializing.
     #[serde(
@@ -669,6 +1023,17 @@ pub struct ChainConfig {
     #[serde(default)]
     pub packet_filter: PacketFilter,
 
+    #[serde(default)]
+    pub cache: CacheConfig,
+
+    /// The minimum balance of the relayer wallet, below which it is reported as running low
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_balance: Option<MinBalance>,
+
+    /// The balance of the relayer wallet below which it is reported as critically low
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub critical_balance: Option<MinBalance>,
+
     #[serde(default)]
     pub address_type: AddressType,
     #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
@@ -779,8 +1144,9 @@ pub(crate) fn store_writer(config: &Config, mut writer: impl Write) -> Result<()
 mod tests {
     use core::str::FromStr;
 
-    use super::{load, parse_gas_prices, store_writer};
+    use super::{load, parse_gas_prices, store_writer, MinBalance};
     use crate::config::GasPrice;
+    use ibc_relayer_types::core::ics24_host::identifier::ChainId;
     use test_log::test;
 
     #[test]
@@ -854,6 +1220,26 @@ mod tests {
         store_writer(&config, &mut buffer).unwrap();
     }
 
+    #[test]
+    fn parse_min_balance() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/config/fixtures/relayer_conf_example.toml"
+        );
+
+        let config = load(path).expect("could not parse config");
+        let chain_config = config.find_chain(&ChainId::from_string("chain_A")).unwrap();
+
+        assert_eq!(chain_config.min_balance, Some(MinBalance::Txs(100)));
+        assert_eq!(MinBalance::Txs(100).amount(2500), 250000);
+
+        let min_balance: MinBalance =
+            toml::from_str("amount = '5000000000000000000000'").expect("could not parse amount");
+
+        assert_eq!(min_balance, MinBalance::Amount(5_000_000_000_000_000_000_000));
+        assert_eq!(min_balance.amount(2500), 5_000_000_000_000_000_000_000);
+    }
+
     #[test]
     fn gas_price_from_str() {
         let gp_original = GasPrice::new(10.0, "atom".to_owned());
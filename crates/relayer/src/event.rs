@@ -29,6 +29,8 @@ use crate::light_client::decode_header;
 
 pub mod bus;
 pub mod error;
+pub mod monitor;
+pub mod record;
 pub mod source;
 
 #[derive(Clone, Debug, Serialize)]
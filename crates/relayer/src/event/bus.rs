@@ -19,6 +19,11 @@ impl<T> EventBus<T> {
         }
     }
 
+    /// Whether there are no subscribers to broadcast values to.
+    pub fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+
     pub fn subscribe(&mut self) -> channel::Receiver<T> {
         let (tx, rx) = channel::unbounded();
         self.txs.push_back(tx);
@@ -38,8 +43,9 @@ impl<T> EventBus<T> {
             }
         }
 
-        // Remove all disconnected subscribers
-        for idx in disconnected {
+        // Remove all disconnected subscribers, starting from the last one
+        // so that the indices of the remaining ones are not shifted
+        for idx in disconnected.into_iter().rev() {
             self.txs.remove(idx);
         }
     }
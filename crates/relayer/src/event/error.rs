@@ -48,6 +48,10 @@ define_error! {
         Rpc
             [ TraceError<RpcError> ]
             |_| { "RPC error" },
+
+        MissedHeights
+            { start: u64, end: u64 }
+            |e| { format!("failed to collect the events emitted from height {0} to {1}", e.start, e.end) },
     }
 }
 
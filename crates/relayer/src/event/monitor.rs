@@ -0,0 +1,206 @@
+//! The activity of the relayer, ie. the IBC events it receives, the messages
+//! it assembles, the transactions it submits and confirms, and the errors it
+//! encounters, broadcast over an [`EventBus`] to which any number of consumers
+//! can subscribe, eg. the live event stream of the REST API.
+
+use alloc::sync::Arc;
+use std::sync::Mutex;
+
+use crossbeam_channel as channel;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tendermint::Time;
+
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId};
+use ibc_relayer_types::events::IbcEvent;
+use ibc_relayer_types::Height;
+
+use crate::chain::tracking::TrackingId;
+use crate::event::bus::EventBus;
+
+static BUS: Lazy<Mutex<EventBus<Arc<RelayerEvent>>>> = Lazy::new(Default::default);
+
+/// An event of the activity of the relayer, which relates to a chain,
+/// and to one of its channels if any.
+#[derive(Clone, Debug, Serialize)]
+pub struct RelayerEvent {
+    pub time: Time,
+    pub chain_id: ChainId,
+    pub channel_id: Option<ChannelId>,
+    #[serde(flatten)]
+    pub kind: RelayerEventKind,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RelayerEventKind {
+    /// An IBC event received from the chain
+    IbcEvent { height: Height, event: IbcEvent },
+
+    /// A batch of messages assembled to be submitted to the chain,
+    /// listed by type URL
+    MessagesAssembled {
+        tracking_id: String,
+        messages: Vec<String>,
+    },
+
+    /// Transactions submitted to the chain
+    TxSubmitted {
+        tracking_id: String,
+        tx_hashes: Vec<String>,
+    },
+
+    /// Transactions confirmed to be committed on the chain,
+    /// along with the number of IBC events they emitted
+    TxConfirmed {
+        tracking_id: String,
+        tx_hashes: Vec<String>,
+        events: usize,
+    },
+
+    /// An error encountered while relaying
+    Error { message: String },
+}
+
+impl RelayerEventKind {
+    pub fn messages_assembled(tracking_id: TrackingId, messages: Vec<String>) -> Self {
+        Self::MessagesAssembled {
+            tracking_id: tracking_id.to_string(),
+            messages,
+        }
+    }
+
+    pub fn tx_submitted<H: ToString>(tracking_id: TrackingId, tx_hashes: &[H]) -> Self {
+        Self::TxSubmitted {
+            tracking_id: tracking_id.to_string(),
+            tx_hashes: tx_hashes.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    pub fn tx_confirmed<H: ToString>(
+        tracking_id: TrackingId,
+        tx_hashes: &[H],
+        events: usize,
+    ) -> Self {
+        Self::TxConfirmed {
+            tracking_id: tracking_id.to_string(),
+            tx_hashes: tx_hashes.iter().map(ToString::to_string).collect(),
+            events,
+        }
+    }
+}
+
+impl RelayerEvent {
+    pub fn new(chain_id: ChainId, channel_id: Option<ChannelId>, kind: RelayerEventKind) -> Self {
+        Self {
+            time: Time::now(),
+            chain_id,
+            channel_id,
+            kind,
+        }
+    }
+
+    /// The event for an IBC event received from the given chain, which relates
+    /// to the channel end on that chain of the packet or channel it is about, if any.
+    pub fn ibc_event(chain_id: ChainId, height: Height, event: IbcEvent) -> Self {
+        let channel_id = match &event {
+            IbcEvent::SendPacket(_)
+            | IbcEvent::AcknowledgePacket(_)
+            | IbcEvent::TimeoutPacket(_)
+            | IbcEvent::TimeoutOnClosePacket(_) => {
+                event.packet().map(|packet| packet.source_channel.clone())
+            }
+            IbcEvent::ReceivePacket(_) | IbcEvent::WriteAcknowledgement(_) => event
+                .packet()
+                .map(|packet| packet.destination_channel.clone()),
+            _ => event
+                .clone()
+                .channel_attributes()
+                .and_then(|attributes| attributes.channel_id),
+        };
+
+        Self::new(
+            chain_id,
+            channel_id,
+            RelayerEventKind::IbcEvent { height, event },
+        )
+    }
+
+    /// Whether the event relates to the given chain and channel, if any.
+    ///
+    /// An event which does not relate to any channel never matches a channel.
+    pub fn matches(&self, chain_id: Option<&ChainId>, channel_id: Option<&ChannelId>) -> bool {
+        chain_id.map_or(true, |chain_id| &self.chain_id == chain_id)
+            && channel_id.map_or(true, |channel_id| {
+                self.channel_id.as_ref() == Some(channel_id)
+            })
+    }
+}
+
+/// Subscribe to the events of the activity of the relayer.
+///
+/// The events are only received by the subscriber while the returned receiver is alive.
+pub fn subscribe() -> channel::Receiver<Arc<RelayerEvent>> {
+    BUS.lock().unwrap().subscribe()
+}
+
+/// Broadcast an event to all the subscribers, if any.
+///
+/// The event is only built when there is at least one subscriber.
+pub fn publish(event: impl FnOnce() -> RelayerEvent) {
+    let mut bus = BUS.lock().unwrap();
+
+    if !bus.is_empty() {
+        bus.broadcast(Arc::new(event()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn publish_to_subscribers() {
+        let chain_id = ChainId::from_string("monitor-0");
+        let channel_id = ChannelId::new(7);
+
+        let rx = subscribe();
+
+        publish(|| {
+            RelayerEvent::new(
+                chain_id.clone(),
+                Some(channel_id.clone()),
+                RelayerEventKind::Error {
+                    message: "failed".to_string(),
+                },
+            )
+        });
+
+        // Other tests may publish events concurrently
+        let event = rx
+            .try_iter()
+            .find(|event| event.matches(Some(&chain_id), None))
+            .unwrap();
+
+        assert!(event.matches(Some(&chain_id), Some(&channel_id)));
+        assert!(!event.matches(Some(&chain_id), Some(&ChannelId::new(8))));
+        assert!(!event.matches(Some(&ChainId::from_string("monitor-1")), None));
+    }
+
+    #[test]
+    fn serialize() {
+        let event = RelayerEvent::new(
+            ChainId::from_string("monitor-0"),
+            None,
+            RelayerEventKind::tx_submitted(TrackingId::new_static("test"), &["ABCD"]),
+        );
+
+        let json = serde_json::to_value(event).unwrap();
+
+        assert_eq!(json["chain_id"], "monitor-0");
+        assert_eq!(json["channel_id"], serde_json::Value::Null);
+        assert_eq!(json["type"], "tx_submitted");
+        assert_eq!(json["tracking_id"], "test");
+        assert_eq!(json["tx_hashes"], serde_json::json!(["ABCD"]));
+    }
+}
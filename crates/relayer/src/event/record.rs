@@ -0,0 +1,320 @@
+//! Recording of the event batches received from the chains to an append-only file,
+//! so that they can later be replayed, eg. to reproduce how a packet was relayed.
+//!
+//! Each batch is recorded as a line of JSON. The IBC events are recorded in their
+//! ABCI form, as emitted by the chain, and are parsed again when replayed.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use flex_error::{define_error, TraceError};
+use serde::{Deserialize, Serialize};
+use tendermint::abci::{Event as AbciEvent, EventAttribute};
+use tracing::warn;
+use uuid::Uuid;
+
+use ibc_relayer_types::core::ics02_client::events::NewBlock;
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use ibc_relayer_types::events::IbcEvent;
+use ibc_relayer_types::Height;
+
+use crate::chain::tracking::TrackingId;
+use crate::event::source::EventBatch;
+use crate::event::{ibc_event_try_from_abci_event, IbcEventWithHeight};
+
+/// The name of the file to which the event batches are recorded, in the record directory.
+pub const RECORD_FILE_NAME: &str = "events.jsonl";
+
+define_error! {
+    Error {
+        Io
+            { path: PathBuf }
+            [ TraceError<io::Error> ]
+            |e| { format!("I/O error on event record {}", e.path.display()) },
+
+        Encode
+            [ TraceError<serde_json::Error> ]
+            |_| { "failed to encode event batch" },
+
+        Decode
+            { path: PathBuf, line: usize }
+            [ TraceError<serde_json::Error> ]
+            |e| { format!("failed to decode event batch at line {} of {}", e.line, e.path.display()) },
+    }
+}
+
+/// An event batch, as recorded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedBatch {
+    pub chain_id: ChainId,
+    pub height: Height,
+    pub tracking_id: String,
+    pub events: Vec<RecordedEvent>,
+}
+
+/// An event, as recorded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedEvent {
+    NewBlock {
+        height: Height,
+    },
+    Abci {
+        height: Height,
+        kind: String,
+        attributes: Vec<(String, String)>,
+    },
+}
+
+impl RecordedBatch {
+    pub fn from_batch(batch: &EventBatch) -> Self {
+        let events = batch
+            .events
+            .iter()
+            .filter_map(RecordedEvent::from_event)
+            .collect();
+
+        Self {
+            chain_id: batch.chain_id.clone(),
+            height: batch.height,
+            tracking_id: encode_tracking_id(batch.tracking_id),
+            events,
+        }
+    }
+
+    pub fn into_batch(self) -> EventBatch {
+        let events = self
+            .events
+            .into_iter()
+            .filter_map(RecordedEvent::into_event)
+            .collect();
+
+        EventBatch {
+            chain_id: self.chain_id,
+            tracking_id: decode_tracking_id(&self.tracking_id),
+            height: self.height,
+            events,
+        }
+    }
+}
+
+impl RecordedEvent {
+    pub fn from_event(event: &IbcEventWithHeight) -> Option<Self> {
+        let height = event.height;
+
+        if let IbcEvent::NewBlock(_) = event.event {
+            return Some(Self::NewBlock { height });
+        }
+
+        match AbciEvent::try_from(event.event.clone()) {
+            Ok(abci_event) => Some(Self::Abci {
+                height,
+                kind: abci_event.kind,
+                attributes: abci_event
+                    .attributes
+                    .into_iter()
+                    .map(|attr| (attr.key, attr.value))
+                    .collect(),
+            }),
+            Err(e) => {
+                warn!("event {} cannot be recorded: {}", event.event, e);
+                None
+            }
+        }
+    }
+
+    pub fn into_event(self) -> Option<IbcEventWithHeight> {
+        match self {
+            Self::NewBlock { height } => Some(IbcEventWithHeight::new(
+                IbcEvent::NewBlock(NewBlock::new(height)),
+                height,
+            )),
+            Self::Abci {
+                height,
+                kind,
+                attributes,
+            } => {
+                let abci_event = AbciEvent {
+                    kind,
+                    attributes: attributes
+                        .into_iter()
+                        .map(|(key, value)| EventAttribute {
+                            key,
+                            value,
+                            index: true,
+                        })
+                        .collect(),
+                };
+
+                match ibc_event_try_from_abci_event(&abci_event) {
+                    Ok(event) => Some(IbcEventWithHeight::new(event, height)),
+                    Err(e) => {
+                        warn!(
+                            "recorded event {} cannot be replayed: {}",
+                            abci_event.kind, e
+                        );
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Records event batches to an append-only file.
+#[derive(Debug)]
+pub struct EventRecorder {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl EventRecorder {
+    /// Opens the record file in the given directory for appending, creating it if needed.
+    pub fn create(dir: &Path) -> Result<Self, Error> {
+        fs::create_dir_all(dir).map_err(|e| Error::io(dir.to_path_buf(), e))?;
+
+        let path = dir.join(RECORD_FILE_NAME);
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| Error::io(path.clone(), e))?;
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends the given batch to the record file.
+    pub fn record(&self, batch: &EventBatch) -> Result<(), Error> {
+        let mut line =
+            serde_json::to_string(&RecordedBatch::from_batch(batch)).map_err(Error::encode)?;
+
+        line.push('\n');
+
+        // Write each batch at once, so that batches recorded concurrently are not interleaved
+        self.file
+            .lock()
+            .unwrap()
+            .write_all(line.as_bytes())
+            .map_err(|e| Error::io(self.path.clone(), e))
+    }
+}
+
+/// Reads the event batches recorded in the given directory, in the order they were recorded.
+pub fn read_recorded_batches(dir: &Path) -> Result<Vec<EventBatch>, Error> {
+    let path = dir.join(RECORD_FILE_NAME);
+    let file = File::open(&path).map_err(|e| Error::io(path.clone(), e))?;
+
+    let mut batches = Vec::new();
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| Error::io(path.clone(), e))?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let batch = serde_json::from_str::<RecordedBatch>(&line)
+            .map_err(|e| Error::decode(path.clone(), index + 1, e))?;
+
+        batches.push(batch.into_batch());
+    }
+
+    Ok(batches)
+}
+
+fn encode_tracking_id(tracking_id: TrackingId) -> String {
+    match tracking_id {
+        TrackingId::Uuid(uuid) => uuid.to_string(),
+        TrackingId::ClearedUuid(uuid) => format!("cleared/{uuid}"),
+        TrackingId::Static(s) => s.to_string(),
+    }
+}
+
+fn decode_tracking_id(tracking_id: &str) -> TrackingId {
+    if let Ok(uuid) = Uuid::parse_str(tracking_id) {
+        return TrackingId::Uuid(uuid);
+    }
+
+    match tracking_id
+        .strip_prefix("cleared/")
+        .and_then(|uuid| Uuid::parse_str(uuid).ok())
+    {
+        Some(uuid) => TrackingId::ClearedUuid(uuid),
+        None => TrackingId::new_static("replayed"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc_relayer_types::core::ics04_channel::events::SendPacket;
+    use ibc_relayer_types::core::ics04_channel::packet::{Packet, Sequence};
+    use ibc_relayer_types::core::ics04_channel::timeout::TimeoutHeight;
+    use ibc_relayer_types::timestamp::Timestamp;
+
+    fn batch() -> EventBatch {
+        let height = Height::new(0, 42).unwrap();
+
+        let packet = Packet {
+            sequence: Sequence::from(1),
+            source_port: "transfer".parse().unwrap(),
+            source_channel: "channel-0".parse().unwrap(),
+            destination_port: "transfer".parse().unwrap(),
+            destination_channel: "channel-1".parse().unwrap(),
+            data: vec![1, 2, 3],
+            timeout_height: TimeoutHeight::At(Height::new(0, 100).unwrap()),
+            timeout_timestamp: Timestamp::none(),
+        };
+
+        EventBatch {
+            chain_id: ChainId::from_string("chain-a"),
+            tracking_id: TrackingId::new_uuid(),
+            height,
+            events: vec![
+                IbcEventWithHeight::new(IbcEvent::NewBlock(NewBlock::new(height)), height),
+                IbcEventWithHeight::new(IbcEvent::SendPacket(SendPacket { packet }), height),
+            ],
+        }
+    }
+
+    #[test]
+    fn record_and_read_batches() {
+        let dir = std::env::temp_dir().join(format!("hermes-record-{}", std::process::id()));
+        let batch = batch();
+
+        {
+            let recorder = EventRecorder::create(&dir).unwrap();
+            recorder.record(&batch).unwrap();
+            recorder.record(&batch).unwrap();
+        }
+
+        let batches = read_recorded_batches(&dir).unwrap();
+        assert_eq!(batches.len(), 2);
+
+        for replayed in batches {
+            assert_eq!(
+                RecordedBatch::from_batch(&replayed),
+                RecordedBatch::from_batch(&batch)
+            );
+
+            assert!(matches!(replayed.events[0].event, IbcEvent::NewBlock(_)));
+            assert!(matches!(
+                &replayed.events[1].event,
+                IbcEvent::SendPacket(SendPacket { packet }) if packet.data == vec![1, 2, 3]
+            ));
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
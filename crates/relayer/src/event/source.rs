@@ -1,3 +1,4 @@
+pub mod hybrid;
 pub mod rpc;
 pub mod websocket;
 
@@ -6,6 +7,7 @@ use std::{sync::Arc, time::Duration};
 use crossbeam_channel as channel;
 
 use futures::Stream;
+use tendermint::block::Height as BlockHeight;
 use tendermint_rpc::{
     client::CompatMode, event::Event as RpcEvent, Error as RpcError, HttpClient, WebSocketClientUrl,
 };
@@ -18,13 +20,16 @@ use ibc_relayer_types::{
 pub use super::error::{Error, ErrorDetail};
 
 use super::IbcEventWithHeight;
-use crate::chain::{handle::Subscription, tracking::TrackingId};
+use crate::chain::{
+    cosmos::compatibility::BlockResultsLayout, handle::Subscription, tracking::TrackingId,
+};
 
 pub type Result<T> = core::result::Result<T, Error>;
 
 pub enum EventSource {
     WebSocket(websocket::EventSource),
     Rpc(rpc::EventSource),
+    Hybrid(hybrid::EventSource),
 }
 
 impl EventSource {
@@ -33,10 +38,19 @@ impl EventSource {
         ws_url: WebSocketClientUrl,
         rpc_compat: CompatMode,
         batch_delay: Duration,
+        rpc_client: HttpClient,
+        block_results_layout: BlockResultsLayout,
         rt: Arc<TokioRuntime>,
     ) -> Result<(Self, TxEventSourceCmd)> {
-        let (mut source, tx) =
-            websocket::EventSource::new(chain_id, ws_url, rpc_compat, batch_delay, rt)?;
+        let (mut source, tx) = websocket::EventSource::new(
+            chain_id,
+            ws_url,
+            rpc_compat,
+            batch_delay,
+            Some(rpc_client),
+            block_results_layout,
+            rt,
+        )?;
 
         source.init_subscriptions()?;
 
@@ -46,17 +60,56 @@ impl EventSource {
     pub fn rpc(
         chain_id: ChainId,
         rpc_client: HttpClient,
+        block_results_layout: BlockResultsLayout,
         poll_interval: Duration,
+        start_height: Option<BlockHeight>,
         rt: Arc<TokioRuntime>,
     ) -> Result<(Self, TxEventSourceCmd)> {
-        let (source, tx) = rpc::EventSource::new(chain_id, rpc_client, poll_interval, rt)?;
+        let (source, tx) = rpc::EventSource::new(
+            chain_id,
+            rpc_client,
+            block_results_layout,
+            poll_interval,
+            start_height,
+            rt,
+        )?;
         Ok((Self::Rpc(source), tx))
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn hybrid(
+        chain_id: ChainId,
+        ws_url: WebSocketClientUrl,
+        rpc_compat: CompatMode,
+        batch_delay: Duration,
+        rpc_client: HttpClient,
+        block_results_layout: BlockResultsLayout,
+        poll_interval: Duration,
+        stall_timeout: Duration,
+        start_height: Option<BlockHeight>,
+        rt: Arc<TokioRuntime>,
+    ) -> Result<(Self, TxEventSourceCmd)> {
+        let (source, tx) = hybrid::EventSource::new(
+            chain_id,
+            ws_url,
+            rpc_compat,
+            batch_delay,
+            rpc_client,
+            block_results_layout,
+            poll_interval,
+            stall_timeout,
+            start_height,
+            rt,
+        )?;
+
+        Ok((Self::Hybrid(source), tx))
+    }
+
     pub fn run(self) {
         match self {
             Self::WebSocket(source) => source.run(),
             Self::Rpc(source) => source.run(),
+            Self::Hybrid(source) => source.run(),
         }
     }
 }
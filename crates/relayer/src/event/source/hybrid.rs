@@ -0,0 +1,733 @@
+use alloc::collections::{BTreeSet, VecDeque};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel as channel;
+use tokio::runtime::Runtime as TokioRuntime;
+use tracing::{debug, error, error_span, info, trace, warn};
+
+use tendermint::block::Height as BlockHeight;
+use tendermint_rpc::{client::CompatMode, HttpClient, WebSocketClientUrl};
+
+use ibc_relayer_types::{core::ics24_host::identifier::ChainId, events::IbcEvent};
+
+use crate::{
+    chain::{cosmos::compatibility::BlockResultsLayout, handle::Subscription},
+    event::{bus::EventBus, IbcEventWithHeight},
+    notifications::{self, Incident},
+    telemetry,
+};
+
+use super::{rpc, websocket, EventBatch, EventSourceCmd, Result, TxEventSourceCmd};
+
+/// Amount of time during which the WebSocket connection must deliver events without
+/// interruption before switching back from pulling events to receiving them over WebSocket.
+const STABLE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Number of disruptions of the WebSocket connection within [`DISRUPTION_WINDOW`],
+/// eg. subscription errors or reconnections, after which the connection is deemed
+/// unreliable and events are pulled instead.
+const MAX_DISRUPTIONS: usize = 3;
+const DISRUPTION_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// Minimum interval between two attempts at connecting to the WebSocket endpoint,
+/// when no connection could be established.
+const CONNECT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Mode {
+    /// Broadcast the events received over WebSocket
+    Push,
+    /// Broadcast the events pulled via RPC /block_results
+    Pull,
+}
+
+/// A WebSocket event source running in its own thread,
+/// along with the state of the connection.
+struct WebSocket {
+    /// Channel where to send commands to the WebSocket event source
+    tx_cmd: TxEventSourceCmd,
+    /// Subscription to the batches received over WebSocket
+    subscription: Subscription,
+    /// Height of the last batch received
+    last_height: Option<u64>,
+    /// When the last batch was received
+    last_received: Instant,
+    /// Since when the batches have been received without interruption, if they are
+    stable_since: Option<Instant>,
+}
+
+/// An event source which receives events over WebSocket while the connection is reliable,
+/// and otherwise falls back to pulling them via RPC /block_results, until the connection
+/// is stable again.
+///
+/// Every height is broadcast exactly once, whichever way its events were collected:
+/// - the heights skipped by the WebSocket connection, eg. while reconnecting,
+///   are pulled before broadcasting the batches received after them;
+/// - the batches received over WebSocket for heights which were already pulled are dropped;
+/// - when falling back to pulling events in the middle of a height, the events of that
+///   height which were not received over WebSocket are pulled and broadcast.
+pub struct EventSource {
+    /// Chain identifier
+    chain_id: ChainId,
+
+    /// WebSocket URL
+    ws_url: WebSocketClientUrl,
+
+    /// RPC compatibility mode
+    rpc_compat: CompatMode,
+
+    /// Delay until a batch of events received over WebSocket is emitted
+    batch_delay: Duration,
+
+    /// RPC client
+    rpc_client: HttpClient,
+
+    /// Layout of the block results returned by the node
+    block_results_layout: BlockResultsLayout,
+
+    /// Poll interval, while pulling events
+    poll_interval: Duration,
+
+    /// Maximum amount of time without any batch received over WebSocket
+    stall_timeout: Duration,
+
+    /// Event bus for broadcasting events
+    event_bus: EventBus<Arc<Result<EventBatch>>>,
+
+    /// Channel where to receive commands
+    rx_cmd: channel::Receiver<EventSourceCmd>,
+
+    /// Tokio runtime
+    rt: Arc<TokioRuntime>,
+
+    /// Whether events are currently received over WebSocket or pulled
+    mode: Mode,
+
+    /// The WebSocket event source, if connected
+    websocket: Option<WebSocket>,
+
+    /// When the last attempt at connecting to the WebSocket endpoint was made
+    last_connect_attempt: Instant,
+
+    /// Height of the last batch broadcast
+    last_height: Option<u64>,
+
+    /// Batches received over WebSocket and broadcast at the last height, if any,
+    /// in which case more events may be emitted at that height
+    pushed: Vec<Arc<Result<EventBatch>>>,
+
+    /// When the recent disruptions of the WebSocket connection occurred
+    disruptions: VecDeque<Instant>,
+}
+
+impl EventSource {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        chain_id: ChainId,
+        ws_url: WebSocketClientUrl,
+        rpc_compat: CompatMode,
+        batch_delay: Duration,
+        rpc_client: HttpClient,
+        block_results_layout: BlockResultsLayout,
+        poll_interval: Duration,
+        stall_timeout: Duration,
+        start_height: Option<BlockHeight>,
+        rt: Arc<TokioRuntime>,
+    ) -> Result<(Self, TxEventSourceCmd)> {
+        let event_bus = EventBus::new();
+        let (tx_cmd, rx_cmd) = channel::unbounded();
+
+        // When starting from a past height, pull the events up to the latest height first
+        let (mode, last_height) = match start_height {
+            Some(start_height) => (Mode::Pull, Some(start_height.value().saturating_sub(1))),
+            None => (Mode::Push, None),
+        };
+
+        let source = Self {
+            chain_id,
+            ws_url,
+            rpc_compat,
+            batch_delay,
+            rpc_client,
+            block_results_layout,
+            poll_interval,
+            stall_timeout,
+            event_bus,
+            rx_cmd,
+            rt,
+            mode,
+            websocket: None,
+            last_connect_attempt: Instant::now(),
+            last_height,
+            pushed: Vec::new(),
+            disruptions: VecDeque::new(),
+        };
+
+        Ok((source, TxEventSourceCmd(tx_cmd)))
+    }
+
+    pub fn run(mut self) {
+        let _span = error_span!("event_source.hybrid", chain.id = %self.chain_id).entered();
+
+        debug!("collecting events");
+
+        self.connect();
+
+        loop {
+            let next = match self.mode {
+                Mode::Push => self.push_step(),
+                Mode::Pull => self.pull_step(),
+            };
+
+            if let Next::Abort = next {
+                break;
+            }
+        }
+
+        debug!("shutting down event source");
+
+        if let Some(websocket) = self.websocket.take() {
+            let _ = websocket.tx_cmd.shutdown();
+        }
+    }
+
+    /// Spawn a WebSocket event source and subscribe to its batches,
+    /// falling back to pulling events if that fails.
+    fn connect(&mut self) {
+        self.last_connect_attempt = Instant::now();
+
+        let result = websocket::EventSource::new(
+            self.chain_id.clone(),
+            self.ws_url.clone(),
+            self.rpc_compat,
+            self.batch_delay,
+            // The heights skipped by the WebSocket connection are pulled by this event source
+            None,
+            self.block_results_layout,
+            self.rt.clone(),
+        )
+        .and_then(|(mut source, tx_cmd)| {
+            source.init_subscriptions()?;
+            Ok((source, tx_cmd))
+        });
+
+        let (source, tx_cmd) = match result {
+            Ok(source) => source,
+            Err(e) => {
+                self.switch_to_pull(&format!(
+                    "failed to connect to WebSocket endpoint {}: {e}",
+                    self.ws_url
+                ));
+
+                return;
+            }
+        };
+
+        thread::spawn(move || source.run());
+
+        match tx_cmd.subscribe() {
+            Ok(subscription) => {
+                trace!("connected to WebSocket endpoint {}", self.ws_url);
+
+                self.websocket = Some(WebSocket {
+                    tx_cmd,
+                    subscription,
+                    last_height: None,
+                    last_received: Instant::now(),
+                    stable_since: None,
+                });
+            }
+            Err(e) => {
+                let _ = tx_cmd.shutdown();
+
+                self.switch_to_pull(&format!(
+                    "failed to subscribe to WebSocket event source: {e}"
+                ));
+            }
+        }
+    }
+
+    /// Wait for the next batch received over WebSocket and broadcast it,
+    /// falling back to pulling events if the connection stalls.
+    fn push_step(&mut self) -> Next {
+        let Some(websocket) = &self.websocket else {
+            self.switch_to_pull("not connected to WebSocket endpoint");
+            return Next::Continue;
+        };
+
+        let subscription = websocket.subscription.clone();
+        let timeout = (websocket.last_received + self.stall_timeout)
+            .saturating_duration_since(Instant::now());
+
+        channel::select! {
+            recv(self.rx_cmd) -> cmd => self.process_cmd(cmd.ok()),
+            recv(subscription) -> batch => {
+                match batch {
+                    Ok(batch) => self.handle_pushed_batch(batch),
+                    Err(_) => self.disconnected(),
+                }
+
+                Next::Continue
+            },
+            default(timeout) => {
+                if let Some(websocket) = &mut self.websocket {
+                    websocket.stable_since = None;
+                }
+
+                self.switch_to_pull(&format!(
+                    "no events received over WebSocket for {}",
+                    humantime::format_duration(self.stall_timeout)
+                ));
+
+                Next::Continue
+            },
+        }
+    }
+
+    /// Pull the events emitted since the last height, and switch back to
+    /// receiving events over WebSocket once caught up and the connection is stable.
+    fn pull_step(&mut self) -> Next {
+        let before_step = Instant::now();
+
+        let caught_up = match self.pull_batches() {
+            Ok(caught_up) => caught_up,
+            Err(e) => {
+                error!("failed to pull events: {e}");
+                false
+            }
+        };
+
+        if caught_up && self.is_websocket_stable() {
+            info!(
+                "WebSocket connection is stable, switching back to receiving events over WebSocket"
+            );
+
+            self.mode = Mode::Push;
+            self.disruptions.clear();
+
+            return Next::Continue;
+        }
+
+        if self.websocket.is_none() && self.last_connect_attempt.elapsed() >= CONNECT_INTERVAL {
+            self.connect();
+        }
+
+        // Keep pulling without waiting while catching up with the chain
+        let delay = if caught_up {
+            self.poll_interval.saturating_sub(before_step.elapsed())
+        } else {
+            Duration::ZERO
+        };
+
+        self.wait(delay)
+    }
+
+    /// Pull the events emitted since the last height, up to the latest height,
+    /// and broadcast them, returning whether the latest height was reached.
+    fn pull_batches(&mut self) -> Result<bool> {
+        // First pull the events of the last height which were not received over WebSocket
+        self.complete_pushed_height()?;
+
+        let latest_height = self
+            .rt
+            .block_on(rpc::latest_height(&self.rpc_client))?
+            .value();
+
+        // Start from the latest height when no height was broadcast yet
+        let last_height = *self.last_height.get_or_insert(latest_height);
+
+        if latest_height > last_height {
+            trace!("latest height ({latest_height}) > last height ({last_height})");
+
+            let end_height = latest_height.min(last_height + rpc::MAX_BLOCKS_PER_STEP);
+            self.pull_range(last_height + 1, end_height)?;
+        }
+
+        Ok(self.last_height >= Some(latest_height))
+    }
+
+    /// Pull the events emitted in the given range of heights, and broadcast them.
+    fn pull_range(&mut self, start_height: u64, end_height: u64) -> Result<()> {
+        trace!("pulling events from height {start_height} to {end_height}");
+
+        let heights = (start_height..=end_height)
+            .filter_map(|height| BlockHeight::try_from(height).ok())
+            .collect::<Vec<_>>();
+
+        let (collected, failed) = self.rt.block_on(rpc::collect_events_at(
+            &self.rpc_client,
+            self.block_results_layout,
+            &self.chain_id,
+            &heights,
+        ));
+
+        for (height, batch) in collected {
+            if let Some(batch) = batch {
+                self.broadcast_batch(Arc::new(Ok(batch)));
+            }
+
+            self.last_height = Some(height.value());
+            self.pushed.clear();
+        }
+
+        match failed {
+            Some((_, e)) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Pull the events emitted at the last height, if its batches were received
+    /// over WebSocket, and broadcast the ones which were not, if any.
+    fn complete_pushed_height(&mut self) -> Result<()> {
+        let Some(height) = self.last_height.filter(|_| !self.pushed.is_empty()) else {
+            return Ok(());
+        };
+
+        let Ok(block_height) = BlockHeight::try_from(height) else {
+            return Ok(());
+        };
+
+        let (collected, failed) = self.rt.block_on(rpc::collect_events_at(
+            &self.rpc_client,
+            self.block_results_layout,
+            &self.chain_id,
+            &[block_height],
+        ));
+
+        if let Some((_, e)) = failed {
+            return Err(e);
+        }
+
+        let pushed_events = self
+            .pushed
+            .iter()
+            .filter_map(|batch| batch.as_ref().as_ref().ok())
+            .flat_map(|batch| batch.events.iter())
+            .map(event_key)
+            .collect::<BTreeSet<_>>();
+
+        for (_, batch) in collected {
+            let Some(mut batch) = batch else {
+                continue;
+            };
+
+            batch.events.retain(|event| {
+                !matches!(event.event, IbcEvent::NewBlock(_))
+                    && !pushed_events.contains(&event_key(event))
+            });
+
+            if !batch.events.is_empty() {
+                debug!(
+                    "pulled {} events at height {height} which were not received over WebSocket",
+                    batch.events.len()
+                );
+
+                self.broadcast_batch(Arc::new(Ok(batch)));
+            }
+        }
+
+        self.pushed.clear();
+
+        Ok(())
+    }
+
+    /// Broadcast a batch received over WebSocket, unless its height was already pulled,
+    /// after pulling the heights which were skipped since the last height, if any.
+    fn handle_pushed_batch(&mut self, batch: Arc<Result<EventBatch>>) {
+        let height = match batch.as_ref() {
+            Ok(batch) => batch.height.revision_height(),
+            Err(e) => {
+                warn!("WebSocket event source encountered an error: {e}");
+
+                if let Some(websocket) = &mut self.websocket {
+                    websocket.stable_since = None;
+                }
+
+                self.record_disruption();
+                return;
+            }
+        };
+
+        let skipped = self.track_pushed_height(height);
+
+        if self.mode == Mode::Pull {
+            return;
+        }
+
+        if skipped {
+            self.record_disruption();
+
+            if self.mode == Mode::Pull {
+                return;
+            }
+        }
+
+        match self.last_height {
+            // This height was already broadcast, either pulled or received over WebSocket
+            Some(last_height) if height < last_height => {
+                trace!("dropping batch at height {height} which was already broadcast");
+                return;
+            }
+
+            // This height was pulled, so all of its events were already broadcast
+            Some(last_height) if height == last_height && self.pushed.is_empty() => {
+                trace!("dropping batch at height {height} which was already pulled");
+                return;
+            }
+
+            // More events for the last height received over WebSocket
+            Some(last_height) if height == last_height => {}
+
+            Some(last_height) => {
+                // Pull the heights skipped by the WebSocket connection, if any
+                let backfill = if height > last_height + 1 {
+                    debug!(
+                        "pulling events from height {} to {} which were not received over WebSocket",
+                        last_height + 1,
+                        height - 1
+                    );
+
+                    self.complete_pushed_height()
+                        .and_then(|()| self.pull_range(last_height + 1, height - 1))
+                } else {
+                    Ok(())
+                };
+
+                if let Err(e) = backfill {
+                    self.switch_to_pull(&format!(
+                        "failed to pull events which were not received over WebSocket: {e}"
+                    ));
+
+                    return;
+                }
+
+                self.pushed.clear();
+            }
+
+            None => {}
+        }
+
+        self.last_height = Some(height);
+        self.pushed.push(batch.clone());
+        self.broadcast_batch(batch);
+    }
+
+    /// Track the height of a batch received over WebSocket, returning
+    /// whether some heights were skipped since the previous batch.
+    fn track_pushed_height(&mut self, height: u64) -> bool {
+        let Some(websocket) = &mut self.websocket else {
+            return false;
+        };
+
+        let skipped = websocket
+            .last_height
+            .map_or(false, |last_height| height > last_height + 1);
+
+        let now = Instant::now();
+
+        websocket.last_height = Some(height);
+        websocket.last_received = now;
+
+        if skipped {
+            websocket.stable_since = None;
+        } else {
+            websocket.stable_since.get_or_insert(now);
+        }
+
+        skipped
+    }
+
+    /// Whether the WebSocket connection has delivered events without
+    /// interruption for long enough to switch back to it.
+    fn is_websocket_stable(&self) -> bool {
+        self.websocket.as_ref().map_or(false, |websocket| {
+            websocket.last_received.elapsed() < self.stall_timeout
+                && websocket
+                    .stable_since
+                    .map_or(false, |since| since.elapsed() >= STABLE_PERIOD)
+        })
+    }
+
+    /// Record a disruption of the WebSocket connection, and fall back
+    /// to pulling events if the connection was disrupted too often.
+    fn record_disruption(&mut self) {
+        let now = Instant::now();
+
+        self.disruptions.push_back(now);
+
+        while let Some(at) = self.disruptions.front() {
+            if now.duration_since(*at) > DISRUPTION_WINDOW {
+                self.disruptions.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if self.mode == Mode::Push && self.disruptions.len() >= MAX_DISRUPTIONS {
+            self.switch_to_pull(&format!(
+                "WebSocket connection was disrupted {} times in the last {}",
+                self.disruptions.len(),
+                humantime::format_duration(DISRUPTION_WINDOW)
+            ));
+        }
+    }
+
+    /// Handle the termination of the WebSocket event source.
+    fn disconnected(&mut self) {
+        self.websocket = None;
+        self.switch_to_pull("WebSocket event source has terminated");
+    }
+
+    fn switch_to_pull(&mut self, reason: &str) {
+        if self.mode == Mode::Pull {
+            debug!("{reason}");
+            return;
+        }
+
+        warn!("{reason}, falling back to pulling events via /block_results");
+
+        notifications::notify(Incident::EndpointFailover {
+            chain_id: self.chain_id.clone(),
+            endpoint: self.ws_url.to_string(),
+            reason: reason.to_string(),
+        });
+
+        self.mode = Mode::Pull;
+    }
+
+    /// Wait for the given delay, while processing commands and
+    /// tracking the batches received over WebSocket.
+    fn wait(&mut self, delay: Duration) -> Next {
+        let deadline = Instant::now() + delay;
+
+        loop {
+            let subscription = match &self.websocket {
+                Some(websocket) => websocket.subscription.clone(),
+                None => channel::never(),
+            };
+
+            let timeout = deadline.saturating_duration_since(Instant::now());
+
+            channel::select! {
+                recv(self.rx_cmd) -> cmd => {
+                    if let Next::Abort = self.process_cmd(cmd.ok()) {
+                        return Next::Abort;
+                    }
+                },
+                recv(subscription) -> batch => match batch {
+                    Ok(batch) => self.handle_pushed_batch(batch),
+                    Err(_) => self.disconnected(),
+                },
+                default(timeout) => return Next::Continue,
+            }
+        }
+    }
+
+    /// Process the given command, aborting if the command channel was closed.
+    fn process_cmd(&mut self, cmd: Option<EventSourceCmd>) -> Next {
+        match cmd {
+            None | Some(EventSourceCmd::Shutdown) => Next::Abort,
+
+            Some(EventSourceCmd::Subscribe(tx)) => {
+                if let Err(e) = tx.send(self.event_bus.subscribe()) {
+                    error!("failed to send back subscription: {e}");
+                }
+
+                Next::Continue
+            }
+        }
+    }
+
+    fn broadcast_batch(&mut self, batch: Arc<Result<EventBatch>>) {
+        if let Ok(batch) = batch.as_ref() {
+            telemetry!(ws_events, &batch.chain_id, batch.events.len() as u64);
+
+            trace!(
+                chain = %batch.chain_id,
+                count = %batch.events.len(),
+                height = %batch.height,
+                "broadcasting batch of {} events",
+                batch.events.len()
+            );
+        }
+
+        self.event_bus.broadcast(batch);
+    }
+}
+
+/// A key identifying an event, to find the events pulled at a given height
+/// which were already received over WebSocket.
+fn event_key(event: &IbcEventWithHeight) -> String {
+    serde_json::to_string(&event.event).unwrap_or_default()
+}
+
+pub enum Next {
+    Abort,
+    Continue,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc_relayer_types::core::ics02_client::{events::NewBlock, height::Height};
+
+    use crate::chain::tracking::TrackingId;
+
+    fn batch_at(chain_id: &ChainId, height: u64) -> Arc<Result<EventBatch>> {
+        let height = Height::new(0, height).unwrap();
+        let event = IbcEventWithHeight::new(IbcEvent::NewBlock(NewBlock::new(height)), height);
+
+        Arc::new(Ok(EventBatch {
+            chain_id: chain_id.clone(),
+            tracking_id: TrackingId::new_uuid(),
+            height,
+            events: vec![event],
+        }))
+    }
+
+    #[test]
+    fn pushed_batches_are_broadcast_once() {
+        let chain_id = ChainId::from_string("chain-a");
+        let rt = Arc::new(TokioRuntime::new().unwrap());
+
+        let (mut source, _tx_cmd) = EventSource::new(
+            chain_id.clone(),
+            "ws://127.0.0.1:26657/websocket".parse().unwrap(),
+            CompatMode::V0_37,
+            Duration::from_millis(500),
+            HttpClient::new("http://127.0.0.1:26657").unwrap(),
+            BlockResultsLayout::BeginEndBlock,
+            Duration::from_secs(1),
+            Duration::from_secs(30),
+            None,
+            rt,
+        )
+        .unwrap();
+
+        let subscription = source.event_bus.subscribe();
+
+        // Batches received over WebSocket, including a batch split over the same height
+        source.handle_pushed_batch(batch_at(&chain_id, 10));
+        source.handle_pushed_batch(batch_at(&chain_id, 10));
+        source.handle_pushed_batch(batch_at(&chain_id, 11));
+
+        // Heights which were already broadcast are dropped
+        source.handle_pushed_batch(batch_at(&chain_id, 10));
+
+        // Heights which were already pulled are dropped
+        source.last_height = Some(12);
+        source.pushed.clear();
+        source.handle_pushed_batch(batch_at(&chain_id, 12));
+        source.handle_pushed_batch(batch_at(&chain_id, 13));
+
+        let heights = subscription
+            .try_iter()
+            .map(|batch| batch.as_ref().as_ref().unwrap().height.revision_height())
+            .collect::<Vec<_>>();
+
+        assert_eq!(heights, vec![10, 10, 11, 13]);
+    }
+}
@@ -3,11 +3,12 @@ pub mod extract;
 use std::sync::Arc;
 
 use crossbeam_channel as channel;
+use futures::future::join_all;
 use tokio::{
     runtime::Runtime as TokioRuntime,
     time::{sleep, Duration, Instant},
 };
-use tracing::{debug, error, error_span, trace};
+use tracing::{debug, error, error_span, info, trace, warn};
 
 use tendermint::abci;
 use tendermint::block::Height as BlockHeight;
@@ -22,7 +23,10 @@ use ibc_relayer_types::{
 };
 
 use crate::{
-    chain::tracking::TrackingId,
+    chain::{
+        cosmos::{compatibility::BlockResultsLayout, query::block_results::fetch_block_results},
+        tracking::TrackingId,
+    },
     event::{bus::EventBus, source::Error, IbcEventWithHeight},
     telemetry,
     util::retry::ConstantGrowth,
@@ -34,6 +38,17 @@ use self::extract::extract_events;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
+/// Maximum number of blocks whose events are fetched concurrently.
+const MAX_CONCURRENT_FETCHES: usize = 10;
+
+/// Maximum number of blocks whose events are fetched in a single step,
+/// so that the batches collected while catching up are broadcast as we go.
+pub(super) const MAX_BLOCKS_PER_STEP: u64 = 100;
+
+/// Number of times fetching the events of a block is attempted while catching up,
+/// before giving up on catching up, eg. because the block was pruned by the node.
+const MAX_CATCH_UP_ATTEMPTS: u32 = 5;
+
 /// An RPC endpoint that serves as a source of events for a given chain.
 pub struct EventSource {
     /// Chain identifier
@@ -42,6 +57,9 @@ pub struct EventSource {
     /// RPC client
     rpc_client: HttpClient,
 
+    /// Layout of the block results returned by the node
+    block_results_layout: BlockResultsLayout,
+
     /// Poll interval
     poll_interval: Duration,
 
@@ -56,13 +74,24 @@ pub struct EventSource {
 
     /// Last fetched block height
     last_fetched_height: BlockHeight,
+
+    /// Height from which to start collecting events, if not the latest height
+    start_height: Option<BlockHeight>,
+
+    /// Height up to which past events are being replayed, if catching up
+    catch_up_height: Option<BlockHeight>,
+
+    /// Number of failed attempts at fetching a block while catching up
+    catch_up_failures: u32,
 }
 
 impl EventSource {
     pub fn new(
         chain_id: ChainId,
         rpc_client: HttpClient,
+        block_results_layout: BlockResultsLayout,
         poll_interval: Duration,
+        start_height: Option<BlockHeight>,
         rt: Arc<TokioRuntime>,
     ) -> Result<(Self, TxEventSourceCmd)> {
         let event_bus = EventBus::new();
@@ -72,10 +101,14 @@ impl EventSource {
             rt,
             chain_id,
             rpc_client,
+            block_results_layout,
             poll_interval,
             event_bus,
             rx_cmd,
             last_fetched_height: BlockHeight::from(0_u32),
+            start_height,
+            catch_up_height: None,
+            catch_up_failures: 0,
         };
 
         Ok((source, TxEventSourceCmd(tx_cmd)))
@@ -93,7 +126,7 @@ impl EventSource {
 
             // Initialize the latest fetched height
             if let Ok(latest_height) = latest_height(&self.rpc_client).await {
-                self.last_fetched_height = latest_height;
+                self.init_fetched_height(latest_height);
             }
 
             // Continuously run the event loop, so that when it aborts
@@ -108,6 +141,11 @@ impl EventSource {
                         // Reset the backoff
                         backoff = poll_backoff(self.poll_interval);
 
+                        // Do not wait before fetching the next blocks while catching up
+                        if self.catch_up_height.is_some() && self.catch_up_failures == 0 {
+                            continue;
+                        }
+
                         // Check if we need to wait some more before the next iteration.
                         let delay = self.poll_interval.checked_sub(before_step.elapsed());
 
@@ -134,6 +172,33 @@ impl EventSource {
         debug!("shutting down event source");
     }
 
+    /// Sets the last fetched height to the height preceding the start height, if any,
+    /// so that the events emitted from the start height up to the latest height are
+    /// replayed before collecting new events, or to the latest height otherwise.
+    fn init_fetched_height(&mut self, latest_height: BlockHeight) {
+        self.last_fetched_height = latest_height;
+
+        let Some(start_height) = self.start_height else {
+            return;
+        };
+
+        if start_height > latest_height {
+            warn!(
+                "start height ({start_height}) is ahead of the latest height ({latest_height}), \
+                 collecting events from the latest height"
+            );
+
+            return;
+        }
+
+        info!("catching up on events emitted from height {start_height} to {latest_height}");
+
+        if let Ok(height) = BlockHeight::try_from(start_height.value().saturating_sub(1)) {
+            self.last_fetched_height = height;
+            self.catch_up_height = Some(latest_height);
+        }
+    }
+
     async fn step(&mut self) -> Result<Next> {
         // Process any shutdown or subscription commands before we start doing any work
         if let Next::Abort = self.try_process_cmd() {
@@ -194,29 +259,53 @@ impl EventSource {
 
     async fn fetch_batches(&mut self, latest_height: BlockHeight) -> Result<Vec<EventBatch>> {
         let start_height = self.last_fetched_height.increment();
+        let heights = HeightRangeInclusive::new(start_height, latest_height)
+            .take(MAX_BLOCKS_PER_STEP as usize)
+            .collect::<Vec<_>>();
 
-        trace!("fetching blocks from {start_height} to {latest_height}");
-
-        let heights = HeightRangeInclusive::new(start_height, latest_height);
-        let mut batches = Vec::with_capacity(heights.len());
+        trace!(
+            "fetching blocks from {start_height} to {}",
+            heights.last().unwrap_or(&latest_height)
+        );
 
-        for height in heights {
-            trace!("collecting events at height {height}");
+        let (fetched, failed) = collect_events_at(
+            &self.rpc_client,
+            self.block_results_layout,
+            &self.chain_id,
+            &heights,
+        )
+        .await;
 
-            let result = collect_events(&self.rpc_client, &self.chain_id, height).await;
+        let mut batches = Vec::with_capacity(fetched.len());
 
-            match result {
-                Ok(batch) => {
-                    self.last_fetched_height = height;
+        for (height, batch) in fetched {
+            self.last_fetched_height = height;
+            batches.extend(batch);
+        }
 
-                    if let Some(batch) = batch {
-                        batches.push(batch);
-                    }
-                }
-                Err(e) => {
-                    error!(%height, "failed to collect events: {e}");
-                    break;
+        let failed = failed.is_some();
+
+        if let Some(catch_up_height) = self.catch_up_height {
+            if self.last_fetched_height >= catch_up_height {
+                info!("caught up on past events, collecting new events from now on");
+                self.catch_up_height = None;
+            } else if failed {
+                self.catch_up_failures += 1;
+
+                if self.catch_up_failures >= MAX_CATCH_UP_ATTEMPTS {
+                    warn!(
+                        "giving up catching up on events after {} failed attempts at height {}, \
+                         collecting events from the latest height ({latest_height}); \
+                         the packets sent in between will be relayed by packet clearing",
+                        self.catch_up_failures,
+                        self.last_fetched_height.increment()
+                    );
+
+                    self.last_fetched_height = latest_height;
+                    self.catch_up_height = None;
                 }
+            } else {
+                self.catch_up_failures = 0;
             }
         }
 
@@ -289,13 +378,55 @@ fn dedupe(events: Vec<abci::Event>) -> Vec<abci::Event> {
         .collect()
 }
 
+/// Collect the IBC events emitted at the given heights, in order, fetching the
+/// events of up to [`MAX_CONCURRENT_FETCHES`] blocks concurrently.
+///
+/// Stops at the first height at which the events could not be collected,
+/// in which case the events collected at the preceding heights are
+/// returned along with the height and the error which occurred.
+pub(super) async fn collect_events_at(
+    rpc_client: &HttpClient,
+    block_results_layout: BlockResultsLayout,
+    chain_id: &ChainId,
+    heights: &[BlockHeight],
+) -> (
+    Vec<(BlockHeight, Option<EventBatch>)>,
+    Option<(BlockHeight, Error)>,
+) {
+    let mut collected = Vec::with_capacity(heights.len());
+
+    for chunk in heights.chunks(MAX_CONCURRENT_FETCHES) {
+        let results = join_all(chunk.iter().map(|&height| async move {
+            trace!("collecting events at height {height}");
+
+            let result = collect_events(rpc_client, block_results_layout, chain_id, height).await;
+            (height, result)
+        }))
+        .await;
+
+        for (height, result) in results {
+            match result {
+                Ok(batch) => collected.push((height, batch)),
+                Err(e) => {
+                    error!(%height, "failed to collect events: {e}");
+                    return (collected, Some((height, e)));
+                }
+            }
+        }
+    }
+
+    (collected, None)
+}
+
 /// Collect the IBC events from an RPC event
 async fn collect_events(
     rpc_client: &HttpClient,
+    block_results_layout: BlockResultsLayout,
     chain_id: &ChainId,
     latest_block_height: BlockHeight,
 ) -> Result<Option<EventBatch>> {
-    let abci_events = fetch_all_events(rpc_client, latest_block_height).await?;
+    let abci_events =
+        fetch_all_events(rpc_client, block_results_layout, latest_block_height).await?;
     trace!("Found {} ABCI events before dedupe", abci_events.len());
 
     let abci_events = dedupe(abci_events);
@@ -326,34 +457,17 @@ async fn collect_events(
 
 async fn fetch_all_events(
     rpc_client: &HttpClient,
+    block_results_layout: BlockResultsLayout,
     height: BlockHeight,
 ) -> Result<Vec<abci::Event>> {
-    let mut response = rpc_client.block_results(height).await.map_err(Error::rpc)?;
-    let mut events = vec![];
-
-    if let Some(begin_block_events) = &mut response.begin_block_events {
-        events.append(begin_block_events);
-    }
-
-    if let Some(txs_results) = &mut response.txs_results {
-        for tx_result in txs_results {
-            if tx_result.code != abci::Code::Ok {
-                // Transaction failed, skip it
-                continue;
-            }
-
-            events.append(&mut tx_result.events);
-        }
-    }
-
-    if let Some(end_block_events) = &mut response.end_block_events {
-        events.append(end_block_events);
-    }
+    let block_results = fetch_block_results(rpc_client, block_results_layout, height)
+        .await
+        .map_err(Error::rpc)?;
 
-    Ok(events)
+    Ok(block_results.into_events())
 }
 
-async fn latest_height(rpc_client: &HttpClient) -> Result<BlockHeight> {
+pub(super) async fn latest_height(rpc_client: &HttpClient) -> Result<BlockHeight> {
     rpc_client
         .abci_info()
         .await
@@ -12,17 +12,18 @@ use futures::{
 };
 use tokio::task::JoinHandle;
 use tokio::{runtime::Runtime as TokioRuntime, sync::mpsc};
-use tracing::{debug, error, info, instrument, trace};
+use tracing::{debug, error, info, instrument, trace, warn};
 
+use tendermint::block::Height as BlockHeight;
 use tendermint_rpc::{
-    client::CompatMode, event::Event as RpcEvent, query::Query, SubscriptionClient,
+    client::CompatMode, event::Event as RpcEvent, query::Query, HttpClient, SubscriptionClient,
     WebSocketClient, WebSocketClientDriver, WebSocketClientUrl,
 };
 
 use ibc_relayer_types::{core::ics24_host::identifier::ChainId, events::IbcEvent};
 
 use crate::{
-    chain::tracking::TrackingId,
+    chain::{cosmos::compatibility::BlockResultsLayout, tracking::TrackingId},
     event::{bus::EventBus, error::*, IbcEventWithHeight},
     telemetry,
     util::{
@@ -31,7 +32,7 @@ use crate::{
     },
 };
 
-use super::{EventBatch, EventSourceCmd, Result, SubscriptionStream, TxEventSourceCmd};
+use super::{rpc, EventBatch, EventSourceCmd, Result, SubscriptionStream, TxEventSourceCmd};
 
 use self::extract::extract_events;
 
@@ -86,6 +87,13 @@ pub struct EventSource {
     subscriptions: Box<SubscriptionStream>,
     /// Tokio runtime
     rt: Arc<TokioRuntime>,
+    /// RPC client used to fetch the events emitted at the heights
+    /// skipped by the WebSocket connection, if any
+    rpc_client: Option<HttpClient>,
+    /// Layout of the block results returned by the node
+    block_results_layout: BlockResultsLayout,
+    /// Height of the last batch broadcast
+    last_height: Option<u64>,
 }
 
 impl EventSource {
@@ -101,6 +109,8 @@ impl EventSource {
         ws_url: WebSocketClientUrl,
         rpc_compat: CompatMode,
         batch_delay: Duration,
+        rpc_client: Option<HttpClient>,
+        block_results_layout: BlockResultsLayout,
         rt: Arc<TokioRuntime>,
     ) -> Result<(Self, TxEventSourceCmd)> {
         let event_bus = EventBus::new();
@@ -132,6 +142,9 @@ impl EventSource {
             ws_url,
             rpc_compat,
             subscriptions: Box::new(futures::stream::empty()),
+            rpc_client,
+            block_results_layout,
+            last_height: None,
         };
 
         Ok((source, TxEventSourceCmd(tx_cmd)))
@@ -330,7 +343,15 @@ impl EventSource {
             }
 
             match result {
-                Ok(batch) => self.broadcast_batch(batch),
+                Ok(batch) => {
+                    let height = batch.height.revision_height();
+
+                    // Make sure no heights are skipped, eg. after reconnecting
+                    self.backfill(height).await;
+
+                    self.broadcast_batch(batch);
+                    self.last_height = Some(height);
+                }
                 Err(e) => {
                     if let ErrorDetail::SubscriptionCancelled(reason) = e.detail() {
                         error!("subscription cancelled, reason: {}", reason);
@@ -350,6 +371,63 @@ impl EventSource {
         }
     }
 
+    /// Fetch and broadcast the events emitted at the heights between the last batch
+    /// and the batch at the given height, which were skipped by the WebSocket connection,
+    /// eg. while reconnecting, if any.
+    ///
+    /// If the events cannot be fetched, an error is propagated to subscribers,
+    /// so that the [`Supervisor`] clears the pending packets instead.
+    async fn backfill(&mut self, height: u64) {
+        let (start, end) = match Gap::between(self.last_height, height) {
+            Gap::None => return,
+            Gap::Backfill { start, end } => (start, end),
+            Gap::TooLong { start, end } => {
+                warn!(
+                    "heights {start} to {end} were skipped by the WebSocket connection, \
+                     which is more than the {MAX_BACKFILL_HEIGHTS} heights which are backfilled"
+                );
+
+                self.propagate_error(Error::missed_heights(start, end));
+                return;
+            }
+        };
+
+        let Some(rpc_client) = self.rpc_client.clone() else {
+            return;
+        };
+
+        warn!(
+            "heights {start} to {end} were skipped by the WebSocket connection, \
+             fetching their events via /block_results"
+        );
+
+        for step in backfill_steps(start, end) {
+            let (collected, failed) = rpc::collect_events_at(
+                &rpc_client,
+                self.block_results_layout,
+                &self.chain_id,
+                &step,
+            )
+            .await;
+
+            for (_, batch) in collected {
+                if let Some(batch) = batch {
+                    self.broadcast_batch(batch);
+                }
+            }
+
+            if let Some((height, e)) = failed {
+                error!(
+                    "failed to fetch the events emitted at height {height}, \
+                     which was skipped by the WebSocket connection: {e}"
+                );
+
+                self.propagate_error(Error::missed_heights(height.value(), end));
+                return;
+            }
+        }
+    }
+
     /// Propagate error to subscribers.
     ///
     /// The main use case for propagating RPC errors is for the [`Supervisor`]
@@ -394,6 +472,53 @@ impl EventSource {
     }
 }
 
+/// The maximum number of heights skipped by the WebSocket connection whose events are
+/// fetched via `/block_results`. The pending packets are cleared after longer gaps instead.
+const MAX_BACKFILL_HEIGHTS: u64 = 1000;
+
+/// The heights skipped by the WebSocket connection between two batches.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Gap {
+    /// No height was skipped
+    None,
+    /// The heights from `start` to `end` inclusive were skipped, and are to be backfilled
+    Backfill { start: u64, end: u64 },
+    /// The heights from `start` to `end` inclusive were skipped, which is too many to backfill
+    TooLong { start: u64, end: u64 },
+}
+
+impl Gap {
+    /// The gap between the last height for which a batch was broadcast, if any,
+    /// and the height of the next batch.
+    fn between(last_height: Option<u64>, height: u64) -> Self {
+        match last_height {
+            Some(last_height) if height > last_height + 1 => {
+                let (start, end) = (last_height + 1, height - 1);
+
+                if end - start + 1 > MAX_BACKFILL_HEIGHTS {
+                    Self::TooLong { start, end }
+                } else {
+                    Self::Backfill { start, end }
+                }
+            }
+            _ => Self::None,
+        }
+    }
+}
+
+/// The heights from `start` to `end` inclusive, split into the steps
+/// in which their events are fetched via `/block_results`.
+fn backfill_steps(start: u64, end: u64) -> Vec<Vec<BlockHeight>> {
+    let heights = (start..=end)
+        .filter_map(|height| BlockHeight::try_from(height).ok())
+        .collect::<Vec<_>>();
+
+    heights
+        .chunks(rpc::MAX_BLOCKS_PER_STEP as usize)
+        .map(<[_]>::to_vec)
+        .collect()
+}
+
 /// Collect the IBC events from an RPC event
 fn collect_events(
     chain_id: &ChainId,
@@ -468,3 +593,80 @@ pub enum Next {
     Continue,
     Reconnect,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_gap_between_consecutive_heights() {
+        assert_eq!(Gap::between(None, 10), Gap::None);
+        assert_eq!(Gap::between(Some(9), 10), Gap::None);
+
+        // A batch at the same or an earlier height, eg. after reconnecting
+        assert_eq!(Gap::between(Some(10), 10), Gap::None);
+        assert_eq!(Gap::between(Some(11), 10), Gap::None);
+    }
+
+    #[test]
+    fn gap_of_skipped_heights() {
+        assert_eq!(
+            Gap::between(Some(8), 10),
+            Gap::Backfill { start: 9, end: 9 }
+        );
+        assert_eq!(
+            Gap::between(Some(10), 20),
+            Gap::Backfill { start: 11, end: 19 }
+        );
+    }
+
+    #[test]
+    fn gap_up_to_the_limit_is_backfilled() {
+        assert_eq!(
+            Gap::between(Some(10), 11 + MAX_BACKFILL_HEIGHTS),
+            Gap::Backfill {
+                start: 11,
+                end: 10 + MAX_BACKFILL_HEIGHTS
+            }
+        );
+    }
+
+    #[test]
+    fn gap_longer_than_the_limit_is_not_backfilled() {
+        assert_eq!(
+            Gap::between(Some(10), 12 + MAX_BACKFILL_HEIGHTS),
+            Gap::TooLong {
+                start: 11,
+                end: 11 + MAX_BACKFILL_HEIGHTS
+            }
+        );
+    }
+
+    #[test]
+    fn backfill_steps_cover_the_gap() {
+        let steps = backfill_steps(11, 260);
+
+        assert_eq!(
+            steps.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![100, 100, 50]
+        );
+        assert_eq!(steps[0][0].value(), 11);
+        assert_eq!(steps[1][0].value(), 111);
+        assert_eq!(steps[2].last().unwrap().value(), 260);
+
+        assert_eq!(backfill_steps(11, 11).concat().len(), 1);
+    }
+
+    #[test]
+    fn missed_heights_error() {
+        let e = Error::missed_heights(11, 1011);
+
+        assert!(matches!(
+            e.detail(),
+            ErrorDetail::MissedHeights(e) if e.start == 11 && e.end == 1011
+        ));
+        assert!(e
+            .to_string()
+            .starts_with("failed to collect the events emitted from height 11 to 1011"));
+    }
+}
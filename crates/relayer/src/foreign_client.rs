@@ -39,6 +39,7 @@ use crate::error::Error as RelayerError;
 use crate::event::IbcEventWithHeight;
 use crate::light_client::AnyHeader;
 use crate::misbehaviour::MisbehaviourEvidence;
+use crate::notifications::{self, Incident};
 use crate::telemetry;
 use crate::util::collate::CollatedIterExt;
 use crate::util::pretty::{PrettyDuration, PrettySlice};
@@ -677,6 +678,11 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
         Ok(())
     }
 
+    /// Query the latest state of the client and check that it is neither frozen nor expired,
+    /// in which case the time elapsed since its latest update is returned along with it.
+    ///
+    /// The status of the client and the time until it expires are recorded in telemetry,
+    /// which the client refresh worker thereby keeps up to date.
     #[instrument(
         name = "foreign_client.validated_client_state",
         level = "error",
@@ -705,6 +711,15 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
         };
 
         if client_state.is_frozen() {
+            telemetry!(
+                client_expiry,
+                &self.src_chain.id(),
+                &self.dst_chain.id(),
+                &self.id,
+                ClientStatus::Frozen,
+                None
+            );
+
             return Err(ForeignClientError::expired_or_frozen(
                 self.id().clone(),
                 self.dst_chain.id(),
@@ -720,6 +735,21 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
                 network_timestamp,
                 consensus_state_timestmap,
             } => {
+                telemetry!(
+                    client_expiry,
+                    &self.src_chain.id(),
+                    &self.dst_chain.id(),
+                    &self.id,
+                    ClientStatus::Expired,
+                    Some((elapsed, Duration::ZERO))
+                );
+
+                notifications::notify(Incident::ClientExpired {
+                    chain_id: self.dst_chain.id(),
+                    client_id: self.id.clone(),
+                    counterparty_chain_id: self.src_chain.id(),
+                });
+
                 error!(
                     latest_height = %client_state.latest_height(),
                     network_timestmap = %network_timestamp,
@@ -733,7 +763,31 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
                     format!("expired: time elapsed since last client update: {elapsed:?}"),
                 ));
             }
-            ConsensusStateTrusted::Trusted { elapsed } => Ok((client_state, Some(elapsed))),
+            ConsensusStateTrusted::Trusted { elapsed } => {
+                let expires_in = client_state.trusting_period().saturating_sub(elapsed);
+
+                telemetry!(
+                    client_expiry,
+                    &self.src_chain.id(),
+                    &self.dst_chain.id(),
+                    &self.id,
+                    ClientStatus::Active,
+                    Some((elapsed, expires_in))
+                );
+
+                if notifications::config()
+                    .map_or(false, |config| expires_in < config.client_expiry_warning)
+                {
+                    notifications::notify(Incident::ClientExpiring {
+                        chain_id: self.dst_chain.id(),
+                        client_id: self.id.clone(),
+                        counterparty_chain_id: self.src_chain.id(),
+                        expires_in_secs: expires_in.as_secs(),
+                    });
+                }
+
+                Ok((client_state, Some(elapsed)))
+            }
         }
     }
 
@@ -1703,6 +1757,13 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
                     "misbehaviour detected, sending evidence"
                 );
 
+                notifications::notify(Incident::Misbehaviour {
+                    chain_id: self.dst_chain.id(),
+                    client_id: self.id.clone(),
+                    counterparty_chain_id: self.src_chain.id(),
+                    misbehaviour: detected.misbehaviour.to_string(),
+                });
+
                 telemetry!(
                     client_misbehaviours_submitted,
                     &self.src_chain.id(),
@@ -21,6 +21,8 @@
 extern crate alloc;
 
 pub mod account;
+pub mod accounting;
+pub mod audit;
 pub mod cache;
 pub mod chain;
 pub mod channel;
@@ -37,12 +39,14 @@ pub mod keyring;
 pub mod light_client;
 pub mod link;
 pub mod misbehaviour;
+pub mod notifications;
 pub mod object;
 pub mod path;
 pub mod registry;
 pub mod rest;
 pub mod sdk_error;
 pub mod spawn;
+pub mod store;
 pub mod supervisor;
 pub mod telemetry;
 pub mod transfer;
@@ -11,6 +11,7 @@ use crate::chain::{handle::ChainHandle, requests::IncludeProof};
 use crate::channel::{Channel, ChannelSide};
 use crate::link::error::LinkError;
 
+pub mod activity;
 pub mod cli;
 pub mod error;
 pub mod operational_data;
@@ -0,0 +1,44 @@
+//! The outcome of the latest relaying attempts on a relay path,
+//! as reported by the REST API.
+
+use core::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use tendermint::Time;
+
+use crate::util::lock::RwArc;
+
+pub type SharedRelayActivity = RwArc<RelayActivity>;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayActivity {
+    /// When the transactions submitted on the path were last confirmed,
+    /// or last passed CheckTx if `tx_confirmation` is disabled
+    pub last_relay: Option<Time>,
+    /// The last error encountered while relaying on the path
+    pub last_error: Option<RelayError>,
+    /// The number of errors encountered since the messages on the path were last relayed
+    #[serde(default)]
+    pub consecutive_errors: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayError {
+    pub time: Time,
+    pub message: String,
+}
+
+impl RelayActivity {
+    pub fn record_relay(&mut self) {
+        self.last_relay = Some(Time::now());
+        self.consecutive_errors = 0;
+    }
+
+    pub fn record_error(&mut self, error: impl Display) {
+        self.last_error = Some(RelayError {
+            time: Time::now(),
+            message: error.to_string(),
+        });
+        self.consecutive_errors = self.consecutive_errors.saturating_add(1);
+    }
+}
@@ -4,6 +4,7 @@ use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, PortId
 use ibc_relayer_types::events::IbcEvent;
 use ibc_relayer_types::Height;
 
+use crate::chain::cosmos::authz::Error as AuthzError;
 use crate::channel::ChannelError;
 use crate::connection::ConnectionError;
 use crate::error::Error;
@@ -148,6 +149,13 @@ define_error! {
 
         UpdateClientFailed
              |_| { "failed to update client" },
+
+        AuthzGranter
+            { chain_id: ChainId }
+            [ AuthzError ]
+            |e| {
+                format!("invalid authz granter configured for chain {}", e.chain_id)
+            },
    }
 }
 
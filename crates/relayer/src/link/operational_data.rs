@@ -3,11 +3,15 @@ use std::ops::Add;
 use std::time::{Duration, Instant};
 
 use ibc_proto::google::protobuf::Any;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
 use ibc_relayer_types::core::ics02_client::client_state::ClientState;
+use ibc_relayer_types::events::IbcEvent;
 use ibc_relayer_types::Height;
 
+use crate::chain::cosmos::authz::wrap_in_msg_exec;
 use crate::chain::handle::ChainHandle;
 use crate::chain::requests::IncludeProof;
 use crate::chain::requests::QueryClientStateRequest;
@@ -19,7 +23,7 @@ use crate::link::error::LinkError;
 use crate::link::RelayPath;
 
 /// The chain that the events associated with a piece of [`OperationalData`] are bound for.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OperationalDataTarget {
     /// The chain which generated the events associated with the `OperationalData`.
     Source,
@@ -136,6 +140,26 @@ impl OperationalData {
         }
     }
 
+    /// The sequences of the packets relayed by this operational data, separated by commas,
+    /// as recorded in the spans which follow its events along the relaying pipeline.
+    pub fn packet_sequences(&self) -> String {
+        self.batch
+            .iter()
+            .filter_map(|msg| msg.event_with_height.event.packet())
+            .map(|packet| packet.sequence)
+            .join(",")
+    }
+
+    /// Whether this operational data relays the acknowledgements of packets.
+    pub fn relays_acks(&self) -> bool {
+        self.batch.iter().any(|msg| {
+            matches!(
+                msg.event_with_height.event,
+                IbcEvent::WriteAcknowledgement(_)
+            )
+        })
+    }
+
     /// Transforms `self` into the list of events accompanied with the tracking ID.
     pub fn into_events(self) -> TrackedEvents {
         let events = self
@@ -210,9 +234,18 @@ impl OperationalData {
             }
         };
 
+        // When an `authz_granter` is configured, the relayer executes the relay
+        // messages on its behalf. The client update is signed by the relayer itself.
+        // The messages are wrapped before they are batched by the chain, so that
+        // the size of the `MsgExec` wrappers counts towards `max_tx_size`.
+        let authz_grantee = relay_path.authz_grantee(self.target)?;
+
         let msgs = client_update_msg
             .into_iter()
-            .chain(self.batch.iter().map(|gm| gm.msg.clone()))
+            .chain(self.batch.iter().map(|gm| match &authz_grantee {
+                Some(grantee) => wrap_in_msg_exec(grantee, gm.msg.clone()),
+                None => gm.msg.clone(),
+            }))
             .collect();
 
         let tm = TrackedMsgs::new(msgs, self.tracking_id);
@@ -2,16 +2,23 @@ use core::iter::Iterator;
 use core::time::Duration;
 use std::time::Instant;
 
-use tracing::{debug, error, trace, trace_span};
+use tendermint::Hash as TxHash;
+use tracing::{debug, error, info_span, trace, trace_span, warn};
 
 use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc_relayer_types::events::IbcEvent;
 
+use crate::accounting;
+use crate::audit::{self, PacketMsgKind, PacketRecord};
 use crate::chain::requests::{QueryTxHash, QueryTxRequest};
-use crate::chain::tracking::TrackingId;
+use crate::chain::tracking::{link_packet_traces, TrackingId, RELAY_SPAN_TARGET};
 use crate::error::Error as RelayerError;
+use crate::event::monitor::{self, RelayerEvent, RelayerEventKind};
+use crate::event::IbcEventWithHeight;
 use crate::link::{error::LinkError, RelayPath};
 use crate::telemetry;
+use crate::util::append_log;
+use crate::util::lock::LockExt;
 use crate::util::queue::Queue;
 use crate::{
     chain::handle::ChainHandle,
@@ -126,20 +133,64 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
         self.pending_queue.push_back(u);
     }
 
-    fn check_tx_events(&self, tx_hashes: &TxHashes) -> Result<Option<Vec<IbcEvent>>, RelayerError> {
+    /// Insert a pending transaction restored from the state store to the back of the queue.
+    pub fn insert_restored_pending_tx(&self, tx_hashes: TxHashes, od: OperationalData) {
+        let u = PendingData {
+            original_od: od,
+            tx_hashes,
+            submit_time: Instant::now(),
+            error_events: Vec::new(),
+        };
+
+        self.pending_queue.push_back(u);
+    }
+
+    /// The events of the given transactions, along with the hash of the transaction
+    /// which emitted them, or `None` if any of the transactions is not yet committed.
+    fn check_tx_events(
+        &self,
+        tx_hashes: &TxHashes,
+    ) -> Result<Option<Vec<(TxHash, IbcEventWithHeight)>>, RelayerError> {
         let mut all_events = Vec::new();
         for hash in &tx_hashes.0 {
-            let mut events = self
+            let events = self
                 .chain
                 .query_txs(QueryTxRequest::Transaction(QueryTxHash(*hash)))?;
 
             if events.is_empty() {
                 return Ok(None);
             } else {
-                all_events.append(&mut events)
+                all_events.extend(events.into_iter().map(|ev| (*hash, ev)))
             }
         }
-        Ok(Some(all_events.into_iter().map(|ev| ev.event).collect()))
+        Ok(Some(all_events))
+    }
+
+    /// Records the packet messages among the given confirmed events to the audit log.
+    fn audit_packet_msgs<ChainA: ChainHandle, ChainB: ChainHandle>(
+        &self,
+        tracking_id: TrackingId,
+        relay_path: &RelayPath<ChainA, ChainB>,
+        events: &[(TxHash, IbcEventWithHeight)],
+    ) {
+        let signer = match self.chain.get_signer() {
+            Ok(signer) => signer.to_string(),
+            Err(e) => {
+                warn!("failed to get signer for the packet audit log: {}", e);
+                String::new()
+            }
+        };
+
+        let records = packet_records(
+            &relay_path.src_chain().id(),
+            &relay_path.dst_chain().id(),
+            &signer,
+            append_log::now(),
+            tracking_id,
+            events,
+        );
+
+        audit::record(&records);
     }
 
     /// Try and process one pending transaction within the given timeout duration if one
@@ -203,6 +254,23 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
 
                                 match new_od.map(f) {
                                     Some(Ok(reply)) => {
+                                        monitor::publish(|| {
+                                            let tx_hashes = reply
+                                                .responses
+                                                .iter()
+                                                .map(|response| response.hash)
+                                                .collect::<Vec<_>>();
+
+                                            RelayerEvent::new(
+                                                self.chain_id(),
+                                                Some(self.channel_id.clone()),
+                                                RelayerEventKind::tx_submitted(
+                                                    pending.tracking_id(),
+                                                    &tx_hashes,
+                                                ),
+                                            )
+                                        });
+
                                         self.insert_new_pending_tx(reply, pending.original_od);
                                         Ok(None)
                                     }
@@ -229,11 +297,20 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
                         Ok(None)
                     }
                 }
-                Ok(Some(mut events)) => {
+                Ok(Some(tx_events)) => {
                     // We get a list of events for the transaction hashes,
                     // Meaning the transaction has been committed successfully
                     // to the chain.
 
+                    if audit::is_enabled() {
+                        self.audit_packet_msgs(pending.tracking_id(), relay_path, &tx_events);
+                    }
+
+                    let mut events = tx_events
+                        .into_iter()
+                        .map(|(_, ev)| ev.event)
+                        .collect::<Vec<_>>();
+
                     debug!(
                         tracking_id = %pending.tracking_id(),
                         elapsed = ?pending.submit_time.elapsed(),
@@ -241,6 +318,32 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
                         "transactions confirmed",
                     );
 
+                    let tracking_id = pending.tracking_id();
+                    let span = if pending.original_od.relays_acks() {
+                        info_span!(
+                            target: RELAY_SPAN_TARGET,
+                            "ack_relayed",
+                            chain = %self.chain_id(),
+                            channel = %self.channel_id,
+                            tracking_id = %tracking_id,
+                            sequences = %pending.original_od.packet_sequences(),
+                            tx_hashes = %tx_hashes,
+                        )
+                    } else {
+                        info_span!(
+                            target: RELAY_SPAN_TARGET,
+                            "tx_confirmed",
+                            chain = %self.chain_id(),
+                            channel = %self.channel_id,
+                            tracking_id = %tracking_id,
+                            sequences = %pending.original_od.packet_sequences(),
+                            tx_hashes = %tx_hashes,
+                        )
+                    };
+                    let span = tracking_id.trace(span);
+                    link_packet_traces(&span, relay_path.packet_trace_ids(&pending.original_od));
+                    let _span = span.entered();
+
                     telemetry!(
                         tx_confirmed,
                         tx_hashes.0.len(),
@@ -251,6 +354,26 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
                         &self.counterparty_chain_id
                     );
 
+                    monitor::publish(|| {
+                        RelayerEvent::new(
+                            self.chain_id(),
+                            Some(self.channel_id.clone()),
+                            RelayerEventKind::tx_confirmed(
+                                pending.tracking_id(),
+                                &tx_hashes.0,
+                                events.len(),
+                            ),
+                        )
+                    });
+
+                    // The messages are only relayed once their transactions are committed without error
+                    if !events
+                        .iter()
+                        .any(|event| matches!(event, IbcEvent::ChainError(_)))
+                    {
+                        relay_path.activity().acquire_write().record_relay();
+                    }
+
                     // Append the events corresponding to errors from the pending tx.
                     events.extend(pending.error_events);
 
@@ -288,3 +411,113 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
         }
     }
 }
+
+/// The audit records of the packet messages among the given events, confirmed for the path
+/// from the chain `path_src_chain_id` to the chain `path_dst_chain_id`.
+///
+/// The acknowledgements relayed on a path are those of the packets sent from its destination
+/// chain, hence the source and destination chains of their packets are the reverse of the path.
+fn packet_records(
+    path_src_chain_id: &ChainId,
+    path_dst_chain_id: &ChainId,
+    signer: &str,
+    time: u64,
+    tracking_id: TrackingId,
+    events: &[(TxHash, IbcEventWithHeight)],
+) -> Vec<PacketRecord> {
+    events
+        .iter()
+        .filter_map(|(tx_hash, ev)| {
+            let (kind, packet) = PacketMsgKind::from_event(&ev.event)?;
+
+            let (src_chain_id, dst_chain_id) = match kind {
+                PacketMsgKind::Ack => (path_dst_chain_id, path_src_chain_id),
+                PacketMsgKind::Recv | PacketMsgKind::Timeout | PacketMsgKind::TimeoutOnClose => {
+                    (path_src_chain_id, path_dst_chain_id)
+                }
+            };
+
+            Some(PacketRecord {
+                time,
+                kind,
+                src_chain_id: src_chain_id.clone(),
+                src_port_id: packet.source_port.clone(),
+                src_channel_id: packet.source_channel.clone(),
+                dst_chain_id: dst_chain_id.clone(),
+                dst_port_id: packet.destination_port.clone(),
+                dst_channel_id: packet.destination_channel.clone(),
+                sequence: packet.sequence,
+                tx_hash: tx_hash.to_string(),
+                height: ev.height,
+                signer: signer.to_string(),
+                fee: accounting::fee_per_msg(tx_hash).unwrap_or_default(),
+                tracking_id: tracking_id.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc_relayer_types::core::ics04_channel::events as channel_events;
+    use ibc_relayer_types::Height;
+    use tendermint::abci::{Event as AbciEvent, EventAttribute};
+
+    use crate::event::ibc_event_try_from_abci_event;
+
+    #[test]
+    fn ack_records_are_on_the_reverse_path() {
+        let attribute = |key: &str, value: &str| EventAttribute {
+            key: key.to_string(),
+            value: value.to_string(),
+            index: true,
+        };
+
+        // The acknowledgement, confirmed on chain B, of a packet sent from chain B to chain A
+        let abci_event = AbciEvent {
+            kind: "acknowledge_packet".to_string(),
+            attributes: vec![
+                attribute(channel_events::PKT_SEQ_ATTRIBUTE_KEY, "7"),
+                attribute(channel_events::PKT_SRC_PORT_ATTRIBUTE_KEY, "transfer"),
+                attribute(channel_events::PKT_SRC_CHANNEL_ATTRIBUTE_KEY, "channel-1"),
+                attribute(channel_events::PKT_DST_PORT_ATTRIBUTE_KEY, "transfer"),
+                attribute(channel_events::PKT_DST_CHANNEL_ATTRIBUTE_KEY, "channel-0"),
+                attribute(channel_events::PKT_TIMEOUT_HEIGHT_ATTRIBUTE_KEY, "0-0"),
+                attribute(channel_events::PKT_TIMEOUT_TIMESTAMP_ATTRIBUTE_KEY, "0"),
+            ],
+        };
+
+        let event = ibc_event_try_from_abci_event(&abci_event).unwrap();
+        assert!(matches!(event, IbcEvent::AcknowledgePacket(_)));
+
+        let chain_a = ChainId::from_string("chain-a");
+        let chain_b = ChainId::from_string("chain-b");
+        let tx_hash = TxHash::Sha256([1; 32]);
+        let height = Height::new(0, 42).unwrap();
+
+        // Relayed on the path from chain A to chain B
+        let records = packet_records(
+            &chain_a,
+            &chain_b,
+            "cosmos1relayer",
+            1685577600,
+            TrackingId::new_cleared_uuid(),
+            &[(tx_hash, IbcEventWithHeight::new(event, height))],
+        );
+
+        assert_eq!(records.len(), 1);
+
+        let record = &records[0];
+        assert_eq!(record.kind, PacketMsgKind::Ack);
+        assert_eq!(record.src_chain_id, chain_b);
+        assert_eq!(record.src_channel_id, ChannelId::new(1));
+        assert_eq!(record.dst_chain_id, chain_a);
+        assert_eq!(record.dst_channel_id, ChannelId::new(0));
+        assert_eq!(record.chain_id(), &chain_b);
+        assert!(record.involves(&chain_b, &ChannelId::new(1)));
+        assert!(record.involves(&chain_a, &ChannelId::new(0)));
+        assert!(!record.involves(&chain_a, &ChannelId::new(1)));
+    }
+}
@@ -5,7 +5,7 @@ use std::time::{Duration, Instant};
 
 use ibc_proto::google::protobuf::Any;
 use itertools::Itertools;
-use tracing::{debug, error, info, span, trace, warn, Level};
+use tracing::{debug, error, field, info, info_span, span, trace, warn, Level};
 
 use ibc_relayer_types::core::ics02_client::events::ClientMisbehaviour as ClientMisbehaviourEvent;
 use ibc_relayer_types::core::ics04_channel::channel::{
@@ -24,6 +24,7 @@ use ibc_relayer_types::timestamp::Timestamp;
 use ibc_relayer_types::tx_msg::Msg;
 use ibc_relayer_types::Height;
 
+use crate::chain::cosmos::authz;
 use crate::chain::counterparty::unreceived_acknowledgements;
 use crate::chain::counterparty::unreceived_packets;
 use crate::chain::endpoint::ChainStatus;
@@ -40,11 +41,15 @@ use crate::chain::requests::QueryUnreceivedPacketsRequest;
 use crate::chain::requests::{IncludeProof, Qualified};
 use crate::chain::tracking::TrackedMsgs;
 use crate::chain::tracking::TrackingId;
+use crate::chain::tracking::RELAY_SPAN_TARGET;
+use crate::chain::tracking::{link_packet_traces, packet_trace_id};
 use crate::channel::error::ChannelError;
 use crate::channel::Channel;
+use crate::event::monitor::{self, RelayerEvent, RelayerEventKind};
 use crate::event::source::EventBatch;
 use crate::event::IbcEventWithHeight;
 use crate::foreign_client::{ForeignClient, ForeignClientError};
+use crate::link::activity::{RelayActivity, SharedRelayActivity};
 use crate::link::error::{self, LinkError};
 use crate::link::operational_data::{
     OperationalData, OperationalDataTarget, TrackedEvents, TransitMessage,
@@ -55,10 +60,14 @@ use crate::link::packet_events::query_write_ack_events;
 use crate::link::pending::PendingTxs;
 use crate::link::relay_sender::{AsyncReply, SubmitReply};
 use crate::link::relay_summary::RelaySummary;
+use crate::link::tx_hashes::TxHashes;
 use crate::link::{pending, relay_sender};
+use crate::object::Packet as PacketPath;
 use crate::path::PathIdentifiers;
+use crate::store::{PathState, SharedStateStore, StoredEvent, StoredPendingTx};
 use crate::telemetry;
 use crate::util::collate::CollatedIterExt;
+use crate::util::lock::LockExt;
 use crate::util::pretty::PrettyEvents;
 use crate::util::queue::Queue;
 
@@ -108,6 +117,18 @@ pub struct RelayPath<ChainA: ChainHandle, ChainB: ChainHandle> {
     // transactions if [`confirm_txes`] is true.
     pending_txs_src: PendingTxs<ChainA>,
     pending_txs_dst: PendingTxs<ChainB>,
+
+    // The accounts on whose behalf the relay messages are executed, if
+    // an `authz_granter` is configured for the source or destination chain.
+    src_authz_granter: Option<Signer>,
+    dst_authz_granter: Option<Signer>,
+
+    // Persists the pending transactions and the scheduled operational data,
+    // to resume relaying on this path after a restart.
+    state_store: Option<SharedStateStore>,
+
+    // The outcome of the latest relaying attempts on this path.
+    activity: SharedRelayActivity,
 }
 
 impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
@@ -134,6 +155,9 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         let src_port_id = channel.src_port_id().clone();
         let dst_port_id = channel.dst_port_id().clone();
 
+        let src_authz_granter = authz_granter_from_config(&src_chain)?;
+        let dst_authz_granter = authz_granter_from_config(&dst_chain)?;
+
         let path = PathIdentifiers {
             port_id: dst_port_id.clone(),
             channel_id: dst_channel_id.clone(),
@@ -152,6 +176,13 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             confirm_txes: with_tx_confirmation,
             pending_txs_src: PendingTxs::new(src_chain, src_channel_id, src_port_id, dst_chain_id),
             pending_txs_dst: PendingTxs::new(dst_chain, dst_channel_id, dst_port_id, src_chain_id),
+
+            src_authz_granter,
+            dst_authz_granter,
+
+            state_store: None,
+
+            activity: SharedRelayActivity::new_lock(RelayActivity::default()),
         })
     }
 
@@ -163,6 +194,11 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         self.channel.dst_chain()
     }
 
+    /// The outcome of the latest relaying attempts on this path.
+    pub fn activity(&self) -> SharedRelayActivity {
+        self.activity.clone()
+    }
+
     pub fn src_client_id(&self) -> &ClientId {
         self.channel.src_client_id()
     }
@@ -227,16 +263,49 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             .map_err(|e| LinkError::channel(ChannelError::query(self.dst_chain().id(), e)))
     }
 
+    /// The signer of the messages relayed to the source chain, ie. the
+    /// `authz_granter` of the source chain if any, or the relayer account.
     fn src_signer(&self) -> Result<Signer, LinkError> {
-        self.src_chain()
-            .get_signer()
-            .map_err(|e| LinkError::signer(self.src_chain().id(), e))
+        match &self.src_authz_granter {
+            Some(granter) => Ok(granter.clone()),
+            None => self
+                .src_chain()
+                .get_signer()
+                .map_err(|e| LinkError::signer(self.src_chain().id(), e)),
+        }
     }
 
+    /// The signer of the messages relayed to the destination chain, ie. the
+    /// `authz_granter` of the destination chain if any, or the relayer account.
     fn dst_signer(&self) -> Result<Signer, LinkError> {
-        self.dst_chain()
-            .get_signer()
-            .map_err(|e| LinkError::signer(self.dst_chain().id(), e))
+        match &self.dst_authz_granter {
+            Some(granter) => Ok(granter.clone()),
+            None => self
+                .dst_chain()
+                .get_signer()
+                .map_err(|e| LinkError::signer(self.dst_chain().id(), e)),
+        }
+    }
+
+    /// Returns the relayer account, which executes the relayed messages on behalf of
+    /// the `authz_granter` of the target chain, or `None` if no granter is configured.
+    pub(crate) fn authz_grantee(
+        &self,
+        target: OperationalDataTarget,
+    ) -> Result<Option<Signer>, LinkError> {
+        match target {
+            OperationalDataTarget::Source if self.src_authz_granter.is_some() => self
+                .src_chain()
+                .get_signer()
+                .map(Some)
+                .map_err(|e| LinkError::signer(self.src_chain().id(), e)),
+            OperationalDataTarget::Destination if self.dst_authz_granter.is_some() => self
+                .dst_chain()
+                .get_signer()
+                .map(Some)
+                .map_err(|e| LinkError::signer(self.dst_chain().id(), e)),
+            _ => Ok(None),
+        }
     }
 
     pub(crate) fn src_latest_height(&self) -> Result<Height, LinkError> {
@@ -479,10 +548,34 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         &self,
         events: TrackedEvents,
     ) -> Result<(), LinkError> {
+        let tracking_id = events.tracking_id();
+        let span = tracking_id
+            .trace(info_span!(
+                target: RELAY_SPAN_TARGET,
+                "operational_data_built",
+                chain = %self.src_chain().id(),
+                channel = %self.src_channel_id(),
+                tracking_id = %tracking_id,
+                sequences = field::Empty,
+                messages = field::Empty,
+            ))
+            .entered();
+
         // Obtain the operational data for the source chain (mostly timeout packets) and for the
         // destination chain (e.g., receive packet messages).
         let (src_opt, dst_opt) = self.generate_operational_data(events)?;
 
+        let built = src_opt.iter().chain(dst_opt.iter());
+        span.record(
+            "sequences",
+            built.clone().map(|od| od.packet_sequences()).join(","),
+        );
+        span.record(
+            "messages",
+            built.clone().map(|od| od.batch.len()).sum::<usize>(),
+        );
+        link_packet_traces(&span, built.flat_map(|od| self.packet_trace_ids(od)));
+
         if let Some(src_od) = src_opt {
             self.schedule_operational_data(src_od)?;
         }
@@ -791,6 +884,22 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
 
         let msgs = odata.assemble_msgs(self)?;
 
+        monitor::publish(|| {
+            let (chain, _, channel_id, _) = self.target_info(odata.target);
+
+            RelayerEvent::new(
+                chain,
+                Some(channel_id.clone()),
+                RelayerEventKind::messages_assembled(
+                    odata.tracking_id,
+                    msgs.messages()
+                        .iter()
+                        .map(|msg| msg.type_url.clone())
+                        .collect(),
+                ),
+            )
+        });
+
         match odata.target {
             OperationalDataTarget::Source => S::submit(self.src_chain(), msgs),
             OperationalDataTarget::Destination => S::submit(self.dst_chain(), msgs),
@@ -799,6 +908,13 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
 
     fn enqueue_pending_tx(&self, reply: AsyncReply, odata: OperationalData) {
         if !self.confirm_txes {
+            // Without confirmation, the messages are deemed relayed once their transactions pass CheckTx
+            if !reply.responses.is_empty()
+                && reply.responses.iter().all(|response| response.code.is_ok())
+            {
+                self.activity.acquire_write().record_relay();
+            }
+
             return;
         }
 
@@ -1428,11 +1544,54 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     if elapsed {
                         // The current piece of operational data has elapsed; we can go ahead and
                         // attempt to relay it.
+                        let (chain, _, channel_id, _) = self.target_info(od.target);
+                        let span = od
+                            .tracking_id
+                            .trace(info_span!(
+                                target: RELAY_SPAN_TARGET,
+                                "tx_submitted",
+                                chain = %chain,
+                                channel = %channel_id,
+                                tracking_id = %od.tracking_id,
+                                sequences = %od.packet_sequences(),
+                                messages = od.batch.len(),
+                                tx_hashes = field::Empty,
+                            ))
+                            .entered();
+                        link_packet_traces(&span, self.packet_trace_ids(&od));
+
                         match self
                             .relay_from_operational_data::<relay_sender::AsyncSender>(od.clone())
                         {
                             // The operational data was successfully relayed; enqueue the associated tx.
-                            Ok(reply) => self.enqueue_pending_tx(reply, od),
+                            Ok(reply) => {
+                                span.record(
+                                    "tx_hashes",
+                                    reply
+                                        .responses
+                                        .iter()
+                                        .map(|response| response.hash)
+                                        .join(","),
+                                );
+                                drop(span);
+
+                                monitor::publish(|| {
+                                    let (chain, _, channel_id, _) = self.target_info(od.target);
+                                    let tx_hashes = reply
+                                        .responses
+                                        .iter()
+                                        .map(|response| response.hash)
+                                        .collect::<Vec<_>>();
+
+                                    RelayerEvent::new(
+                                        chain,
+                                        Some(channel_id.clone()),
+                                        RelayerEventKind::tx_submitted(od.tracking_id, &tx_hashes),
+                                    )
+                                });
+
+                                self.enqueue_pending_tx(reply, od)
+                            }
                             // The relaying process failed; return all of the subsequent pieces of operational
                             // data along with the underlying error that occurred.
                             Err(e) => {
@@ -1555,6 +1714,137 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Ok(res)
     }
 
+    /// The packet worker path served by this relay path, under which its state is stored.
+    fn packet_path(&self) -> PacketPath {
+        PacketPath {
+            dst_chain_id: self.dst_chain().id(),
+            src_chain_id: self.src_chain().id(),
+            src_channel_id: self.src_channel_id().clone(),
+            src_port_id: self.src_port_id().clone(),
+        }
+    }
+
+    /// Restores the pending transactions and the scheduled operational data of this path
+    /// from the given store, which is then kept up-to-date by [`RelayPath::persist_state`].
+    ///
+    /// The operational data is regenerated from the stored events, so that the
+    /// events which were handled in the meantime are not relayed again.
+    ///
+    /// Returns whether some state was stored for this path.
+    pub fn restore_state(&mut self, store: SharedStateStore) -> bool {
+        let _span = span!(Level::ERROR, "restore_state").entered();
+
+        let state = store.path_state(&self.packet_path());
+        let restored = state.is_some();
+
+        if let Some(state) = state {
+            info!(
+                pending_txs = state.pending_txs.len(),
+                scheduled = state.scheduled.len(),
+                "restoring relay path state"
+            );
+
+            for pending_tx in state.pending_txs {
+                self.restore_pending_tx(pending_tx);
+            }
+
+            let events = state
+                .scheduled
+                .into_iter()
+                .filter_map(StoredEvent::into_event)
+                .collect();
+
+            let tracked_events = TrackedEvents::new(events, TrackingId::new_static("restored"));
+
+            if let Err(e) = self.events_to_operational_data(tracked_events) {
+                error!("failed to restore scheduled operational data: {}", e);
+            }
+        }
+
+        self.state_store = Some(store);
+
+        restored
+    }
+
+    fn restore_pending_tx(&self, pending_tx: StoredPendingTx) {
+        let tx_hashes = TxHashes(pending_tx.tx_hashes);
+
+        let events = pending_tx
+            .events
+            .into_iter()
+            .filter_map(StoredEvent::into_event)
+            .collect();
+
+        let tracked_events = TrackedEvents::new(events, TrackingId::new_static("restored"));
+
+        // If the transaction was committed in the meantime, its events have been
+        // handled and no operational data is generated for them.
+        let (src_od, dst_od) = match self.generate_operational_data(tracked_events) {
+            Ok(ods) => ods,
+            Err(e) => {
+                error!(
+                    "failed to restore pending transactions {}: {}",
+                    tx_hashes, e
+                );
+                return;
+            }
+        };
+
+        for od in [src_od, dst_od].into_iter().flatten() {
+            if od.target != pending_tx.target {
+                // New timeouts, which are scheduled separately
+                if let Err(e) = self.schedule_operational_data(od) {
+                    error!("failed to schedule operational data: {}", e);
+                }
+            } else if od.target == OperationalDataTarget::Source {
+                self.pending_txs_src
+                    .insert_restored_pending_tx(tx_hashes.clone(), od);
+            } else {
+                self.pending_txs_dst
+                    .insert_restored_pending_tx(tx_hashes.clone(), od);
+            }
+        }
+    }
+
+    /// Writes the pending transactions and the scheduled operational data
+    /// of this path to the state store, if any.
+    pub fn persist_state(&self) {
+        let Some(store) = &self.state_store else {
+            return;
+        };
+
+        let pending_txs = self
+            .pending_txs_src
+            .pending_queue
+            .clone_vec()
+            .into_iter()
+            .chain(self.pending_txs_dst.pending_queue.clone_vec())
+            .filter(|pending| !pending.tx_hashes.0.is_empty())
+            .map(|pending| StoredPendingTx {
+                target: pending.original_od.target,
+                tx_hashes: pending.tx_hashes.0,
+                events: stored_events(&pending.original_od),
+            })
+            .collect();
+
+        let scheduled = self
+            .src_operational_data
+            .clone_vec()
+            .iter()
+            .chain(self.dst_operational_data.clone_vec().iter())
+            .flat_map(stored_events)
+            .collect();
+
+        let state = PathState {
+            pending_txs,
+            scheduled,
+        };
+
+        if let Err(e) = store.set_path_state(&self.packet_path(), state) {
+            error!("failed to persist relay path state: {}", e);
+        }
+    }
+
     /// Refreshes the scheduled batches.
     /// Verifies if any sendPacket messages timed-out. If so, moves them from destination op. data
     /// to source operational data, and adjusts the events and messages accordingly.
@@ -1773,8 +2063,6 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         )
     }
 
-    // we need fully qualified ChainId to avoid unneeded imports warnings
-    #[cfg(feature = "telemetry")]
     fn target_info(
         &self,
         target: OperationalDataTarget,
@@ -1800,6 +2088,35 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         }
     }
 
+    /// The identifiers of the traces of the packets relayed by the given operational data,
+    /// see [`packet_trace_id`].
+    pub(crate) fn packet_trace_ids(&self, od: &OperationalData) -> Vec<u128> {
+        let src_chain_id = self.src_chain().id();
+        let dst_chain_id = self.dst_chain().id();
+
+        od.batch
+            .iter()
+            .filter_map(|msg| {
+                let event = &msg.event_with_height.event;
+                let packet = event.packet()?;
+
+                // The acknowledgements relayed on this path are those of the packets sent
+                // from the destination chain, the other packets are sent from the source chain
+                let chain_id = match event {
+                    IbcEvent::WriteAcknowledgement(_) => &dst_chain_id,
+                    _ => &src_chain_id,
+                };
+
+                Some(packet_trace_id(
+                    chain_id,
+                    &packet.source_port,
+                    &packet.source_channel,
+                    packet.sequence,
+                ))
+            })
+            .collect()
+    }
+
     #[cfg(feature = "telemetry")]
     fn backlog_update(&self, event: &IbcEvent) {
         match event {
@@ -1875,3 +2192,27 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         }
     }
 }
+
+/// The stored representation of the events from which the given operational data was built.
+fn stored_events(od: &OperationalData) -> Vec<StoredEvent> {
+    od.batch
+        .iter()
+        .filter_map(|msg| StoredEvent::from_event(&msg.event_with_height))
+        .collect()
+}
+
+/// Returns the `authz_granter` configured for the given chain, if any.
+fn authz_granter_from_config<Chain: ChainHandle>(
+    chain: &Chain,
+) -> Result<Option<Signer>, LinkError> {
+    let config = chain.config().map_err(LinkError::relayer)?;
+
+    config
+        .authz_granter
+        .as_deref()
+        .map(|granter| {
+            authz::parse_granter(granter, &config.account_prefix)
+                .map_err(|e| LinkError::authz_granter(chain.id(), e))
+        })
+        .transpose()
+}
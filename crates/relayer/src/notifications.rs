@@ -0,0 +1,655 @@
+//! Notifications posted as JSON to the webhooks configured in the `[notifications]`
+//! section when incidents occur while relaying, so that operators are alerted
+//! without having to watch the metrics of the relayer.
+//!
+//! The incidents are reported by the components which detect them, and are posted
+//! by a background thread, which drops the incidents already notified within the
+//! dedup window, and defers the notifications beyond the rate limit until the rate
+//! limit allows them, so that the incidents which only occur once are not lost.
+//!
+//! Besides the fields of the incident, each notification has a `text` field
+//! summarizing it, so that it can be posted as is to chat webhooks.
+
+use alloc::collections::VecDeque;
+use std::collections::HashMap;
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{self as channel, RecvTimeoutError};
+use flex_error::{define_error, TraceError};
+use serde::Serialize;
+use tendermint::Time;
+use tracing::{debug, error_span, warn};
+
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
+
+use crate::config::NotificationsConfig;
+use crate::supervisor::paths::Backlog;
+use crate::util::append_log::GlobalSink;
+
+/// How long to wait for a webhook to respond to a notification.
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The period over which the rate limit applies.
+const RATE_LIMIT_PERIOD: Duration = Duration::from_secs(60);
+
+/// The maximum number of notifications deferred by the rate limit, beyond which they are dropped.
+const MAX_DEFERRED: usize = 100;
+
+define_error! {
+    Error {
+        InvalidWebhook
+            { url: String, reason: String }
+            |e| { format!("invalid webhook URL '{}': {}", e.url, e.reason) },
+
+        HttpClient
+            [ TraceError<reqwest::Error> ]
+            |_| { "failed to build the HTTP client posting notifications" },
+
+        Runtime
+            [ TraceError<io::Error> ]
+            |_| { "failed to create the runtime posting notifications" },
+    }
+}
+
+/// An incident which occurred while relaying.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "incident", rename_all = "snake_case")]
+pub enum Incident {
+    /// A client will expire soon if it is not updated
+    ClientExpiring {
+        chain_id: ChainId,
+        client_id: ClientId,
+        counterparty_chain_id: ChainId,
+        expires_in_secs: u64,
+    },
+
+    /// A client has expired
+    ClientExpired {
+        chain_id: ChainId,
+        client_id: ClientId,
+        counterparty_chain_id: ChainId,
+    },
+
+    /// The balance of the relayer wallet fell below its minimum or critical balance
+    LowBalance {
+        chain_id: ChainId,
+        wallet: String,
+        balance: String,
+        denom: String,
+        critical: bool,
+    },
+
+    /// Packets have been pending on a path for too long
+    PacketsStuck {
+        chain_id: ChainId,
+        port_id: PortId,
+        channel_id: ChannelId,
+        counterparty_chain_id: ChainId,
+        unreceived_packets: Backlog,
+        unreceived_acks: Backlog,
+    },
+
+    /// Relaying on a path failed several times in a row
+    TxFailures {
+        chain_id: ChainId,
+        port_id: PortId,
+        channel_id: ChannelId,
+        counterparty_chain_id: ChainId,
+        failures: u32,
+        last_error: String,
+    },
+
+    /// The event source of a chain fell back from WebSocket to polling
+    EndpointFailover {
+        chain_id: ChainId,
+        endpoint: String,
+        reason: String,
+    },
+
+    /// Misbehaviour was detected for a client, and evidence of it submitted
+    Misbehaviour {
+        chain_id: ChainId,
+        client_id: ClientId,
+        counterparty_chain_id: ChainId,
+        misbehaviour: String,
+    },
+}
+
+impl Incident {
+    /// The key identifying the incident, such that the incidents with
+    /// the same key are only notified once within the dedup window.
+    pub fn key(&self) -> String {
+        match self {
+            Self::ClientExpiring {
+                chain_id,
+                client_id,
+                ..
+            } => format!("client_expiring/{chain_id}/{client_id}"),
+            Self::ClientExpired {
+                chain_id,
+                client_id,
+                ..
+            } => format!("client_expired/{chain_id}/{client_id}"),
+            Self::LowBalance {
+                chain_id,
+                wallet,
+                critical,
+                ..
+            } => format!("low_balance/{chain_id}/{wallet}/{critical}"),
+            Self::PacketsStuck {
+                chain_id,
+                port_id,
+                channel_id,
+                ..
+            } => format!("packets_stuck/{chain_id}/{port_id}/{channel_id}"),
+            Self::TxFailures {
+                chain_id,
+                port_id,
+                channel_id,
+                ..
+            } => format!("tx_failures/{chain_id}/{port_id}/{channel_id}"),
+            Self::EndpointFailover {
+                chain_id, endpoint, ..
+            } => format!("endpoint_failover/{chain_id}/{endpoint}"),
+            Self::Misbehaviour {
+                chain_id,
+                client_id,
+                ..
+            } => format!("misbehaviour/{chain_id}/{client_id}"),
+        }
+    }
+
+    /// A human-readable summary of the incident.
+    pub fn text(&self) -> String {
+        match self {
+            Self::ClientExpiring {
+                chain_id,
+                client_id,
+                counterparty_chain_id,
+                expires_in_secs,
+            } => format!(
+                "client {client_id} of {counterparty_chain_id} on {chain_id} expires in {}",
+                humantime::format_duration(Duration::from_secs(*expires_in_secs))
+            ),
+            Self::ClientExpired {
+                chain_id,
+                client_id,
+                counterparty_chain_id,
+            } => format!("client {client_id} of {counterparty_chain_id} on {chain_id} has expired"),
+            Self::LowBalance {
+                chain_id,
+                wallet,
+                balance,
+                denom,
+                critical,
+            } => format!(
+                "balance of wallet {wallet} on {chain_id} is {}low: {balance}{denom}",
+                if *critical { "critically " } else { "" }
+            ),
+            Self::PacketsStuck {
+                chain_id,
+                port_id,
+                channel_id,
+                counterparty_chain_id,
+                unreceived_packets,
+                unreceived_acks,
+            } => format!(
+                "packets are stuck on {chain_id}/{port_id}/{channel_id} to {counterparty_chain_id}: \
+                 {} unreceived packets, {} unreceived acks",
+                unreceived_packets.count, unreceived_acks.count
+            ),
+            Self::TxFailures {
+                chain_id,
+                port_id,
+                channel_id,
+                counterparty_chain_id,
+                failures,
+                last_error,
+            } => format!(
+                "relaying on {chain_id}/{port_id}/{channel_id} to {counterparty_chain_id} \
+                 failed {failures} times in a row: {last_error}"
+            ),
+            Self::EndpointFailover {
+                chain_id,
+                endpoint,
+                reason,
+            } => format!(
+                "event source of {chain_id} fell back from {endpoint} to polling /block_results: {reason}"
+            ),
+            Self::Misbehaviour {
+                chain_id,
+                client_id,
+                counterparty_chain_id,
+                misbehaviour,
+            } => format!(
+                "misbehaviour of {counterparty_chain_id} detected for client {client_id} on {chain_id}: {misbehaviour}"
+            ),
+        }
+    }
+}
+
+/// A notification of an incident, as posted to the webhooks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Notification {
+    pub time: Time,
+    pub text: String,
+    #[serde(flatten)]
+    pub incident: Incident,
+    /// The number of notifications dropped since the previous one,
+    /// as too many notifications were deferred by the rate limit
+    #[serde(skip_serializing_if = "is_zero")]
+    pub dropped: u64,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+/// Deduplicates and rate-limits the incidents to notify.
+#[derive(Debug)]
+struct Throttle {
+    dedup_window: Duration,
+    max_per_period: u32,
+    /// When the incidents notified within the dedup window were notified, by key
+    notified: HashMap<String, Instant>,
+    /// When the notifications within the rate limit period were posted
+    posted: VecDeque<Instant>,
+    /// The incidents deferred by the rate limit, in the order they occurred
+    deferred: VecDeque<Incident>,
+    dropped: u64,
+}
+
+impl Throttle {
+    fn new(config: &NotificationsConfig) -> Self {
+        Self {
+            dedup_window: config.dedup_window,
+            max_per_period: config.max_per_minute,
+            notified: HashMap::new(),
+            posted: VecDeque::new(),
+            deferred: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    /// The notification of the given incident, unless it was already notified
+    /// within the dedup window, or it is deferred by the rate limit.
+    ///
+    /// An incident with the same key as an incident already deferred replaces it,
+    /// so that the most recent occurrence of the incident is notified.
+    fn admit(&mut self, incident: Incident, now: Instant) -> Option<Notification> {
+        self.expire(now);
+
+        let key = incident.key();
+
+        if self.notified.contains_key(&key) {
+            debug!(%key, "dropping incident already notified");
+            return None;
+        }
+
+        if let Some(deferred) = self.deferred.iter_mut().find(|d| d.key() == key) {
+            *deferred = incident;
+            return None;
+        }
+
+        // The incidents are notified in the order they occurred
+        if !self.deferred.is_empty() || self.is_rate_limited() {
+            if self.deferred.len() >= MAX_DEFERRED {
+                warn!(%key, "dropping notification beyond the rate limit");
+                self.dropped += 1;
+            } else {
+                debug!(%key, "deferring notification beyond the rate limit");
+                self.deferred.push_back(incident);
+            }
+
+            return None;
+        }
+
+        Some(self.post(key, incident, now))
+    }
+
+    /// The notification of the earliest incident deferred by the rate limit,
+    /// if the rate limit now allows it.
+    fn release(&mut self, now: Instant) -> Option<Notification> {
+        self.expire(now);
+
+        if self.is_rate_limited() {
+            return None;
+        }
+
+        let incident = self.deferred.pop_front()?;
+
+        Some(self.post(incident.key(), incident, now))
+    }
+
+    /// When the rate limit allows the earliest deferred incident to be notified, if any.
+    fn next_release(&self, now: Instant) -> Option<Instant> {
+        if self.deferred.is_empty() {
+            return None;
+        }
+
+        Some(
+            self.posted
+                .front()
+                .map_or(now, |at| *at + RATE_LIMIT_PERIOD),
+        )
+    }
+
+    fn is_rate_limited(&self) -> bool {
+        self.max_per_period > 0 && self.posted.len() >= self.max_per_period as usize
+    }
+
+    /// Forget the incidents notified before the dedup window,
+    /// and the notifications posted before the rate limit period.
+    fn expire(&mut self, now: Instant) {
+        let dedup_window = self.dedup_window;
+        self.notified
+            .retain(|_, at| now.duration_since(*at) < dedup_window);
+
+        while let Some(at) = self.posted.front() {
+            if now.duration_since(*at) >= RATE_LIMIT_PERIOD {
+                self.posted.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn post(&mut self, key: String, incident: Incident, now: Instant) -> Notification {
+        self.notified.insert(key, now);
+        self.posted.push_back(now);
+
+        Notification {
+            time: Time::now(),
+            text: incident.text(),
+            incident,
+            dropped: core::mem::take(&mut self.dropped),
+        }
+    }
+}
+
+/// Posts the incidents it is notified of to the webhooks, from a background thread
+/// which terminates once the notifier is dropped.
+#[derive(Debug)]
+pub struct Notifier {
+    config: NotificationsConfig,
+    tx: channel::Sender<Incident>,
+}
+
+impl Notifier {
+    pub fn spawn(config: NotificationsConfig) -> Result<Self, Error> {
+        let webhooks = config
+            .webhooks
+            .iter()
+            .map(|url| {
+                reqwest::Url::parse(url)
+                    .map_err(|e| Error::invalid_webhook(url.clone(), e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let client = reqwest::Client::builder()
+            .timeout(POST_TIMEOUT)
+            .build()
+            .map_err(Error::http_client)?;
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(Error::runtime)?;
+
+        let (tx, rx) = channel::unbounded::<Incident>();
+        let mut throttle = Throttle::new(&config);
+
+        thread::spawn(move || {
+            let _span = error_span!("notifications").entered();
+
+            loop {
+                // Wait for the next incident, or for the rate limit to allow
+                // the incidents it deferred, if any
+                let received = match throttle.next_release(Instant::now()) {
+                    Some(deadline) => rx.recv_deadline(deadline),
+                    None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+
+                let now = Instant::now();
+
+                let admitted = match received {
+                    Ok(incident) => throttle.admit(incident, now),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+
+                let released = core::iter::from_fn(|| throttle.release(now));
+
+                for notification in admitted.into_iter().chain(released) {
+                    debug!(text = %notification.text, "posting notification");
+
+                    for url in &webhooks {
+                        let result = rt.block_on(async {
+                            client
+                                .post(url.clone())
+                                .json(&notification)
+                                .send()
+                                .await?
+                                .error_for_status()
+                        });
+
+                        if let Err(e) = result {
+                            warn!("failed to post notification to {url}: {e}");
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self { config, tx })
+    }
+
+    /// Notify the given incident, which is posted unless it is throttled.
+    pub fn notify(&self, incident: Incident) {
+        // The thread only terminates once the notifier is dropped
+        let _ = self.tx.send(incident);
+    }
+}
+
+static NOTIFIER: GlobalSink<Notifier> = GlobalSink::new();
+
+/// Starts posting the incidents to the webhooks configured in the `[notifications]`
+/// section, if enabled.
+pub fn init(config: &NotificationsConfig) -> Result<(), Error> {
+    if !config.enabled || config.webhooks.is_empty() {
+        return Ok(());
+    }
+
+    NOTIFIER.init(|| {
+        let notifier = Notifier::spawn(config.clone())?;
+        debug!(
+            "posting notifications to {} webhooks",
+            notifier.config.webhooks.len()
+        );
+
+        Ok(notifier)
+    })
+}
+
+/// The configuration of the notifications, if they are posted.
+pub fn config() -> Option<&'static NotificationsConfig> {
+    NOTIFIER.get().map(|notifier| &notifier.config)
+}
+
+/// Notify the given incident, if notifications are posted.
+pub fn notify(incident: Incident) {
+    if let Some(notifier) = NOTIFIER.get() {
+        notifier.notify(incident);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    fn config(max_per_minute: u32) -> NotificationsConfig {
+        NotificationsConfig {
+            enabled: true,
+            max_per_minute,
+            ..Default::default()
+        }
+    }
+
+    fn client_expired(client: u64) -> Incident {
+        Incident::ClientExpired {
+            chain_id: ChainId::from_string("ibc-1"),
+            client_id: ClientId::new(
+                ibc_relayer_types::core::ics02_client::client_type::ClientType::Tendermint,
+                client,
+            )
+            .unwrap(),
+            counterparty_chain_id: ChainId::from_string("ibc-0"),
+        }
+    }
+
+    #[test]
+    fn throttle_drops_duplicates_within_dedup_window() {
+        let mut throttle = Throttle::new(&config(0));
+        let now = Instant::now();
+
+        assert!(throttle.admit(client_expired(0), now).is_some());
+        assert!(throttle.admit(client_expired(1), now).is_some());
+        assert!(throttle
+            .admit(client_expired(0), now + Duration::from_secs(60))
+            .is_none());
+        assert!(throttle
+            .admit(client_expired(0), now + Duration::from_secs(60 * 60))
+            .is_some());
+    }
+
+    #[test]
+    fn throttle_defers_notifications_beyond_rate_limit() {
+        let mut throttle = Throttle::new(&config(2));
+        let now = Instant::now();
+
+        assert!(throttle.admit(client_expired(0), now).is_some());
+        assert!(throttle.admit(client_expired(1), now).is_some());
+        assert!(throttle.admit(client_expired(2), now).is_none());
+        assert!(throttle.admit(client_expired(3), now).is_none());
+        assert!(throttle.admit(client_expired(2), now).is_none());
+
+        assert!(throttle.release(now).is_none());
+        assert_eq!(throttle.next_release(now), Some(now + RATE_LIMIT_PERIOD));
+
+        // The deferred incidents are notified once, in the order they occurred
+        let later = now + RATE_LIMIT_PERIOD;
+        assert_eq!(throttle.release(later).unwrap().incident, client_expired(2));
+        assert_eq!(throttle.release(later).unwrap().incident, client_expired(3));
+        assert!(throttle.release(later).is_none());
+        assert_eq!(throttle.next_release(later), None);
+    }
+
+    #[test]
+    fn throttle_drops_notifications_beyond_deferred_limit() {
+        let mut throttle = Throttle::new(&config(1));
+        let now = Instant::now();
+
+        assert!(throttle.admit(client_expired(0), now).is_some());
+
+        for client in 1..=MAX_DEFERRED as u64 + 2 {
+            assert!(throttle.admit(client_expired(client), now).is_none());
+        }
+
+        let later = now + RATE_LIMIT_PERIOD;
+        let notification = throttle.release(later).unwrap();
+        assert_eq!(notification.incident, client_expired(1));
+        assert_eq!(notification.dropped, 2);
+    }
+
+    /// Receive the bodies of the given number of HTTP requests made to a local receiver.
+    fn receive(listener: TcpListener, requests: usize) -> thread::JoinHandle<Vec<String>> {
+        thread::spawn(move || {
+            (0..requests)
+                .map(|_| {
+                    let (stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream);
+
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+
+                        if let Some((name, value)) = line.split_once(':') {
+                            if name.eq_ignore_ascii_case("content-length") {
+                                content_length = value.trim().parse().unwrap();
+                            }
+                        }
+
+                        if line.trim().is_empty() {
+                            break;
+                        }
+                    }
+
+                    let mut body = vec![0; content_length];
+                    reader.read_exact(&mut body).unwrap();
+
+                    reader
+                        .get_mut()
+                        .write_all(
+                            b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                        )
+                        .unwrap();
+
+                    String::from_utf8(body).unwrap()
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn post_notifications_to_local_receiver() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hermes", listener.local_addr().unwrap());
+        let receiver = receive(listener, 2);
+
+        let notifier = Notifier::spawn(NotificationsConfig {
+            webhooks: vec![url],
+            ..config(10)
+        })
+        .unwrap();
+
+        notifier.notify(client_expired(0));
+        notifier.notify(client_expired(0));
+        notifier.notify(Incident::EndpointFailover {
+            chain_id: ChainId::from_string("ibc-0"),
+            endpoint: "ws://127.0.0.1:26657/websocket".to_string(),
+            reason: "WebSocket event source has terminated".to_string(),
+        });
+
+        let bodies = receiver.join().unwrap();
+        let received = bodies
+            .iter()
+            .map(|body| serde_json::from_str::<serde_json::Value>(body).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(received[0]["incident"], "client_expired");
+        assert_eq!(received[0]["client_id"], "07-tendermint-0");
+        assert_eq!(
+            received[0]["text"],
+            "client 07-tendermint-0 of ibc-0 on ibc-1 has expired"
+        );
+
+        // The duplicate was dropped
+        assert_eq!(received[1]["incident"], "endpoint_failover");
+        assert_eq!(received[1]["chain_id"], "ibc-0");
+    }
+
+    #[test]
+    fn reject_invalid_webhook() {
+        assert!(Notifier::spawn(NotificationsConfig {
+            webhooks: vec!["not a url".to_string()],
+            ..config(10)
+        })
+        .is_err());
+    }
+}
@@ -66,6 +66,12 @@ impl<Chain: ChainHandle> Registry<Chain> {
         Ok(handle.clone())
     }
 
+    /// Register the given handle in place of spawning a runtime for its chain.
+    #[cfg(test)]
+    pub(crate) fn insert(&mut self, handle: Chain) {
+        self.handles.insert(handle.id(), handle);
+    }
+
     /// Spawn a chain runtime for the chain with the given [`ChainId`],
     /// only if the registry does not contain a handle for that runtime already.
     ///
@@ -81,6 +87,36 @@ impl<Chain: ChainHandle> Registry<Chain> {
         }
     }
 
+    /// Create a registry holding the chain runtimes spawned so far by this one,
+    /// eg. to use them from another thread.
+    pub fn fork(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            handles: self.handles.clone(),
+            rt: self.rt.clone(),
+        }
+    }
+
+    /// Take over the chain runtimes spawned by a registry forked from this one,
+    /// for the chains which this registry does not hold a runtime for already.
+    pub fn merge(&mut self, other: Self) {
+        for (chain_id, handle) in other.handles {
+            self.handles.entry(chain_id).or_insert(handle);
+        }
+    }
+
+    /// Remove the handle of the given chain from the registry without shutting down its
+    /// runtime, eg. from a fork, when the runtime is shut down through the original registry.
+    pub fn forget(&mut self, chain_id: &ChainId) {
+        self.handles.remove(chain_id);
+    }
+
+    /// Replace the configuration from which new chain runtimes are spawned,
+    /// eg. after the configuration was reloaded.
+    pub fn update_config(&mut self, config: Config) {
+        self.config = config;
+    }
+
     /// Shutdown the runtime associated with the given chain identifier.
     pub fn shutdown(&mut self, chain_id: &ChainId) {
         if let Some(handle) = self.handles.remove(chain_id) {
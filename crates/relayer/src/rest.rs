@@ -1,11 +1,20 @@
 use crossbeam_channel::TryRecvError;
 use tracing::{error, trace};
 
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
+
 use crate::{
     config::Config,
+    event::monitor,
     rest::request::ReplySender,
     rest::request::{Request, VersionInfo},
-    supervisor::dump_state::SupervisorState,
+    supervisor::{
+        control::{ClientUpdate, WorkerAction},
+        dump_state::{SupervisorState, WorkerDesc},
+        paths::PathStatus,
+        reload::ConfigDiff,
+    },
+    worker::WorkerId,
 };
 
 pub mod request;
@@ -25,12 +34,33 @@ pub const VER: &str = env!(
 pub type Receiver = crossbeam_channel::Receiver<Request>;
 
 // TODO: Unify this enum with `SupervisorCmd`
-//  We won't unify yet as it is possible we will never implement
-//  REST API `/chain` adding endpoint; instead of `/chain` we might
-//  implement `/reload` for supporting a broader range of functionality
-//  e.g., adjusting chain config, removing chains, etc.
 pub enum Command {
     DumpState(ReplySender<SupervisorState>),
+    ReloadConfig(ReplySender<ConfigDiff>),
+    GetPaths(ReplySender<Vec<PathStatus>>),
+    GetPath {
+        chain_id: ChainId,
+        port_id: PortId,
+        channel_id: ChannelId,
+        reply_to: ReplySender<PathStatus>,
+    },
+    ClearPackets {
+        chain_id: ChainId,
+        port_id: PortId,
+        channel_id: ChannelId,
+        reply_to: ReplySender<Vec<WorkerId>>,
+    },
+    UpdateClient {
+        chain_id: ChainId,
+        client_id: ClientId,
+        force: bool,
+        reply_to: ReplySender<ClientUpdate>,
+    },
+    ControlWorker {
+        id: WorkerId,
+        action: WorkerAction,
+        reply_to: ReplySender<WorkerDesc>,
+    },
 }
 
 /// Process incoming REST requests.
@@ -77,11 +107,93 @@ pub fn process_incoming_requests(config: &Config, channel: &Receiver) -> Option<
                     .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
             }
 
+            Request::SubscribeEvents { reply_to } => {
+                trace!("SubscribeEvents");
+
+                reply_to
+                    .send(Ok(monitor::subscribe()))
+                    .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+            }
+
             Request::State { reply_to } => {
                 trace!("State");
 
                 return Some(Command::DumpState(reply_to));
             }
+
+            Request::ReloadConfig { reply_to } => {
+                trace!("ReloadConfig");
+
+                return Some(Command::ReloadConfig(reply_to));
+            }
+
+            Request::GetPaths { reply_to } => {
+                trace!("GetPaths");
+
+                return Some(Command::GetPaths(reply_to));
+            }
+
+            Request::GetPath {
+                chain_id,
+                port_id,
+                channel_id,
+                reply_to,
+            } => {
+                trace!("GetPath {}/{} on {}", port_id, channel_id, chain_id);
+
+                return Some(Command::GetPath {
+                    chain_id,
+                    port_id,
+                    channel_id,
+                    reply_to,
+                });
+            }
+
+            Request::ClearPackets {
+                chain_id,
+                port_id,
+                channel_id,
+                reply_to,
+            } => {
+                trace!("ClearPackets {}/{} on {}", port_id, channel_id, chain_id);
+
+                return Some(Command::ClearPackets {
+                    chain_id,
+                    port_id,
+                    channel_id,
+                    reply_to,
+                });
+            }
+
+            Request::UpdateClient {
+                chain_id,
+                client_id,
+                force,
+                reply_to,
+            } => {
+                trace!("UpdateClient {} on {}", client_id, chain_id);
+
+                return Some(Command::UpdateClient {
+                    chain_id,
+                    client_id,
+                    force,
+                    reply_to,
+                });
+            }
+
+            Request::ControlWorker {
+                id,
+                action,
+                reply_to,
+            } => {
+                trace!("ControlWorker {} {:?}", id, action);
+
+                return Some(Command::ControlWorker {
+                    id,
+                    action,
+                    reply_to,
+                });
+            }
         },
         Err(e) => {
             if !matches!(e, TryRecvError::Empty) {
@@ -23,6 +23,24 @@ pub enum RestApiError {
     #[error("failed while parsing the request body into a chain configuration: {0}")]
     InvalidChainConfig(String),
 
+    #[error("failed to reload the configuration: {0}")]
+    ConfigReload(String),
+
+    #[error("failed to clear pending packets: {0}")]
+    ClearPackets(String),
+
+    #[error("failed to update client: {0}")]
+    ClientUpdate(String),
+
+    #[error("failed to query the status of the path: {0}")]
+    PathStatus(String),
+
+    #[error("failed to control worker: {0}")]
+    WorkerControl(String),
+
+    #[error("missing or invalid bearer token")]
+    Unauthorized,
+
     #[error("not implemented")]
     Unimplemented,
 }
@@ -36,6 +54,12 @@ impl RestApiError {
             RestApiError::ChainConfigNotFound(_) => "ChainConfigNotFound",
             RestApiError::InvalidChainId(_, _) => "InvalidChainId",
             RestApiError::InvalidChainConfig(_) => "InvalidChainConfig",
+            RestApiError::ConfigReload(_) => "ConfigReload",
+            RestApiError::ClearPackets(_) => "ClearPackets",
+            RestApiError::ClientUpdate(_) => "ClientUpdate",
+            RestApiError::PathStatus(_) => "PathStatus",
+            RestApiError::WorkerControl(_) => "WorkerControl",
+            RestApiError::Unauthorized => "Unauthorized",
             RestApiError::Unimplemented => "Unimplemented",
         }
     }
@@ -1,8 +1,21 @@
+use alloc::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 
-use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
 
-use crate::{config::ChainConfig, rest::RestApiError, supervisor::dump_state::SupervisorState};
+use crate::{
+    config::ChainConfig,
+    event::monitor::RelayerEvent,
+    rest::RestApiError,
+    supervisor::{
+        control::{ClientUpdate, WorkerAction},
+        dump_state::{SupervisorState, WorkerDesc},
+        paths::PathStatus,
+        reload::ConfigDiff,
+    },
+    worker::WorkerId,
+};
 
 pub type ReplySender<T> = crossbeam_channel::Sender<Result<T, RestApiError>>;
 pub type ReplyReceiver<T> = crossbeam_channel::Receiver<Result<T, RestApiError>>;
@@ -36,4 +49,43 @@ pub enum Request {
         chain_id: ChainId,
         reply_to: ReplySender<ChainConfig>,
     },
+
+    ReloadConfig {
+        reply_to: ReplySender<ConfigDiff>,
+    },
+
+    GetPaths {
+        reply_to: ReplySender<Vec<PathStatus>>,
+    },
+
+    GetPath {
+        chain_id: ChainId,
+        port_id: PortId,
+        channel_id: ChannelId,
+        reply_to: ReplySender<PathStatus>,
+    },
+
+    ClearPackets {
+        chain_id: ChainId,
+        port_id: PortId,
+        channel_id: ChannelId,
+        reply_to: ReplySender<Vec<WorkerId>>,
+    },
+
+    UpdateClient {
+        chain_id: ChainId,
+        client_id: ClientId,
+        force: bool,
+        reply_to: ReplySender<ClientUpdate>,
+    },
+
+    ControlWorker {
+        id: WorkerId,
+        action: WorkerAction,
+        reply_to: ReplySender<WorkerDesc>,
+    },
+
+    SubscribeEvents {
+        reply_to: ReplySender<crossbeam_channel::Receiver<Arc<RelayerEvent>>>,
+    },
 }
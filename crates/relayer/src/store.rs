@@ -0,0 +1,773 @@
+//! Persistent storage of the in-flight state of the relayer, so that it can
+//! be resumed after a restart.
+//!
+//! The state comprises, for every packet worker path, the transactions which
+//! were submitted but not yet confirmed and the events for which operational
+//! data is scheduled, as well as the latest height processed for every chain.
+
+use alloc::collections::btree_map::BTreeMap as HashMap;
+use core::fmt::Debug;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use flex_error::{define_error, TraceError};
+use ibc_proto::ibc::core::channel::v1::Packet as RawPacket;
+use serde::{Deserialize, Serialize};
+use tendermint::Hash as TxHash;
+use tracing::{error, warn};
+
+use ibc_relayer_types::core::ics04_channel::events::{
+    SendPacket, TimeoutPacket, WriteAcknowledgement,
+};
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use ibc_relayer_types::events::IbcEvent;
+use ibc_relayer_types::Height;
+
+use crate::config::StoreConfig;
+use crate::event::IbcEventWithHeight;
+use crate::link::operational_data::OperationalDataTarget;
+use crate::object::Packet;
+
+/// The default location of the state store, relative to the home directory.
+pub const STORE_DEFAULT_PATH: &str = ".hermes/state.json";
+
+/// The minimum interval between two writes of the store which only
+/// update the processed heights, as these change on every block.
+const HEIGHTS_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+define_error! {
+    Error {
+        Io
+            { path: PathBuf }
+            [ TraceError<io::Error> ]
+            |e| { format!("I/O error on state store {}", e.path.display()) },
+
+        Decode
+            { path: PathBuf }
+            [ TraceError<serde_json::Error> ]
+            |e| { format!("failed to decode state store {}", e.path.display()) },
+
+        Encode
+            [ TraceError<serde_json::Error> ]
+            |_| { "failed to encode the relayer state" },
+
+        HomeLocationUnavailable
+            |_| { "home location is unavailable" },
+    }
+}
+
+pub type SharedStateStore = Arc<dyn StateStore>;
+
+/// A store for the in-flight state of the relayer.
+pub trait StateStore: Debug + Send + Sync {
+    /// The packet worker paths for which some state is stored.
+    fn paths(&self) -> Vec<Packet>;
+
+    /// The state stored for the given packet worker path, if any.
+    fn path_state(&self, path: &Packet) -> Option<PathState>;
+
+    /// Store the state of the given packet worker path,
+    /// replacing any previously stored state.
+    fn set_path_state(&self, path: &Packet, state: PathState) -> Result<(), Error>;
+
+    /// The latest height which was processed for the given chain, if any.
+    fn processed_height(&self, chain_id: &ChainId) -> Option<Height>;
+
+    /// Store the latest height which was processed for the given chain,
+    /// unless a later height was already stored.
+    fn set_processed_height(&self, chain_id: &ChainId, height: Height) -> Result<(), Error>;
+}
+
+/// Opens the state store configured in the `[store]` section, if enabled.
+pub fn open_state_store(config: &StoreConfig) -> Result<Option<SharedStateStore>, Error> {
+    if !config.enabled {
+        return Ok(None);
+    }
+
+    let store = FileStore::open(store_path(config)?)?;
+
+    Ok(Some(Arc::new(store)))
+}
+
+/// Loads the latest height processed for every chain from the state store
+/// configured in the `[store]` section, without opening the store for writing.
+///
+/// Returns no heights if the store is disabled or does not exist yet.
+pub fn load_processed_heights(config: &StoreConfig) -> Result<HashMap<ChainId, Height>, Error> {
+    if !config.enabled {
+        return Ok(HashMap::new());
+    }
+
+    let state = load_state(&store_path(config)?)?;
+
+    Ok(state.processed_heights)
+}
+
+fn store_path(config: &StoreConfig) -> Result<PathBuf, Error> {
+    match &config.path {
+        Some(path) => Ok(path.clone()),
+        None => Ok(dirs_next::home_dir()
+            .ok_or_else(Error::home_location_unavailable)?
+            .join(STORE_DEFAULT_PATH)),
+    }
+}
+
+fn load_state(path: &Path) -> Result<State, Error> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice::<RelayerState>(&bytes)
+            .map(State::from)
+            .map_err(|e| Error::decode(path.to_path_buf(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(State::default()),
+        Err(e) => Err(Error::io(path.to_path_buf(), e)),
+    }
+}
+
+/// The in-flight state of a packet worker path.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PathState {
+    /// Transactions which were submitted but not yet confirmed.
+    #[serde(default)]
+    pub pending_txs: Vec<StoredPendingTx>,
+
+    /// Events for which operational data is scheduled but not yet submitted.
+    #[serde(default)]
+    pub scheduled: Vec<StoredEvent>,
+}
+
+impl PathState {
+    pub fn is_empty(&self) -> bool {
+        self.pending_txs.is_empty() && self.scheduled.is_empty()
+    }
+}
+
+/// A submitted transaction, along with the events from which its messages were built.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoredPendingTx {
+    pub target: OperationalDataTarget,
+    pub tx_hashes: Vec<TxHash>,
+    pub events: Vec<StoredEvent>,
+}
+
+/// A packet event from which relay messages are built.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StoredEvent {
+    SendPacket {
+        height: Height,
+        packet: RawPacket,
+    },
+    WriteAcknowledgement {
+        height: Height,
+        packet: RawPacket,
+        ack: Vec<u8>,
+    },
+    TimeoutPacket {
+        height: Height,
+        packet: RawPacket,
+    },
+}
+
+impl StoredEvent {
+    /// Returns the stored representation of the given event,
+    /// or `None` if it is not a packet event.
+    pub fn from_event(event_with_height: &IbcEventWithHeight) -> Option<Self> {
+        let height = event_with_height.height;
+
+        match &event_with_height.event {
+            IbcEvent::SendPacket(ev) => Some(Self::SendPacket {
+                height,
+                packet: ev.packet.clone().into(),
+            }),
+            IbcEvent::WriteAcknowledgement(ev) => Some(Self::WriteAcknowledgement {
+                height,
+                packet: ev.packet.clone().into(),
+                ack: ev.ack.clone(),
+            }),
+            IbcEvent::TimeoutPacket(ev) => Some(Self::TimeoutPacket {
+                height,
+                packet: ev.packet.clone().into(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the event this was built from, or `None` if the stored packet is invalid.
+    pub fn into_event(self) -> Option<IbcEventWithHeight> {
+        let (event, height) = match self {
+            Self::SendPacket { height, packet } => (
+                IbcEvent::SendPacket(SendPacket {
+                    packet: packet.try_into().ok()?,
+                }),
+                height,
+            ),
+            Self::WriteAcknowledgement {
+                height,
+                packet,
+                ack,
+            } => (
+                IbcEvent::WriteAcknowledgement(WriteAcknowledgement {
+                    packet: packet.try_into().ok()?,
+                    ack,
+                }),
+                height,
+            ),
+            Self::TimeoutPacket { height, packet } => (
+                IbcEvent::TimeoutPacket(TimeoutPacket {
+                    packet: packet.try_into().ok()?,
+                }),
+                height,
+            ),
+        };
+
+        Some(IbcEventWithHeight::new(event, height))
+    }
+}
+
+/// The whole state of the relayer, as stored on disk.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct RelayerState {
+    #[serde(default)]
+    paths: Vec<StoredPathState>,
+    #[serde(default)]
+    processed_heights: HashMap<ChainId, Height>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredPathState {
+    path: Packet,
+    #[serde(flatten)]
+    state: PathState,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    paths: HashMap<Packet, PathState>,
+    processed_heights: HashMap<ChainId, Height>,
+}
+
+impl From<RelayerState> for State {
+    fn from(state: RelayerState) -> Self {
+        Self {
+            paths: state
+                .paths
+                .into_iter()
+                .map(|stored| (stored.path, stored.state))
+                .collect(),
+            processed_heights: state.processed_heights,
+        }
+    }
+}
+
+impl From<&State> for RelayerState {
+    fn from(state: &State) -> Self {
+        Self {
+            paths: state
+                .paths
+                .iter()
+                .map(|(path, state)| StoredPathState {
+                    path: path.clone(),
+                    state: state.clone(),
+                })
+                .collect(),
+            processed_heights: state.processed_heights.clone(),
+        }
+    }
+}
+
+impl State {
+    /// Updates the state of the given path, returning whether it changed.
+    fn set_path_state(&mut self, path: &Packet, state: PathState) -> bool {
+        if state.is_empty() {
+            self.paths.remove(path).is_some()
+        } else if self.paths.get(path) != Some(&state) {
+            self.paths.insert(path.clone(), state);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Updates the processed height of the given chain, returning whether it changed.
+    /// The height only ever increases, as the batches of events of a chain may be
+    /// handled out of order by the workers they were dispatched to.
+    fn set_processed_height(&mut self, chain_id: &ChainId, height: Height) -> bool {
+        match self.processed_heights.get(chain_id) {
+            Some(processed) if *processed >= height => false,
+            _ => {
+                self.processed_heights.insert(chain_id.clone(), height);
+                true
+            }
+        }
+    }
+}
+
+/// A state store which only lives in memory, mostly useful for testing.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    state: Mutex<State>,
+}
+
+impl StateStore for MemoryStore {
+    fn paths(&self) -> Vec<Packet> {
+        self.state.lock().unwrap().paths.keys().cloned().collect()
+    }
+
+    fn path_state(&self, path: &Packet) -> Option<PathState> {
+        self.state.lock().unwrap().paths.get(path).cloned()
+    }
+
+    fn set_path_state(&self, path: &Packet, state: PathState) -> Result<(), Error> {
+        self.state.lock().unwrap().set_path_state(path, state);
+        Ok(())
+    }
+
+    fn processed_height(&self, chain_id: &ChainId) -> Option<Height> {
+        self.state
+            .lock()
+            .unwrap()
+            .processed_heights
+            .get(chain_id)
+            .copied()
+    }
+
+    fn set_processed_height(&self, chain_id: &ChainId, height: Height) -> Result<(), Error> {
+        self.state
+            .lock()
+            .unwrap()
+            .set_processed_height(chain_id, height);
+
+        Ok(())
+    }
+}
+
+/// A state store which keeps the state in memory and writes it to a JSON file on disk
+/// when it changes, from a background thread so as not to block the relayer workers.
+///
+/// Changes to the state of a path are written right away, while the changes which
+/// only update the processed heights, which change on every block, are written at
+/// most every [`HEIGHTS_FLUSH_INTERVAL`]. Each write replaces the whole file atomically.
+#[derive(Debug)]
+pub struct FileStore {
+    shared: Arc<Shared>,
+    writer: Option<thread::JoinHandle<()>>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    path: PathBuf,
+    state: Mutex<WriterState>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct WriterState {
+    state: State,
+    dirty: Dirty,
+    shutdown: bool,
+}
+
+/// Which part of the state changed since it was last written.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+enum Dirty {
+    #[default]
+    Clean,
+    Heights,
+    Paths,
+}
+
+impl FileStore {
+    /// Opens the store at the given path, loading the state stored there, if any.
+    pub fn open(path: PathBuf) -> Result<Self, Error> {
+        let state = load_state(&path)?;
+
+        let shared = Arc::new(Shared {
+            path,
+            state: Mutex::new(WriterState {
+                state,
+                ..Default::default()
+            }),
+            changed: Condvar::new(),
+        });
+
+        let writer = thread::Builder::new()
+            .name("state-store".to_string())
+            .spawn({
+                let shared = shared.clone();
+                move || shared.run_writer()
+            })
+            .map_err(|e| Error::io(shared.path.clone(), e))?;
+
+        Ok(Self {
+            shared,
+            writer: Some(writer),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.shared.path
+    }
+
+    fn update<R>(&self, f: impl FnOnce(&mut State) -> (R, Dirty)) -> R {
+        let mut guard = self.shared.state.lock().unwrap();
+        let (result, dirty) = f(&mut guard.state);
+
+        if dirty > guard.dirty {
+            guard.dirty = dirty;
+            self.shared.changed.notify_one();
+        }
+
+        result
+    }
+}
+
+impl Shared {
+    /// Writes the state every time it changes, until the store is dropped.
+    fn run_writer(&self) {
+        let mut last_write = Instant::now();
+
+        loop {
+            let (state, shutdown) = {
+                let mut guard = self.state.lock().unwrap();
+
+                loop {
+                    if guard.shutdown {
+                        break;
+                    }
+
+                    match guard.dirty {
+                        Dirty::Clean => guard = self.changed.wait(guard).unwrap(),
+                        Dirty::Heights if last_write.elapsed() < HEIGHTS_FLUSH_INTERVAL => {
+                            let timeout = HEIGHTS_FLUSH_INTERVAL - last_write.elapsed();
+                            guard = self.changed.wait_timeout(guard, timeout).unwrap().0;
+                        }
+                        Dirty::Heights | Dirty::Paths => break,
+                    }
+                }
+
+                if guard.dirty == Dirty::Clean {
+                    return;
+                }
+
+                guard.dirty = Dirty::Clean;
+
+                (RelayerState::from(&guard.state), guard.shutdown)
+            };
+
+            if let Err(e) = write_state(&self.path, &state) {
+                warn!("failed to write the relayer state to disk: {e}");
+            }
+
+            last_write = Instant::now();
+
+            if shutdown {
+                return;
+            }
+        }
+    }
+}
+
+/// Atomically and durably writes the state to disk, by writing it to a temporary
+/// file first, which is synced to disk before it replaces the previous one.
+fn write_state(path: &Path, state: &RelayerState) -> Result<(), Error> {
+    let encoded = serde_json::to_vec_pretty(state).map_err(Error::encode)?;
+
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty());
+
+    if let Some(parent) = parent {
+        fs::create_dir_all(parent).map_err(|e| Error::io(parent.to_path_buf(), e))?;
+    }
+
+    let tmp_path = path.with_extension("tmp");
+
+    let mut file = File::create(&tmp_path).map_err(|e| Error::io(tmp_path.clone(), e))?;
+    file.write_all(&encoded)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| Error::io(tmp_path.clone(), e))?;
+
+    fs::rename(&tmp_path, path).map_err(|e| Error::io(path.to_path_buf(), e))?;
+
+    // Sync the directory too, so that the rename itself survives a crash
+    #[cfg(unix)]
+    if let Some(parent) = parent {
+        File::open(parent)
+            .and_then(|dir| dir.sync_all())
+            .map_err(|e| Error::io(parent.to_path_buf(), e))?;
+    }
+
+    Ok(())
+}
+
+impl StateStore for FileStore {
+    fn paths(&self) -> Vec<Packet> {
+        self.update(|state| (state.paths.keys().cloned().collect(), Dirty::Clean))
+    }
+
+    fn path_state(&self, path: &Packet) -> Option<PathState> {
+        self.update(|state| (state.paths.get(path).cloned(), Dirty::Clean))
+    }
+
+    fn set_path_state(&self, path: &Packet, path_state: PathState) -> Result<(), Error> {
+        self.update(|state| {
+            let dirty = if state.set_path_state(path, path_state) {
+                Dirty::Paths
+            } else {
+                Dirty::Clean
+            };
+
+            ((), dirty)
+        });
+
+        Ok(())
+    }
+
+    fn processed_height(&self, chain_id: &ChainId) -> Option<Height> {
+        self.update(|state| (state.processed_heights.get(chain_id).copied(), Dirty::Clean))
+    }
+
+    fn set_processed_height(&self, chain_id: &ChainId, height: Height) -> Result<(), Error> {
+        self.update(|state| {
+            let dirty = if state.set_processed_height(chain_id, height) {
+                Dirty::Heights
+            } else {
+                Dirty::Clean
+            };
+
+            ((), dirty)
+        });
+
+        Ok(())
+    }
+}
+
+impl Drop for FileStore {
+    fn drop(&mut self) {
+        // Make sure the latest changes are written to disk
+        self.shared.state.lock().unwrap().shutdown = true;
+        self.shared.changed.notify_one();
+
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Records a height as processed for a chain once every worker to which the events
+/// of the batch at that height were dispatched has handled them, ie. once the last
+/// clone of the [`Arc`] returned by [`ProcessedHeight::new`] is dropped,
+/// unless handling the events failed.
+#[derive(Debug)]
+pub struct ProcessedHeight {
+    store: SharedStateStore,
+    chain_id: ChainId,
+    height: Height,
+    failed: AtomicBool,
+}
+
+impl ProcessedHeight {
+    pub fn new(store: SharedStateStore, chain_id: ChainId, height: Height) -> Arc<Self> {
+        Arc::new(Self {
+            store,
+            chain_id,
+            height,
+            failed: AtomicBool::new(false),
+        })
+    }
+
+    /// Marks the events of the batch as not handled, so that the height
+    /// is not recorded as processed.
+    pub fn fail(&self) {
+        self.failed.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for ProcessedHeight {
+    fn drop(&mut self) {
+        if self.failed.load(Ordering::Relaxed) {
+            return;
+        }
+
+        if let Err(e) = self.store.set_processed_height(&self.chain_id, self.height) {
+            error!("failed to persist processed height: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc_relayer_types::core::ics04_channel::packet::Packet as IbcPacket;
+    use ibc_relayer_types::core::ics24_host::identifier::{ChannelId, PortId};
+
+    fn path() -> Packet {
+        Packet {
+            dst_chain_id: ChainId::from_string("chain-b"),
+            src_chain_id: ChainId::from_string("chain-a"),
+            src_channel_id: ChannelId::new(0),
+            src_port_id: PortId::transfer(),
+        }
+    }
+
+    fn send_packet_event() -> IbcEventWithHeight {
+        let packet = IbcPacket {
+            sequence: 1.into(),
+            source_port: PortId::transfer(),
+            source_channel: ChannelId::new(0),
+            destination_port: PortId::transfer(),
+            destination_channel: ChannelId::new(1),
+            data: vec![1, 2, 3],
+            ..Default::default()
+        };
+
+        IbcEventWithHeight::new(
+            IbcEvent::SendPacket(SendPacket { packet }),
+            Height::new(0, 10).unwrap(),
+        )
+    }
+
+    #[test]
+    fn stored_event_roundtrip() {
+        let event = send_packet_event();
+        let stored = StoredEvent::from_event(&event).unwrap();
+        let restored = stored.into_event().unwrap();
+
+        assert_eq!(restored.height, event.height);
+        match (restored.event, event.event) {
+            (IbcEvent::SendPacket(restored), IbcEvent::SendPacket(original)) => {
+                assert_eq!(restored, original)
+            }
+            (restored, _) => panic!("unexpected restored event: {restored}"),
+        }
+    }
+
+    #[test]
+    fn file_store_persists_state() {
+        let dir = std::env::temp_dir().join(format!("hermes-store-{}", std::process::id()));
+        let file = dir.join("state.json");
+        let chain_id = ChainId::from_string("chain-a");
+
+        let state = PathState {
+            pending_txs: vec![StoredPendingTx {
+                target: OperationalDataTarget::Destination,
+                tx_hashes: vec![TxHash::Sha256([1; 32])],
+                events: vec![StoredEvent::from_event(&send_packet_event()).unwrap()],
+            }],
+            scheduled: vec![],
+        };
+
+        {
+            let store = FileStore::open(file.clone()).unwrap();
+            store.set_path_state(&path(), state.clone()).unwrap();
+            store
+                .set_processed_height(&chain_id, Height::new(0, 42).unwrap())
+                .unwrap();
+        }
+
+        let config = StoreConfig {
+            enabled: true,
+            path: Some(file.clone()),
+        };
+        assert_eq!(
+            load_processed_heights(&config).unwrap().get(&chain_id),
+            Some(&Height::new(0, 42).unwrap())
+        );
+
+        let store = FileStore::open(file).unwrap();
+        assert_eq!(store.paths(), vec![path()]);
+        assert_eq!(store.path_state(&path()), Some(state));
+        assert_eq!(
+            store.processed_height(&chain_id),
+            Some(Height::new(0, 42).unwrap())
+        );
+
+        // Empty states are removed from the store
+        store.set_path_state(&path(), PathState::default()).unwrap();
+        assert!(store.paths().is_empty());
+
+        drop(store);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn file_store_writes_path_state_in_background() {
+        let dir = std::env::temp_dir().join(format!("hermes-store-bg-{}", std::process::id()));
+        let file = dir.join("state.json");
+
+        let state = PathState {
+            pending_txs: vec![],
+            scheduled: vec![StoredEvent::from_event(&send_packet_event()).unwrap()],
+        };
+
+        let store = FileStore::open(file.clone()).unwrap();
+        store.set_path_state(&path(), state.clone()).unwrap();
+
+        // The state is written while the store is still open
+        let written = (0..50).any(|_| {
+            thread::sleep(Duration::from_millis(100));
+
+            load_state(&file)
+                .map(|stored| stored.paths.get(&path()) == Some(&state))
+                .unwrap_or(false)
+        });
+        assert!(written);
+        assert!(!file.with_extension("tmp").exists());
+
+        drop(store);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn processed_heights_only_increase() {
+        let store = MemoryStore::default();
+        let chain_id = ChainId::from_string("chain-a");
+
+        store
+            .set_processed_height(&chain_id, Height::new(0, 42).unwrap())
+            .unwrap();
+        store
+            .set_processed_height(&chain_id, Height::new(0, 41).unwrap())
+            .unwrap();
+
+        assert_eq!(
+            store.processed_height(&chain_id),
+            Some(Height::new(0, 42).unwrap())
+        );
+    }
+
+    #[test]
+    fn processed_height_is_recorded_once_handled() {
+        let store: SharedStateStore = Arc::new(MemoryStore::default());
+        let chain_id = ChainId::from_string("chain-a");
+
+        let processed =
+            ProcessedHeight::new(store.clone(), chain_id.clone(), Height::new(0, 10).unwrap());
+        let worker = processed.clone();
+
+        drop(processed);
+        assert_eq!(store.processed_height(&chain_id), None);
+
+        drop(worker);
+        assert_eq!(
+            store.processed_height(&chain_id),
+            Some(Height::new(0, 10).unwrap())
+        );
+
+        // The height of a batch which failed to be handled is not recorded
+        let failed =
+            ProcessedHeight::new(store.clone(), chain_id.clone(), Height::new(0, 11).unwrap());
+        failed.fail();
+        drop(failed);
+
+        assert_eq!(
+            store.processed_height(&chain_id),
+            Some(Height::new(0, 10).unwrap())
+        );
+    }
+}
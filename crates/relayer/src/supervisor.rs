@@ -3,11 +3,13 @@ use alloc::sync::Arc;
 use core::convert::Infallible;
 use core::ops::Deref;
 use core::time::Duration;
+use std::path::PathBuf;
 use std::sync::RwLock;
+use std::thread;
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use itertools::Itertools;
-use tracing::{debug, error, error_span, info, instrument, trace, warn};
+use tracing::{debug, error, error_span, info, info_span, instrument, trace, warn, Span};
 
 use ibc_relayer_types::{
     core::ics24_host::identifier::{ChainId, ChannelId, PortId},
@@ -16,15 +18,24 @@ use ibc_relayer_types::{
 };
 
 use crate::{
-    chain::{endpoint::HealthCheck, handle::ChainHandle, tracking::TrackingId},
+    accounting, audit,
+    chain::{
+        endpoint::HealthCheck,
+        handle::ChainHandle,
+        tracking::{link_packet_traces, packet_trace_id, TrackingId, RELAY_SPAN_TARGET},
+    },
     config::Config,
     event::{
+        monitor::{self, RelayerEvent},
+        record::EventRecorder,
         source::{self, Error as EventError, ErrorDetail as EventErrorDetail, EventBatch},
         IbcEventWithHeight,
     },
+    notifications::{self, Incident},
     object::Object,
     registry::{Registry, SharedRegistry},
     rest,
+    store::{open_state_store, ProcessedHeight, SharedStateStore},
     supervisor::scan::ScanMode,
     telemetry,
     util::{
@@ -49,8 +60,19 @@ pub mod spawn;
 pub mod cmd;
 use cmd::SupervisorCmd;
 
+pub mod reload;
+use reload::{ConfigDiff, ConfigReloader};
+
+pub mod control;
+pub mod paths;
+use paths::PathsStatus;
+
 use self::{scan::ChainScanner, spawn::SpawnContext};
 
+/// How often the backlogs of the paths are checked for stuck packets,
+/// when notifications are posted.
+const STUCK_PACKETS_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
 type ArcBatch = Arc<source::Result<EventBatch>>;
 type Subscription = Receiver<ArcBatch>;
 
@@ -75,6 +97,14 @@ pub struct SupervisorOptions {
     /// even when an allow list is configured for a chain and the full scan could
     /// be omitted.
     pub force_full_scan: bool,
+
+    /// Record the event batches received from the chains
+    /// to an append-only file in the given directory
+    pub record_events: Option<PathBuf>,
+
+    /// Path to the configuration file, which is loaded again
+    /// when the supervisor is asked to reload its configuration
+    pub config_path: Option<PathBuf>,
 }
 
 /**
@@ -131,6 +161,17 @@ impl SupervisorHandle {
 
         Ok(state)
     }
+
+    /// Ask the supervisor to reload its configuration
+    pub fn reload_config(&self) -> Result<ConfigDiff, Error> {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+
+        self.sender
+            .send(SupervisorCmd::ReloadConfig(tx))
+            .map_err(|_| Error::handle_send())?;
+
+        rx.recv().map_err(|_| Error::handle_recv())?
+    }
 }
 
 /// Whether the supervisor should scan the chains for clients, connections, and channels.
@@ -170,11 +211,35 @@ pub fn spawn_supervisor_tasks<Chain: ChainHandle>(
         }
     }
 
-    let workers = Arc::new(RwLock::new(WorkerMap::new()));
+    let state_store = open_state_store(&config.store).map_err(Error::state_store)?;
+
+    accounting::init(&config.accounting).map_err(Error::accounting_ledger)?;
+    audit::init(&config.audit).map_err(Error::audit_log)?;
+    notifications::init(&config.notifications).map_err(Error::notifications)?;
+
+    if config.accounting.enabled && !config.mode.packets.tx_confirmation {
+        warn!(
+            "the fees ledger is enabled but `tx_confirmation` is disabled: \
+            the fees of the packet transactions are recorded, but not their ICS29 rewards"
+        );
+    }
+
+    if config.audit.enabled && !config.mode.packets.tx_confirmation {
+        warn!(
+            "the audit log is enabled but `tx_confirmation` is disabled: \
+            no packet message will be recorded to it"
+        );
+    }
+
+    let workers = Arc::new(RwLock::new(match &state_store {
+        Some(state_store) => WorkerMap::with_state_store(state_store.clone()),
+        None => WorkerMap::new(),
+    }));
     let client_state_filter = Arc::new(RwLock::new(FilterPolicy::default()));
 
     // Only scan when needed
     if should_scan(&config, &options) {
+        // Spawn the workers for each chain as soon as its scan is done
         let scan = chain_scanner(
             &config,
             &mut registry.write(),
@@ -185,32 +250,73 @@ pub fn spawn_supervisor_tasks<Chain: ChainHandle>(
                 ScanMode::Auto
             },
         )
-        .scan_chains();
+        .scan_chains_with(|registry, chain_scan| {
+            spawn_context(&config, registry, &mut workers.acquire_write())
+                .spawn_workers_for_chain(chain_scan.clone());
+        });
 
         info!("scanned chains:");
         info!("{}", scan);
+    }
 
-        spawn_context(&config, &mut registry.write(), &mut workers.acquire_write())
-            .spawn_workers(scan);
+    if let Some(state_store) = &state_store {
+        spawn_restored_workers(
+            &config,
+            &mut registry.write(),
+            &mut workers.acquire_write(),
+            state_store,
+        );
     }
 
+    let recorder = match &options.record_events {
+        Some(dir) => {
+            let recorder = EventRecorder::create(dir).map_err(Error::event_recorder)?;
+            info!("recording event batches to {}", recorder.path().display());
+            Some(Arc::new(recorder))
+        }
+        None => None,
+    };
+
     let subscriptions = init_subscriptions(&config, &mut registry.write())?;
 
-    let batch_tasks = spawn_batch_workers(
-        &config,
+    let scan = should_scan(&config, &options);
+    let config = Arc::new(RwLock::new(config));
+
+    let reloader = Arc::new(ConfigReloader::new(
+        options.config_path,
+        scan,
+        config.clone(),
         registry.clone(),
         client_state_filter,
         workers.clone(),
-        subscriptions,
-    );
+        recorder,
+    ));
 
-    let cmd_task = spawn_cmd_worker(registry.clone(), workers.clone(), cmd_rx);
+    for (chain, subscription) in subscriptions {
+        reloader.spawn_batch_worker(chain, subscription);
+    }
+
+    let cmd_task = spawn_cmd_worker(registry.clone(), workers.clone(), reloader.clone(), cmd_rx);
 
     let mut tasks = vec![cmd_task];
-    tasks.extend(batch_tasks);
+
+    let paths_status = Arc::new(PathsStatus::new(registry.clone(), workers.clone()));
+
+    if let Some(notifications) = notifications::config() {
+        let stuck_packets_task =
+            spawn_stuck_packets_worker(paths_status.clone(), notifications.stuck_packets_age);
+        tasks.push(stuck_packets_task);
+    }
 
     if let Some(rest_rx) = rest_rx {
-        let rest_task = spawn_rest_worker(config, registry, workers.clone(), rest_rx);
+        let rest_task = spawn_rest_worker(
+            config,
+            registry,
+            workers.clone(),
+            reloader,
+            paths_status,
+            rest_rx,
+        );
         tasks.push(rest_task);
     }
 
@@ -220,49 +326,79 @@ pub fn spawn_supervisor_tasks<Chain: ChainHandle>(
     Ok(tasks)
 }
 
-fn spawn_batch_workers<Chain: ChainHandle>(
+/// Spawn the packet workers for the paths which have some in-flight state
+/// in the store, so that they resume relaying without waiting for new events.
+fn spawn_restored_workers<Chain: ChainHandle>(
     config: &Config,
+    registry: &mut Registry<Chain>,
+    workers: &mut WorkerMap,
+    state_store: &SharedStateStore,
+) {
+    if !config.mode.packets.enabled {
+        return;
+    }
+
+    for path in state_store.paths() {
+        let chains = registry
+            .get_or_spawn(&path.src_chain_id)
+            .and_then(|src| Ok((src, registry.get_or_spawn(&path.dst_chain_id)?)));
+
+        match chains {
+            Ok((src, dst)) => {
+                info!(path = %path.short_name(), "resuming packet worker from stored state");
+
+                workers.spawn(src, dst, &Object::Packet(path), config);
+            }
+            Err(e) => {
+                error!(
+                    path = %path.short_name(),
+                    "failed to resume packet worker from stored state: {}", e
+                );
+            }
+        }
+    }
+}
+
+/// Spawn the task processing the event batches received from the given chain.
+fn spawn_batch_worker<Chain: ChainHandle>(
+    config: Arc<RwLock<Config>>,
     registry: SharedRegistry<Chain>,
     client_state_filter: Arc<RwLock<FilterPolicy>>,
     workers: Arc<RwLock<WorkerMap>>,
-    subscriptions: Vec<(Chain, Subscription)>,
-) -> Vec<TaskHandle> {
-    let mut handles = Vec::with_capacity(subscriptions.len());
-
-    for (chain, subscription) in subscriptions {
-        let config = config.clone();
-        let registry = registry.clone();
-        let client_state_filter = client_state_filter.clone();
-        let workers = workers.clone();
-
-        let handle = spawn_background_task(
-            error_span!("worker.batch", chain = %chain.id()),
-            Some(Duration::from_millis(5)),
-            move || -> Result<Next, TaskError<Infallible>> {
-                if let Ok(batch) = subscription.try_recv() {
-                    handle_batch(
-                        &config,
-                        &mut registry.write(),
-                        &mut client_state_filter.acquire_write(),
-                        &mut workers.acquire_write(),
-                        chain.clone(),
-                        batch,
-                    );
+    chain: Chain,
+    subscription: Subscription,
+    recorder: Option<Arc<EventRecorder>>,
+) -> TaskHandle {
+    spawn_background_task(
+        error_span!("worker.batch", chain = %chain.id()),
+        Some(Duration::from_millis(5)),
+        move || -> Result<Next, TaskError<Infallible>> {
+            if let Ok(batch) = subscription.try_recv() {
+                if let (Some(recorder), Ok(batch)) = (&recorder, batch.deref()) {
+                    if let Err(e) = recorder.record(batch) {
+                        error!("failed to record event batch: {}", e);
+                    }
                 }
 
-                Ok(Next::Continue)
-            },
-        );
-
-        handles.push(handle);
-    }
+                handle_batch(
+                    &config.acquire_read(),
+                    &mut registry.write(),
+                    &mut client_state_filter.acquire_write(),
+                    &mut workers.acquire_write(),
+                    chain.clone(),
+                    batch,
+                );
+            }
 
-    handles
+            Ok(Next::Continue)
+        },
+    )
 }
 
 pub fn spawn_cmd_worker<Chain: ChainHandle>(
     registry: SharedRegistry<Chain>,
     workers: Arc<RwLock<WorkerMap>>,
+    reloader: Arc<ConfigReloader<Chain>>,
     cmd_rx: Receiver<SupervisorCmd>,
 ) -> TaskHandle {
     spawn_background_task(
@@ -274,6 +410,9 @@ pub fn spawn_cmd_worker<Chain: ChainHandle>(
                     SupervisorCmd::DumpState(reply_to) => {
                         dump_state(&registry.read(), &workers.acquire_read(), reply_to);
                     }
+                    SupervisorCmd::ReloadConfig(reply_to) => {
+                        let _ = reply_to.try_send(reload_config(&reloader));
+                    }
                 }
             }
 
@@ -283,16 +422,25 @@ pub fn spawn_cmd_worker<Chain: ChainHandle>(
 }
 
 pub fn spawn_rest_worker<Chain: ChainHandle>(
-    config: Config,
+    config: Arc<RwLock<Config>>,
     registry: SharedRegistry<Chain>,
     workers: Arc<RwLock<WorkerMap>>,
+    reloader: Arc<ConfigReloader<Chain>>,
+    paths_status: Arc<PathsStatus<Chain>>,
     rest_rx: rest::Receiver,
 ) -> TaskHandle {
     spawn_background_task(
         error_span!("rest"),
         Some(Duration::from_millis(500)),
         move || -> Result<Next, TaskError<Infallible>> {
-            handle_rest_requests(&config, &registry.read(), &workers.acquire_read(), &rest_rx);
+            handle_rest_requests(
+                &config,
+                &registry,
+                &workers,
+                &reloader,
+                &paths_status,
+                &rest_rx,
+            );
 
             Ok(Next::Continue)
         },
@@ -311,6 +459,41 @@ pub fn spawn_cleanup_worker(workers: Arc<RwLock<WorkerMap>>) -> TaskHandle {
     )
 }
 
+/// Spawn a background task which periodically checks the backlogs of the paths
+/// on which packets are relayed, and notifies the paths whose oldest pending
+/// packet or acknowledgement is older than the given age.
+pub fn spawn_stuck_packets_worker<Chain: ChainHandle>(
+    paths_status: Arc<PathsStatus<Chain>>,
+    stuck_packets_age: Duration,
+) -> TaskHandle {
+    let is_stuck = move |backlog: &paths::Backlog| {
+        backlog
+            .oldest_age_secs
+            .map_or(false, |age| age >= stuck_packets_age.as_secs())
+    };
+
+    spawn_background_task(
+        error_span!("stuck_packets"),
+        Some(STUCK_PACKETS_CHECK_INTERVAL),
+        move || -> Result<Next, TaskError<Infallible>> {
+            for status in paths_status.get() {
+                if is_stuck(&status.unreceived_packets) || is_stuck(&status.unreceived_acks) {
+                    notifications::notify(Incident::PacketsStuck {
+                        chain_id: status.channel.chain_id,
+                        port_id: status.channel.port_id,
+                        channel_id: status.channel.channel_id,
+                        counterparty_chain_id: status.counterparty.chain_id,
+                        unreceived_packets: status.unreceived_packets,
+                        unreceived_acks: status.unreceived_acks,
+                    });
+                }
+            }
+
+            Ok(Next::Continue)
+        },
+    )
+}
+
 /// Returns `true` if the relayer should filter based on
 /// client state attributes, e.g., trust threshold.
 /// Returns `false` otherwise.
@@ -594,6 +777,42 @@ pub fn collect_events(
     collected
 }
 
+/// Feed a batch of events, eg. one which was recorded, through the same pipeline as the
+/// batches received from the chains, up to the point where the events are forwarded to
+/// the workers, without spawning any worker, and thus without submitting any transaction.
+///
+/// Returns the events which would have been forwarded to the worker of each object.
+pub fn replay_batch<Chain: ChainHandle>(
+    config: &Config,
+    registry: &mut Registry<Chain>,
+    client_state_filter: &mut FilterPolicy,
+    workers: &WorkerMap,
+    batch: &EventBatch,
+) -> Result<Vec<(Object, Vec<IbcEventWithHeight>)>, Error> {
+    let src_chain = registry
+        .get_or_spawn(&batch.chain_id)
+        .map_err(Error::spawn)?;
+
+    let collected = collect_events(config, workers, &src_chain, batch);
+
+    let forwarded = collected
+        .per_object
+        .into_iter()
+        .filter(|(object, events_with_heights)| {
+            !events_with_heights.is_empty()
+                && relay_on_object(
+                    config,
+                    registry,
+                    client_state_filter,
+                    &src_chain.id(),
+                    object,
+                )
+        })
+        .collect();
+
+    Ok(forwarded)
+}
+
 /// Create a new `SpawnContext` for spawning workers.
 fn spawn_context<'a, Chain: ChainHandle>(
     config: &'a Config,
@@ -699,30 +918,128 @@ fn state<Chain: ChainHandle>(registry: &Registry<Chain>, workers: &WorkerMap) ->
     SupervisorState::new(chains, workers.handles())
 }
 
+/// Reload the configuration of the supervisor, and log the outcome.
+fn reload_config<Chain: ChainHandle>(
+    reloader: &ConfigReloader<Chain>,
+) -> Result<ConfigDiff, Error> {
+    let _span = error_span!("reload_config").entered();
+
+    info!("reloading configuration");
+
+    let result = reloader.reload();
+
+    match &result {
+        Ok(diff) => info!("configuration reloaded, {}", diff),
+        Err(e) => error!("failed to reload configuration: {}", e),
+    }
+
+    result
+}
+
 fn handle_rest_requests<Chain: ChainHandle>(
-    config: &Config,
-    registry: &Registry<Chain>,
-    workers: &WorkerMap,
+    config: &Arc<RwLock<Config>>,
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    reloader: &ConfigReloader<Chain>,
+    paths_status: &Arc<PathsStatus<Chain>>,
     rest_rx: &rest::Receiver,
 ) {
-    if let Some(cmd) = rest::process_incoming_requests(config, rest_rx) {
-        handle_rest_cmd(registry, workers, cmd);
+    // Release the lock on the configuration before handling the command, which might reload it
+    let cmd = rest::process_incoming_requests(&config.acquire_read(), rest_rx);
+
+    if let Some(cmd) = cmd {
+        handle_rest_cmd(config, registry, workers, reloader, paths_status, cmd);
     }
 }
 
 #[instrument(name = "supervisor.handle_rest_cmd", level = "error", skip_all)]
 fn handle_rest_cmd<Chain: ChainHandle>(
-    registry: &Registry<Chain>,
-    workers: &WorkerMap,
+    config: &Arc<RwLock<Config>>,
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    reloader: &ConfigReloader<Chain>,
+    paths_status: &Arc<PathsStatus<Chain>>,
     m: rest::Command,
 ) {
     match m {
         rest::Command::DumpState(reply) => {
-            let state = state(registry, workers);
+            let state = state(&registry.read(), &workers.acquire_read());
             reply
                 .send(Ok(state))
                 .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
         }
+        rest::Command::ReloadConfig(reply) => {
+            let result = reload_config(reloader)
+                .map_err(|e| rest::RestApiError::ConfigReload(e.to_string()));
+
+            reply
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+        rest::Command::ClearPackets {
+            chain_id,
+            port_id,
+            channel_id,
+            reply_to,
+        } => {
+            let result =
+                control::clear_packets(&workers.acquire_read(), &chain_id, &port_id, &channel_id)
+                    .map_err(|e| rest::RestApiError::ClearPackets(e.to_string()));
+
+            reply_to
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+        rest::Command::UpdateClient {
+            chain_id,
+            client_id,
+            force,
+            reply_to,
+        } => {
+            let result = control::update_client(registry, &chain_id, &client_id, force)
+                .map_err(|e| rest::RestApiError::ClientUpdate(e.to_string()));
+
+            reply_to
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+        rest::Command::GetPaths(reply_to) => {
+            // Querying the status of all the paths may take a while,
+            // during which the other requests must still be handled
+            let paths_status = paths_status.clone();
+
+            thread::spawn(move || {
+                reply_to
+                    .send(Ok(paths_status.get()))
+                    .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+            });
+        }
+        rest::Command::GetPath {
+            chain_id,
+            port_id,
+            channel_id,
+            reply_to,
+        } => {
+            let result = paths::path_status(registry, workers, &chain_id, &port_id, &channel_id)
+                .map_err(|e| rest::RestApiError::PathStatus(e.to_string()));
+
+            reply_to
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+        rest::Command::ControlWorker {
+            id,
+            action,
+            reply_to,
+        } => {
+            let result =
+                control::control_worker(&config.acquire_read(), registry, workers, id, action)
+                    .map_err(|e| rest::RestApiError::WorkerControl(e.to_string()));
+
+            reply_to
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
     }
 }
 
@@ -754,11 +1071,68 @@ fn process_batch<Chain: ChainHandle>(
     workers: &mut WorkerMap,
     src_chain: Chain,
     batch: &EventBatch,
+    processed_height: Option<&Arc<ProcessedHeight>>,
 ) -> Result<(), Error> {
     assert_eq!(src_chain.id(), batch.chain_id);
 
     telemetry!(received_event_batch, batch.tracking_id);
 
+    // Only follow the batches which carry IBC events besides the new block
+    let span = if batch
+        .events
+        .iter()
+        .any(|event_with_height| !matches!(event_with_height.event, IbcEvent::NewBlock(_)))
+    {
+        batch.tracking_id.trace(info_span!(
+            target: RELAY_SPAN_TARGET,
+            "events_received",
+            chain = %batch.chain_id,
+            height = %batch.height,
+            tracking_id = %batch.tracking_id,
+            events = batch.events.len(),
+            sequences = %batch
+                .events
+                .iter()
+                .filter_map(|event_with_height| event_with_height.event.packet())
+                .map(|packet| packet.sequence)
+                .join(","),
+        ))
+    } else {
+        Span::none()
+    };
+
+    // Link the batch to the traces of the packets sent on the chain it comes from,
+    // which the spans relaying these packets and their acknowledgments link to as well
+    link_packet_traces(
+        &span,
+        batch
+            .events
+            .iter()
+            .filter_map(|event_with_height| match &event_with_height.event {
+                IbcEvent::SendPacket(send_packet) => Some(packet_trace_id(
+                    &batch.chain_id,
+                    &send_packet.packet.source_port,
+                    &send_packet.packet.source_channel,
+                    send_packet.packet.sequence,
+                )),
+                _ => None,
+            }),
+    );
+
+    let _span = span.entered();
+
+    for event_with_height in &batch.events {
+        if !matches!(event_with_height.event, IbcEvent::NewBlock(_)) {
+            monitor::publish(|| {
+                RelayerEvent::ibc_event(
+                    batch.chain_id.clone(),
+                    event_with_height.height,
+                    event_with_height.event.clone(),
+                )
+            });
+        }
+    }
+
     let collected = collect_events(config, workers, &src_chain, batch);
 
     // If there is a NewBlock event, forward this event first to any workers affected by it.
@@ -808,6 +1182,7 @@ fn process_batch<Chain: ChainHandle>(
             events_with_heights,
             batch.chain_id.clone(),
             batch.tracking_id,
+            processed_height.cloned(),
         );
     }
 
@@ -892,10 +1267,26 @@ fn handle_batch<Chain: ChainHandle>(
 
     match batch.deref() {
         Ok(batch) => {
-            if let Err(e) =
-                process_batch(config, registry, client_state_filter, workers, chain, batch)
-            {
+            // The height is recorded as processed once the workers
+            // to which the events are dispatched have handled them
+            let processed_height = workers
+                .state_store()
+                .map(|store| ProcessedHeight::new(store.clone(), chain_id, batch.height));
+
+            if let Err(e) = process_batch(
+                config,
+                registry,
+                client_state_filter,
+                workers,
+                chain,
+                batch,
+                processed_height.as_ref(),
+            ) {
                 error!("error during batch processing: {}", e);
+
+                if let Some(processed_height) = &processed_height {
+                    processed_height.fail();
+                }
             }
         }
         Err(EventError(EventErrorDetail::SubscriptionCancelled(_), _)) => {
@@ -904,6 +1295,12 @@ fn handle_batch<Chain: ChainHandle>(
             let _ = clear_pending_packets(workers, &chain_id)
                 .map_err(|e| error!("error during clearing pending packets: {}", e));
         }
+        Err(EventError(EventErrorDetail::MissedHeights(_), _)) => {
+            warn!("some events might have been missed, clearing pending packets");
+
+            let _ = clear_pending_packets(workers, &chain_id)
+                .map_err(|e| error!("error during clearing pending packets: {}", e));
+        }
         Err(e) => {
             error!("error when receiving event batch: {}", e)
         }
@@ -943,3 +1340,162 @@ impl CollectedEvents {
         self.new_block.is_some()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc_relayer_types::clients::ics07_tendermint::client_state::{
+        AllowUpdate, ClientState as TmClientState,
+    };
+    use ibc_relayer_types::core::ics02_client::trust_threshold::TrustThreshold;
+    use ibc_relayer_types::core::ics03_connection::connection::{
+        ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+    };
+    use ibc_relayer_types::core::ics03_connection::version::Version as ConnectionVersion;
+    use ibc_relayer_types::core::ics04_channel::channel::{
+        ChannelEnd, Counterparty as ChannelCounterparty, Ordering, State as ChannelState,
+    };
+    use ibc_relayer_types::core::ics04_channel::version::Version as ChannelVersion;
+    use ibc_relayer_types::core::ics23_commitment::specs::ProofSpecs;
+    use ibc_relayer_types::core::ics24_host::identifier::{ClientId, ConnectionId};
+    use ibc_relayer_types::events::IbcEventType;
+
+    use crate::chain::handle::{BaseChainHandle, ChainRequest};
+    use crate::client_state::AnyClientState;
+    use crate::event::record::read_recorded_batches;
+
+    /// A handle to a chain whose channels are all open to the given counterparty chain,
+    /// on top of `connection-0` and client `07-tendermint-0`. The queries of anything
+    /// else fail, as the handle receives no reply.
+    fn chain_handle(chain_id: &str, counterparty_chain_id: &str) -> BaseChainHandle {
+        let (sender, receiver) = unbounded::<(Span, ChainRequest)>();
+
+        let counterparty_chain_id = ChainId::from_string(counterparty_chain_id);
+        let client_id = ClientId::default();
+        let connection_id = ConnectionId::new(0);
+
+        thread::spawn(move || {
+            for (_, request) in receiver {
+                match request {
+                    ChainRequest::QueryChannel {
+                        request, reply_to, ..
+                    } => {
+                        let channel_end = ChannelEnd::new(
+                            ChannelState::Open,
+                            Ordering::Unordered,
+                            ChannelCounterparty::new(request.port_id, Some(ChannelId::new(1))),
+                            vec![connection_id.clone()],
+                            ChannelVersion::ics20(),
+                        );
+
+                        reply_to.send(Ok((channel_end, None))).unwrap();
+                    }
+                    ChainRequest::QueryConnection { reply_to, .. } => {
+                        let connection_end = ConnectionEnd::new(
+                            ConnectionState::Open,
+                            client_id.clone(),
+                            ConnectionCounterparty::new(
+                                client_id.clone(),
+                                Some(connection_id.clone()),
+                                b"ibc".to_vec().try_into().unwrap(),
+                            ),
+                            vec![ConnectionVersion::default()],
+                            Duration::ZERO,
+                        );
+
+                        reply_to.send(Ok((connection_end, None))).unwrap();
+                    }
+                    ChainRequest::QueryClientState { reply_to, .. } => {
+                        let client_state = TmClientState::new(
+                            counterparty_chain_id.clone(),
+                            TrustThreshold::default(),
+                            Duration::from_secs(14 * 24 * 3600),
+                            Duration::from_secs(21 * 24 * 3600),
+                            Duration::from_secs(5),
+                            Height::new(counterparty_chain_id.version(), 10).unwrap(),
+                            ProofSpecs::default(),
+                            vec![],
+                            AllowUpdate {
+                                after_expiry: true,
+                                after_misbehaviour: true,
+                            },
+                        )
+                        .unwrap();
+
+                        reply_to
+                            .send(Ok((AnyClientState::Tendermint(client_state), None)))
+                            .unwrap();
+                    }
+                    _ => (),
+                }
+            }
+        });
+
+        BaseChainHandle::new(ChainId::from_string(chain_id), sender)
+    }
+
+    #[test]
+    fn replay_recorded_batches() {
+        let config = crate::config::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/config/fixtures/relayer_conf_example.toml"
+        ))
+        .unwrap();
+
+        let mut registry = Registry::new(config.clone());
+        registry.insert(chain_handle("chain_A", "chain_B"));
+        registry.insert(chain_handle("chain_B", "chain_A"));
+
+        let mut client_state_filter = FilterPolicy::default();
+        let workers = WorkerMap::new();
+
+        let batches = read_recorded_batches(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/event/fixtures").as_ref(),
+        )
+        .unwrap();
+
+        let dispatched = batches
+            .iter()
+            .map(|batch| {
+                replay_batch(
+                    &config,
+                    &mut registry,
+                    &mut client_state_filter,
+                    &workers,
+                    batch,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|(object, events)| {
+                    let events = events
+                        .into_iter()
+                        .map(|event| {
+                            let packet = event.event.packet().unwrap();
+                            (event.event.event_type(), packet.sequence.into())
+                        })
+                        .collect::<Vec<(_, u64)>>();
+
+                    (object.short_name(), events)
+                })
+                .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // The packet sent on `channel-5` is not dispatched, as the channel is filtered out,
+        // and the new blocks are not dispatched to the packet workers
+        assert_eq!(
+            dispatched,
+            vec![
+                vec![(
+                    "packet::channel-0/transfer:chain_A->chain_B".to_string(),
+                    vec![(IbcEventType::SendPacket, 1), (IbcEventType::SendPacket, 2)]
+                )],
+                vec![(
+                    "packet::channel-0/transfer:chain_A->chain_B".to_string(),
+                    vec![(IbcEventType::WriteAck, 7)]
+                )],
+            ]
+        );
+    }
+}
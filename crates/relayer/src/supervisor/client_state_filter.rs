@@ -86,6 +86,11 @@ pub struct FilterPolicy {
 }
 
 impl FilterPolicy {
+    /// Add the permissions cached by another filter to this one.
+    pub fn merge(&mut self, other: FilterPolicy) {
+        self.permission_cache.extend(other.permission_cache);
+    }
+
     /// Given a connection end and the underlying client for that
     /// connection, controls both the client as well as the
     /// client on the counterparty chain.
@@ -1,8 +1,11 @@
 use crossbeam_channel::Sender;
 
 use super::dump_state::SupervisorState;
+use super::reload::ConfigDiff;
+use super::Error;
 
 #[derive(Clone, Debug)]
 pub enum SupervisorCmd {
     DumpState(Sender<SupervisorState>),
+    ReloadConfig(Sender<Result<ConfigDiff, Error>>),
 }
@@ -0,0 +1,200 @@
+//! Operations on the workers of a running supervisor, which are triggered
+//! on demand, eg. through the REST API, rather than by the events of the chains.
+
+use alloc::sync::Arc;
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use ibc_relayer_types::core::{
+    ics02_client::client_state::ClientState,
+    ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId},
+};
+
+use crate::{
+    chain::{
+        handle::ChainHandle,
+        requests::{IncludeProof, QueryClientStateRequest, QueryHeight},
+    },
+    config::Config,
+    foreign_client::ForeignClient,
+    object::Object,
+    registry::SharedRegistry,
+    util::lock::LockExt,
+    worker::{WorkerId, WorkerMap},
+};
+
+use super::{dump_state::WorkerDesc, Error};
+
+/// An action to perform on a specific worker.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerAction {
+    /// Stop processing events and commands until the worker is resumed.
+    /// The events received in the meantime are queued.
+    Pause,
+
+    /// Resume a paused worker.
+    Resume,
+
+    /// Shut down the worker and spawn a new one for the same object,
+    /// with a new identifier.
+    Restart,
+}
+
+/// The outcome of a client update requested with [`update_client`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientUpdate {
+    /// The chain hosting the client
+    pub chain_id: ChainId,
+    pub client_id: ClientId,
+    /// The chain tracked by the client
+    pub src_chain_id: ChainId,
+    /// Whether an update was submitted, which is not the case when a refresh
+    /// was requested and the client did not need to be refreshed yet
+    pub updated: bool,
+}
+
+/// Instruct the packet workers relaying from the given channel to clear their
+/// pending packets, and return the identifiers of those workers.
+pub fn clear_packets(
+    workers: &WorkerMap,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Result<Vec<WorkerId>, Error> {
+    let cleared = workers
+        .workers_for_chain(chain_id)
+        .into_iter()
+        .filter(|worker| match worker.object() {
+            Object::Packet(packet) => {
+                &packet.src_chain_id == chain_id
+                    && &packet.src_port_id == port_id
+                    && &packet.src_channel_id == channel_id
+            }
+            _ => false,
+        })
+        .map(|worker| {
+            worker.clear_pending_packets();
+            worker.id()
+        })
+        .collect::<Vec<_>>();
+
+    if cleared.is_empty() {
+        return Err(Error::no_packet_worker(
+            chain_id.clone(),
+            port_id.clone(),
+            channel_id.clone(),
+        ));
+    }
+
+    info!(
+        "clearing pending packets on {}/{} of chain {}",
+        port_id, channel_id, chain_id
+    );
+
+    Ok(cleared)
+}
+
+/// Update the client with the given identifier, hosted on the given chain.
+///
+/// If `force` is `false`, the client is only refreshed, ie. it is updated only
+/// if its refresh window has elapsed. Otherwise it is updated to the latest
+/// height of the chain it tracks.
+pub fn update_client<Chain: ChainHandle>(
+    registry: &SharedRegistry<Chain>,
+    chain_id: &ChainId,
+    client_id: &ClientId,
+    force: bool,
+) -> Result<ClientUpdate, Error> {
+    let chain = registry.get_or_spawn(chain_id).map_err(Error::spawn)?;
+
+    let (client_state, _) = chain
+        .query_client_state(
+            QueryClientStateRequest {
+                client_id: client_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map_err(Error::relayer)?;
+
+    let src_chain_id = client_state.chain_id();
+    let src_chain = registry.get_or_spawn(&src_chain_id).map_err(Error::spawn)?;
+
+    let mut client = ForeignClient::restore(client_id.clone(), chain, src_chain);
+
+    let to_error = |e| Error::client_update(client_id.clone(), chain_id.clone(), e);
+
+    let updated = if force {
+        !client
+            .build_latest_update_client_and_send()
+            .map_err(to_error)?
+            .is_empty()
+    } else {
+        client.refresh().map_err(to_error)?.is_some()
+    };
+
+    info!(
+        "client {} on chain {} {}",
+        client_id,
+        chain_id,
+        if updated { "updated" } else { "up to date" }
+    );
+
+    Ok(ClientUpdate {
+        chain_id: chain_id.clone(),
+        client_id: client_id.clone(),
+        src_chain_id,
+        updated,
+    })
+}
+
+/// Perform the given action on the worker with the given identifier,
+/// and return the description of the worker afterwards.
+pub fn control_worker<Chain: ChainHandle>(
+    config: &Config,
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    id: WorkerId,
+    action: WorkerAction,
+) -> Result<WorkerDesc, Error> {
+    let object = workers
+        .acquire_read()
+        .get(id)
+        .map(|worker| worker.object().clone())
+        .ok_or_else(|| Error::unknown_worker(id))?;
+
+    info!("{:?} worker {} ({})", action, id, object.short_name());
+
+    match action {
+        WorkerAction::Pause | WorkerAction::Resume => {
+            let workers = workers.acquire_read();
+            let worker = workers.get(id).ok_or_else(|| Error::unknown_worker(id))?;
+
+            if action == WorkerAction::Pause {
+                worker.pause();
+            } else {
+                worker.resume();
+            }
+
+            Ok(WorkerDesc::from_handle(worker))
+        }
+        WorkerAction::Restart => {
+            // Spawn the chain runtimes before locking the workers, as the registry
+            // must always be locked first
+            let src = registry
+                .get_or_spawn(object.src_chain_id())
+                .map_err(Error::spawn)?;
+            let dst = registry
+                .get_or_spawn(object.dst_chain_id())
+                .map_err(Error::spawn)?;
+
+            let mut workers = workers.acquire_write();
+            let worker = workers.respawn(src, dst, &object, config);
+
+            Ok(WorkerDesc::from_handle(worker))
+        }
+    }
+}
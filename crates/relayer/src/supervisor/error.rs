@@ -1,11 +1,23 @@
+use std::path::PathBuf;
+
 use flex_error::define_error;
 
 use ibc_relayer_types::core::ics03_connection::connection::Counterparty;
-use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, ConnectionId, PortId};
+use ibc_relayer_types::core::ics24_host::identifier::{
+    ChainId, ChannelId, ClientId, ConnectionId, PortId,
+};
 
+use crate::accounting::Error as AccountingError;
+use crate::audit::Error as AuditError;
+use crate::config::Error as ConfigError;
 use crate::error::Error as RelayerError;
+use crate::event::record::Error as RecordError;
+use crate::foreign_client::ForeignClientError;
+use crate::notifications::Error as NotificationsError;
 use crate::spawn::SpawnError;
+use crate::store::Error as StoreError;
 use crate::supervisor::scan::Error as ScanError;
+use crate::worker::WorkerId;
 
 define_error! {
     Error {
@@ -70,6 +82,61 @@ define_error! {
             [ ScanError ]
             |_| { "supervisor encountered an error when scanning chains" },
 
+        StateStore
+            [ StoreError ]
+            |_| { "supervisor was not able to open the state store" },
+
+        AccountingLedger
+            [ AccountingError ]
+            |_| { "supervisor was not able to open the accounting ledger" },
+
+        AuditLog
+            [ AuditError ]
+            |_| { "supervisor was not able to open the packet audit log" },
+
+        Notifications
+            [ NotificationsError ]
+            |_| { "supervisor was not able to start posting notifications" },
+
+        EventRecorder
+            [ RecordError ]
+            |_| { "supervisor was not able to open the event record" },
+
+        NoConfigPath
+            |_| { "cannot reload the configuration, as the path to the configuration file is unknown" },
+
+        ConfigLoad
+            { path: PathBuf }
+            [ ConfigError ]
+            |e| { format!("failed to load the configuration from {}", e.path.display()) },
+
+        DuplicateChain
+            { chain_id: ChainId }
+            |e| { format!("the configuration has duplicate entries for chain '{}'", e.chain_id) },
+
+        UnknownWorker
+            { id: WorkerId }
+            |e| { format!("there is no worker with id {}", e.id) },
+
+        NoPacketWorker
+            {
+                chain_id: ChainId,
+                port_id: PortId,
+                channel_id: ChannelId,
+            }
+            |e| {
+                format_args!("there is no packet worker relaying from channel {0}/{1} on chain {2}",
+                    e.port_id, e.channel_id, e.chain_id)
+            },
+
+        ClientUpdate
+            {
+                client_id: ClientId,
+                chain_id: ChainId,
+            }
+            [ ForeignClientError ]
+            |e| { format!("failed to update client {} on chain {}", e.client_id, e.chain_id) },
+
         HandleSend
             |_| { "failed to send a command to the supervisor through a channel" },
 
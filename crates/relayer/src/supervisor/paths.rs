@@ -0,0 +1,394 @@
+//! The status of the paths on which packets are relayed, as reported by the REST API.
+//!
+//! The status of a path is mostly queried from the chains at both of its ends,
+//! except for the outcome of the latest relaying attempts, which is tracked by
+//! the packet worker relaying from the channel.
+
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+use core::time::Duration;
+use std::sync::{Mutex, RwLock};
+use std::thread;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tendermint::Time;
+use tracing::{debug, warn};
+
+use ibc_relayer_types::core::{
+    ics02_client::client_state::ClientState,
+    ics03_connection::connection::IdentifiedConnectionEnd,
+    ics04_channel::{
+        channel::{IdentifiedChannelEnd, State as ChannelState},
+        packet::Sequence,
+    },
+    ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+};
+use ibc_relayer_types::events::WithBlockDataType;
+use ibc_relayer_types::Height;
+
+use crate::{
+    chain::{
+        counterparty::{channel_connection_client_no_checks, pending_packet_summary},
+        handle::ChainHandle,
+        requests::{
+            IncludeProof, Qualified, QueryConsensusStateRequest, QueryHeight,
+            QueryHostConsensusStateRequest, QueryPacketEventDataRequest,
+        },
+    },
+    client_state::IdentifiedAnyClientState,
+    link::activity::{RelayActivity, RelayError},
+    object::{Object, Packet},
+    registry::SharedRegistry,
+    util::lock::LockExt,
+    worker::WorkerMap,
+};
+
+use super::Error;
+
+/// How long the status of all the paths is reused for, as querying it
+/// makes several queries to the chains at both ends of every path.
+const PATHS_STATUS_TTL: Duration = Duration::from_secs(30);
+
+/// Maximum number of paths whose status is queried at the same time.
+const MAX_CONCURRENT_PATH_QUERIES: usize = 8;
+
+/// The status of the path on which the packets sent on a channel are relayed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathStatus {
+    /// The channel on which the packets are sent
+    pub channel: ChannelEndStatus,
+    /// The channel on which the packets are received
+    pub counterparty: ChannelEndStatus,
+    /// The packets sent and not yet received on the counterparty
+    pub unreceived_packets: Backlog,
+    /// The packets received on the counterparty, whose acknowledgement
+    /// is not yet received on the channel
+    pub unreceived_acks: Backlog,
+    /// When the transactions submitted on the path were last confirmed
+    pub last_relay: Option<Time>,
+    /// The last error encountered while relaying on the path
+    pub last_error: Option<RelayError>,
+}
+
+/// The status of the end of a channel on a chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelEndStatus {
+    pub chain_id: ChainId,
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub state: ChannelState,
+    pub connection_id: ConnectionId,
+    pub client: ClientStatus,
+}
+
+/// The status of the client underlying the end of a channel.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientStatus {
+    pub client_id: ClientId,
+    pub latest_height: Height,
+    /// When the client expires if it is not updated, ie. the time of its latest
+    /// consensus state plus its trusting period
+    pub trusting_period_expiry: Option<Time>,
+    pub frozen: bool,
+}
+
+/// The packets pending on a path.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Backlog {
+    pub count: usize,
+    pub oldest_sequence: Option<Sequence>,
+    /// How long ago the oldest pending packet was sent, or its acknowledgement
+    /// was written, in seconds
+    pub oldest_age_secs: Option<u64>,
+}
+
+/// The status of the paths of all the packet workers, shared by the REST API and
+/// the stuck packets notifications, which is queried again once it is older than
+/// [`PATHS_STATUS_TTL`] or the packet workers changed, by a single caller at a time.
+pub struct PathsStatus<Chain: ChainHandle> {
+    registry: SharedRegistry<Chain>,
+    workers: Arc<RwLock<WorkerMap>>,
+    latest: Mutex<Option<QueriedStatus>>,
+}
+
+/// The status of the paths, along with when it was queried and the paths it was queried for.
+type QueriedStatus = (Instant, BTreeSet<Packet>, Vec<PathStatus>);
+
+impl<Chain: ChainHandle> PathsStatus<Chain> {
+    pub fn new(registry: SharedRegistry<Chain>, workers: Arc<RwLock<WorkerMap>>) -> Self {
+        Self {
+            registry,
+            workers,
+            latest: Mutex::new(None),
+        }
+    }
+
+    /// The status of the paths of all the packet workers, as queried at most
+    /// [`PATHS_STATUS_TTL`] ago for the same packet workers.
+    pub fn get(&self) -> Vec<PathStatus> {
+        // Callers wait for the status being queried, if any, instead of querying it again
+        // The status is queried again if a previous query panicked while holding the lock
+        let mut latest = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+
+        let paths = packet_paths(&self.workers);
+        let queried_paths = paths.iter().map(|(path, _)| path.clone()).collect();
+
+        // The paths change as the packet workers are spawned and shut down,
+        // eg. when the configuration is reloaded
+        if let Some((queried_at, latest_paths, statuses)) = latest.as_ref() {
+            if queried_at.elapsed() < PATHS_STATUS_TTL && latest_paths == &queried_paths {
+                return statuses.clone();
+            }
+        }
+
+        let statuses = query_paths_status(&self.registry, &paths);
+        *latest = Some((Instant::now(), queried_paths, statuses.clone()));
+
+        statuses
+    }
+}
+
+/// The paths of all the packet workers, ie. the paths on which packets
+/// are currently relayed, along with the activity of their workers.
+fn packet_paths(workers: &Arc<RwLock<WorkerMap>>) -> Vec<(Packet, Option<RelayActivity>)> {
+    workers
+        .acquire_read()
+        .handles()
+        .filter_map(|worker| match worker.object() {
+            Object::Packet(path) => Some((path.clone(), worker.relay_activity())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The status of the given paths, queried up to [`MAX_CONCURRENT_PATH_QUERIES`]
+/// paths at a time.
+///
+/// The paths whose status cannot be queried are skipped.
+fn query_paths_status<Chain: ChainHandle>(
+    registry: &SharedRegistry<Chain>,
+    paths: &[(Packet, Option<RelayActivity>)],
+) -> Vec<PathStatus> {
+    let query = |(path, activity): &(Packet, Option<RelayActivity>)| {
+        path_status_with_activity(
+            registry,
+            &path.src_chain_id,
+            &path.src_port_id,
+            &path.src_channel_id,
+            activity.clone(),
+        )
+        .map_err(|e| {
+            warn!(
+                "failed to query the status of path {}: {}",
+                path.short_name(),
+                e
+            )
+        })
+        .ok()
+    };
+
+    paths
+        .chunks(MAX_CONCURRENT_PATH_QUERIES)
+        .flat_map(|chunk| {
+            thread::scope(|s| {
+                let threads = chunk
+                    .iter()
+                    .map(|path| s.spawn(|| query(path)))
+                    .collect::<Vec<_>>();
+
+                chunk
+                    .iter()
+                    .zip(threads)
+                    .filter_map(|((path, _), thread)| {
+                        thread.join().unwrap_or_else(|_| {
+                            warn!(
+                                "failed to query the status of path {}: query panicked",
+                                path.short_name()
+                            );
+                            None
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect()
+}
+
+/// The status of the path on which the packets sent on the given channel are relayed.
+pub fn path_status<Chain: ChainHandle>(
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Result<PathStatus, Error> {
+    let activity = workers
+        .acquire_read()
+        .workers_for_chain(chain_id)
+        .into_iter()
+        .find(|worker| match worker.object() {
+            Object::Packet(path) => {
+                &path.src_chain_id == chain_id
+                    && &path.src_port_id == port_id
+                    && &path.src_channel_id == channel_id
+            }
+            _ => false,
+        })
+        .and_then(|worker| worker.relay_activity());
+
+    path_status_with_activity(registry, chain_id, port_id, channel_id, activity)
+}
+
+fn path_status_with_activity<Chain: ChainHandle>(
+    registry: &SharedRegistry<Chain>,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    activity: Option<RelayActivity>,
+) -> Result<PathStatus, Error> {
+    let chain = registry.get_or_spawn(chain_id).map_err(Error::spawn)?;
+    let local = channel_connection_client_no_checks(&chain, port_id, channel_id)?;
+
+    let counterparty_end = local.channel.channel_end.counterparty();
+    let counterparty_port_id = counterparty_end.port_id.clone();
+    let counterparty_channel_id = counterparty_end
+        .channel_id
+        .clone()
+        .ok_or_else(Error::missing_counterparty_channel_id)?;
+
+    let counterparty_chain = registry
+        .get_or_spawn(&local.client.client_state.chain_id())
+        .map_err(Error::spawn)?;
+    let remote = channel_connection_client_no_checks(
+        &counterparty_chain,
+        &counterparty_port_id,
+        &counterparty_channel_id,
+    )?;
+
+    let pending = pending_packet_summary(&chain, &counterparty_chain, &local.channel)?;
+
+    let packet_query = |event_id, sequence| QueryPacketEventDataRequest {
+        event_id,
+        source_port_id: port_id.clone(),
+        source_channel_id: channel_id.clone(),
+        destination_port_id: counterparty_port_id.clone(),
+        destination_channel_id: counterparty_channel_id.clone(),
+        sequences: vec![sequence],
+        height: Qualified::SmallerEqual(QueryHeight::Latest),
+    };
+
+    // Packets are sent on the chain, and their acknowledgements written on the counterparty
+    let unreceived_packets = backlog(&chain, pending.unreceived_packets, |sequence| {
+        packet_query(WithBlockDataType::SendPacket, sequence)
+    });
+    let unreceived_acks = backlog(&counterparty_chain, pending.unreceived_acks, |sequence| {
+        packet_query(WithBlockDataType::WriteAck, sequence)
+    });
+
+    let activity = activity.unwrap_or_default();
+
+    Ok(PathStatus {
+        channel: channel_end_status(&chain, &local.channel, &local.connection, &local.client)?,
+        counterparty: channel_end_status(
+            &counterparty_chain,
+            &remote.channel,
+            &remote.connection,
+            &remote.client,
+        )?,
+        unreceived_packets,
+        unreceived_acks,
+        last_relay: activity.last_relay,
+        last_error: activity.last_error,
+    })
+}
+
+fn channel_end_status<Chain: ChainHandle>(
+    chain: &Chain,
+    channel: &IdentifiedChannelEnd,
+    connection: &IdentifiedConnectionEnd,
+    client: &IdentifiedAnyClientState,
+) -> Result<ChannelEndStatus, Error> {
+    let latest_height = client.client_state.latest_height();
+
+    let (consensus_state, _) = chain
+        .query_consensus_state(
+            QueryConsensusStateRequest {
+                client_id: client.client_id.clone(),
+                consensus_height: latest_height,
+                query_height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map_err(Error::relayer)?;
+
+    let trusting_period_expiry = (consensus_state.timestamp()
+        + client.client_state.trusting_period())
+    .ok()
+    .and_then(|expiry| expiry.into_tm_time());
+
+    Ok(ChannelEndStatus {
+        chain_id: chain.id(),
+        port_id: channel.port_id.clone(),
+        channel_id: channel.channel_id.clone(),
+        state: *channel.channel_end.state(),
+        connection_id: connection.connection_id.clone(),
+        client: ClientStatus {
+            client_id: client.client_id.clone(),
+            latest_height,
+            trusting_period_expiry,
+            frozen: client.client_state.frozen_height().is_some(),
+        },
+    })
+}
+
+/// The backlog of the given pending sequences, where the age of the oldest one
+/// is the time elapsed since the block of the event returned by the given query.
+fn backlog<Chain: ChainHandle>(
+    chain: &Chain,
+    sequences: Vec<Sequence>,
+    query: impl Fn(Sequence) -> QueryPacketEventDataRequest,
+) -> Backlog {
+    let oldest_sequence = sequences.iter().min().copied();
+
+    let oldest_age_secs = oldest_sequence
+        .and_then(|sequence| event_age(chain, query(sequence)))
+        .map(|age| age.as_secs());
+
+    Backlog {
+        count: sequences.len(),
+        oldest_sequence,
+        oldest_age_secs,
+    }
+}
+
+/// The time elapsed since the block of the first event returned by the given query, if any.
+fn event_age<Chain: ChainHandle>(
+    chain: &Chain,
+    query: QueryPacketEventDataRequest,
+) -> Option<Duration> {
+    let result = chain.query_packet_events(query).and_then(|events| {
+        events
+            .first()
+            .map(|event| {
+                chain.query_host_consensus_state(QueryHostConsensusStateRequest {
+                    height: QueryHeight::Specific(event.height),
+                })
+            })
+            .transpose()
+    });
+
+    match result {
+        Ok(consensus_state) => consensus_state
+            .and_then(|consensus_state| consensus_state.timestamp().into_tm_time())
+            .and_then(|time| Time::now().duration_since(time).ok()),
+        Err(e) => {
+            debug!(
+                "failed to query the age of a pending packet on {}: {}",
+                chain.id(),
+                e
+            );
+            None
+        }
+    }
+}
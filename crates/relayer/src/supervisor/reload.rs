@@ -0,0 +1,691 @@
+//! Reloading of the configuration of a running supervisor, without restarting it.
+//!
+//! The configuration file is loaded again and compared against the running configuration.
+//! The runtimes of the chains which were added, removed or whose configuration changed are
+//! spawned or shut down, and only the workers affected by the changes are restarted, so that
+//! the other chains keep relaying without going through a full scan and clear again.
+
+use alloc::collections::btree_map::BTreeMap as HashMap;
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+use core::fmt::{Display, Error as FmtError, Formatter};
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+
+use serde::{Deserialize, Serialize};
+use tendermint::block::Height as BlockHeight;
+use tracing::{error, info, warn};
+
+use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId};
+
+use crate::{
+    chain::handle::ChainHandle,
+    config::{self, filter::PacketFilter, ChainConfig, Config},
+    event::record::EventRecorder,
+    object::Object,
+    registry::SharedRegistry,
+    util::{lock::LockExt, task::TaskHandle},
+    worker::WorkerMap,
+};
+
+use super::{
+    client_state_filter::FilterPolicy,
+    scan::{ChainScanner, ChainsScan, ScanMode},
+    spawn::SpawnContext,
+    spawn_batch_worker, Error,
+};
+
+/// The differences between the running configuration and the reloaded one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigDiff {
+    /// Chains which were added to the configuration
+    pub added_chains: Vec<ChainId>,
+    /// Chains which were removed from the configuration
+    pub removed_chains: Vec<ChainId>,
+    /// Chains whose configuration changed, besides their packet filter,
+    /// and whose runtime must therefore be restarted
+    pub updated_chains: Vec<ChainId>,
+    /// Chains for which only the packet filter changed
+    pub updated_packet_filters: Vec<ChainId>,
+    /// Sections of the configuration which changed, but cannot be reloaded
+    pub ignored_sections: Vec<String>,
+}
+
+impl ConfigDiff {
+    pub fn new(old: &Config, new: &Config) -> Self {
+        let mut diff = Self::default();
+
+        for new_chain in &new.chains {
+            match old.find_chain(&new_chain.id) {
+                None => diff.added_chains.push(new_chain.id.clone()),
+                Some(old_chain) if same_chain_config(old_chain, new_chain) => {}
+                Some(old_chain) if only_packet_filter_changed(old_chain, new_chain) => {
+                    diff.updated_packet_filters.push(new_chain.id.clone())
+                }
+                Some(_) => diff.updated_chains.push(new_chain.id.clone()),
+            }
+        }
+
+        for old_chain in &old.chains {
+            if !new.has_chain(&old_chain.id) {
+                diff.removed_chains.push(old_chain.id.clone());
+            }
+        }
+
+        let sections = [
+            ("global", section_changed(&old.global, &new.global)),
+            ("mode", section_changed(&old.mode, &new.mode)),
+            ("rest", section_changed(&old.rest, &new.rest)),
+            ("telemetry", section_changed(&old.telemetry, &new.telemetry)),
+            ("store", section_changed(&old.store, &new.store)),
+            ("scan", section_changed(&old.scan, &new.scan)),
+            ("traces", section_changed(&old.traces, &new.traces)),
+            (
+                "accounting",
+                section_changed(&old.accounting, &new.accounting),
+            ),
+            ("audit", section_changed(&old.audit, &new.audit)),
+            (
+                "notifications",
+                section_changed(&old.notifications, &new.notifications),
+            ),
+        ];
+
+        diff.ignored_sections = sections
+            .into_iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        diff
+    }
+
+    /// Whether the chains of the configuration are unchanged.
+    pub fn chains_unchanged(&self) -> bool {
+        self.added_chains.is_empty()
+            && self.removed_chains.is_empty()
+            && self.updated_chains.is_empty()
+            && self.updated_packet_filters.is_empty()
+    }
+}
+
+impl Display for ConfigDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        if self.chains_unchanged() {
+            return write!(f, "no chain changed");
+        }
+
+        let lists = [
+            ("added chains", &self.added_chains),
+            ("removed chains", &self.removed_chains),
+            ("updated chains", &self.updated_chains),
+            ("updated packet filters", &self.updated_packet_filters),
+        ];
+
+        let parts = lists
+            .iter()
+            .filter(|(_, chains)| !chains.is_empty())
+            .map(|(name, chains)| {
+                let chains = chains.iter().map(ToString::to_string).collect::<Vec<_>>();
+                format!("{name}: {}", chains.join(", "))
+            })
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// Whether the two configurations of a chain are the same, besides the height from which
+/// their event source starts collecting events, which is not read from the configuration
+/// file but set on start.
+fn same_chain_config(old: &ChainConfig, new: &ChainConfig) -> bool {
+    let without_start_height = |chain: &ChainConfig| {
+        let mut chain = chain.clone();
+        if let Some(start_height) = chain.event_source.start_height_mut() {
+            *start_height = None;
+        }
+        chain
+    };
+
+    without_start_height(old) == without_start_height(new)
+}
+
+/// Whether the two configurations of a chain only differ by their packet filter.
+fn only_packet_filter_changed(old: &ChainConfig, new: &ChainConfig) -> bool {
+    let mut old = old.clone();
+    old.packet_filter = new.packet_filter.clone();
+    same_chain_config(&old, new)
+}
+
+/// Whether a section of the configuration changed, compared through its serialized form,
+/// as not every section implements `PartialEq`.
+fn section_changed<T: Serialize>(old: &T, new: &T) -> bool {
+    serde_json::to_value(old).ok() != serde_json::to_value(new).ok()
+}
+
+/// Whether the worker for the given object must be restarted after the packet filter
+/// of the given chain changed, ie. whether the worker relays on a channel of that chain
+/// which is no longer allowed, or whose fee filter changed.
+fn affected_by_packet_filter_update(
+    object: &Object,
+    chain_id: &ChainId,
+    old: &PacketFilter,
+    new: &PacketFilter,
+) -> bool {
+    let (port_id, channel_id) = match object {
+        Object::Packet(p) if &p.src_chain_id == chain_id => (&p.src_port_id, &p.src_channel_id),
+        Object::Channel(c) if &c.src_chain_id == chain_id => (&c.src_port_id, &c.src_channel_id),
+        _ => return false,
+    };
+
+    let allowed = |filter: &PacketFilter| filter.channel_policy.is_allowed(port_id, channel_id);
+
+    allowed(old) != allowed(new) || min_fees(old, channel_id) != min_fees(new, channel_id)
+}
+
+/// The fee filter applying to packets on the given channel, as looked up by the packet workers.
+fn min_fees<'a>(
+    filter: &'a PacketFilter,
+    channel_id: &ChannelId,
+) -> Option<&'a config::filter::FeePolicy> {
+    filter
+        .min_fees
+        .iter()
+        .find(|(channel, _)| channel.matches(channel_id))
+        .map(|(_, policy)| policy)
+}
+
+/// Reloads the configuration of the supervisor, and owns the tasks
+/// processing the event batches of every chain, since those have to be
+/// spawned or shut down when chains are added or removed.
+pub struct ConfigReloader<Chain: ChainHandle> {
+    config_path: Option<PathBuf>,
+    scan: bool,
+    config: Arc<RwLock<Config>>,
+    registry: SharedRegistry<Chain>,
+    client_state_filter: Arc<RwLock<FilterPolicy>>,
+    workers: Arc<RwLock<WorkerMap>>,
+    recorder: Option<Arc<EventRecorder>>,
+    batch_tasks: Mutex<HashMap<ChainId, TaskHandle>>,
+}
+
+impl<Chain: ChainHandle> ConfigReloader<Chain> {
+    pub(super) fn new(
+        config_path: Option<PathBuf>,
+        scan: bool,
+        config: Arc<RwLock<Config>>,
+        registry: SharedRegistry<Chain>,
+        client_state_filter: Arc<RwLock<FilterPolicy>>,
+        workers: Arc<RwLock<WorkerMap>>,
+        recorder: Option<Arc<EventRecorder>>,
+    ) -> Self {
+        Self {
+            config_path,
+            scan,
+            config,
+            registry,
+            client_state_filter,
+            workers,
+            recorder,
+            batch_tasks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Spawn the task processing the event batches received from the given chain.
+    pub(super) fn spawn_batch_worker(&self, chain: Chain, subscription: super::Subscription) {
+        let chain_id = chain.id();
+
+        let task = spawn_batch_worker(
+            self.config.clone(),
+            self.registry.clone(),
+            self.client_state_filter.clone(),
+            self.workers.clone(),
+            chain,
+            subscription,
+            self.recorder.clone(),
+        );
+
+        self.batch_tasks.lock().unwrap().insert(chain_id, task);
+    }
+
+    /// Load the configuration file again, and apply the changes made to its chains.
+    pub fn reload(&self) -> Result<ConfigDiff, Error> {
+        let path = self
+            .config_path
+            .as_ref()
+            .ok_or_else(Error::no_config_path)?;
+
+        let new_config = config::load(path).map_err(|e| Error::config_load(path.clone(), e))?;
+
+        let mut chain_ids = BTreeSet::new();
+        for chain_config in &new_config.chains {
+            if !chain_ids.insert(&chain_config.id) {
+                return Err(Error::duplicate_chain(chain_config.id.clone()));
+            }
+        }
+
+        let old_config = self.config.acquire_read().clone();
+        let diff = ConfigDiff::new(&old_config, &new_config);
+
+        for section in &diff.ignored_sections {
+            warn!(
+                "changes to the [{}] section of the configuration cannot be reloaded, \
+                 restart Hermes to apply them",
+                section
+            );
+        }
+
+        if diff.chains_unchanged() {
+            return Ok(diff);
+        }
+
+        // Only the chains are reloaded, the other sections are kept as they are
+        let mut config = Config {
+            chains: new_config.chains,
+            ..old_config.clone()
+        };
+
+        self.set_start_heights(&mut config, &old_config, &diff);
+
+        let rescan = self.chains_to_rescan(&diff);
+
+        // Stop processing the events of the chains whose runtime is shut down.
+        // This must be done before taking the locks below, which the batch workers take as well.
+        let stopped_tasks = {
+            let mut batch_tasks = self.batch_tasks.lock().unwrap();
+
+            diff.removed_chains
+                .iter()
+                .chain(&diff.updated_chains)
+                .filter_map(|chain_id| batch_tasks.remove(chain_id))
+                .collect::<Vec<_>>()
+        };
+
+        for task in stopped_tasks {
+            task.shutdown_and_wait();
+        }
+
+        // Spawn the runtimes of the added and updated chains and scan the chains on a fork
+        // of the registry, without holding the locks on the registry and the workers,
+        // which the workers of the other chains and the REST API take meanwhile.
+        let mut registry = self.registry.read().fork();
+        registry.update_config(config.clone());
+
+        for chain_id in diff.removed_chains.iter().chain(&diff.updated_chains) {
+            registry.forget(chain_id);
+        }
+
+        let mut subscriptions = Vec::new();
+
+        for chain_id in diff.added_chains.iter().chain(&diff.updated_chains) {
+            let chain = match registry.get_or_spawn(chain_id) {
+                Ok(chain) => chain,
+                Err(e) => {
+                    error!("failed to spawn chain runtime for {}: {}", chain_id, e);
+                    continue;
+                }
+            };
+
+            info!(chain = %chain_id, "spawned chain runtime");
+
+            match chain.subscribe() {
+                Ok(subscription) => subscriptions.push((chain, subscription)),
+                Err(e) => error!("failed to subscribe to events of {}: {}", chain_id, e),
+            }
+        }
+
+        // The permissions cached by the client filter may no longer hold
+        let mut client_state_filter = FilterPolicy::default();
+
+        let scan = self.scan.then(|| {
+            let mut scanner = ChainScanner::new(
+                &config,
+                &mut registry,
+                &mut client_state_filter,
+                ScanMode::Auto,
+            );
+
+            let scan = ChainsScan {
+                chains: rescan
+                    .iter()
+                    .filter_map(|chain_id| config.find_chain(chain_id))
+                    .map(|chain_config| scanner.scan_chain(chain_config))
+                    .collect(),
+            };
+
+            info!("scanned chains:");
+            info!("{}", scan);
+
+            scan
+        });
+
+        // Only swap the chain runtimes, workers and configuration while holding the locks
+        {
+            let mut current_config = self.config.acquire_write();
+            let mut shared_registry = self.registry.write();
+            let mut shared_client_state_filter = self.client_state_filter.acquire_write();
+            let mut workers = self.workers.acquire_write();
+
+            for chain_id in diff.removed_chains.iter().chain(&diff.updated_chains) {
+                for object in workers.objects_for_chain(chain_id) {
+                    workers.shutdown_worker(&object);
+                }
+
+                shared_registry.shutdown(chain_id);
+
+                info!(chain = %chain_id, "shut down chain runtime");
+            }
+
+            for chain_id in &diff.updated_packet_filters {
+                let (Some(old_chain), Some(new_chain)) =
+                    (old_config.find_chain(chain_id), config.find_chain(chain_id))
+                else {
+                    continue;
+                };
+
+                for object in workers.objects_for_chain(chain_id) {
+                    if affected_by_packet_filter_update(
+                        &object,
+                        chain_id,
+                        &old_chain.packet_filter,
+                        &new_chain.packet_filter,
+                    ) {
+                        info!(
+                            "restarting worker for {} after its packet filter changed",
+                            object.short_name()
+                        );
+
+                        workers.shutdown_worker(&object);
+                    }
+                }
+            }
+
+            shared_registry.merge(registry);
+            shared_registry.update_config(config.clone());
+
+            *shared_client_state_filter = client_state_filter;
+            *current_config = config.clone();
+
+            if let Some(scan) = scan {
+                SpawnContext::new(&config, &mut shared_registry, &mut workers).spawn_workers(scan);
+            }
+        }
+
+        for (chain, subscription) in subscriptions {
+            self.spawn_batch_worker(chain, subscription);
+        }
+
+        Ok(diff)
+    }
+
+    /// Sets the height from which the event sources of the reloaded chains start collecting
+    /// events: the chains whose runtime keeps running keep their start height, while the
+    /// runtimes spawned for the added and updated chains resume from the height following
+    /// the last height processed for that chain, as recorded in the state store, if enabled.
+    fn set_start_heights(&self, config: &mut Config, old_config: &Config, diff: &ConfigDiff) {
+        let state_store = self.workers.acquire_read().state_store().cloned();
+
+        for chain_config in config.chains.iter_mut() {
+            let respawned = diff.added_chains.contains(&chain_config.id)
+                || diff.updated_chains.contains(&chain_config.id);
+
+            let start_height = if respawned {
+                state_store
+                    .as_ref()
+                    .and_then(|store| store.processed_height(&chain_config.id))
+                    .and_then(|height| BlockHeight::try_from(height.revision_height() + 1).ok())
+            } else {
+                old_config
+                    .find_chain(&chain_config.id)
+                    .and_then(|old_chain| old_chain.event_source.start_height())
+            };
+
+            if let Some(height) = chain_config.event_source.start_height_mut() {
+                *height = start_height;
+            }
+        }
+    }
+
+    /// The chains for which the workers must be spawned again after applying the given changes,
+    /// ie. the chains which changed and the counterparties of the chains which were removed
+    /// or updated, whose workers are shut down.
+    fn chains_to_rescan(&self, diff: &ConfigDiff) -> BTreeSet<ChainId> {
+        let workers = self.workers.acquire_read();
+
+        let mut rescan = BTreeSet::new();
+
+        for chain_id in diff.removed_chains.iter().chain(&diff.updated_chains) {
+            for object in workers.objects_for_chain(chain_id) {
+                rescan.insert(object.src_chain_id().clone());
+                rescan.insert(object.dst_chain_id().clone());
+            }
+        }
+
+        rescan.extend(
+            diff.added_chains
+                .iter()
+                .chain(&diff.updated_chains)
+                .chain(&diff.updated_packet_filters)
+                .cloned(),
+        );
+
+        rescan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc_relayer_types::core::ics24_host::identifier::PortId;
+
+    use crate::config::filter::{ChannelFilters, ChannelPolicy, FilterPattern};
+    use crate::object::Packet;
+
+    fn config() -> Config {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/config/fixtures/relayer_conf_example.toml"
+        );
+
+        config::load(path).expect("could not parse config")
+    }
+
+    fn allow(channel: &str) -> PacketFilter {
+        PacketFilter::allow(vec![(
+            FilterPattern::Exact(PortId::transfer()),
+            FilterPattern::Exact(channel.parse().unwrap()),
+        )])
+    }
+
+    #[test]
+    fn diff_unchanged() {
+        let diff = ConfigDiff::new(&config(), &config());
+
+        assert!(diff.chains_unchanged());
+        assert!(diff.ignored_sections.is_empty());
+    }
+
+    #[test]
+    fn diff_ignores_start_heights() {
+        let chain_a = ChainId::from_string("chain_A");
+
+        let pull = |start_height: Option<u64>| config::EventSourceMode::Pull {
+            interval: core::time::Duration::from_secs(1),
+            start_height: start_height.map(|height| BlockHeight::try_from(height).unwrap()),
+        };
+
+        // The running configuration holds the height set on start,
+        // which the reloaded configuration file does not
+        let mut old = config();
+        old.find_chain_mut(&chain_a).unwrap().event_source = pull(Some(42));
+
+        let mut new = config();
+        new.find_chain_mut(&chain_a).unwrap().event_source = pull(None);
+
+        let diff = ConfigDiff::new(&old, &new);
+
+        assert!(diff.chains_unchanged());
+        assert!(diff.updated_chains.is_empty());
+        assert!(diff.updated_packet_filters.is_empty());
+
+        new.find_chain_mut(&chain_a).unwrap().packet_filter = allow("channel-42");
+
+        let diff = ConfigDiff::new(&old, &new);
+
+        assert!(diff.updated_chains.is_empty());
+        assert_eq!(diff.updated_packet_filters, vec![chain_a]);
+    }
+
+    #[test]
+    fn reload_unchanged_config_with_start_height() {
+        use std::fs;
+
+        use crate::chain::handle::BaseChainHandle;
+
+        let chain_a = ChainId::from_string("chain_A");
+
+        let fixture = fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/config/fixtures/relayer_conf_example.toml"
+        ))
+        .unwrap()
+        .replace(
+            "event_source = { mode = 'push', url = 'ws://localhost:26657/websocket', batch_delay = '500ms' }",
+            "event_source = { mode = 'pull', interval = '1s' }",
+        );
+
+        let dir = std::env::temp_dir().join(format!("hermes-reload-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, fixture).unwrap();
+
+        let mut config = config::load(&path).unwrap();
+        let start_height = BlockHeight::try_from(42_u64).unwrap();
+        *config
+            .find_chain_mut(&chain_a)
+            .unwrap()
+            .event_source
+            .start_height_mut()
+            .unwrap() = Some(start_height);
+
+        let shared_config = Arc::new(RwLock::new(config.clone()));
+
+        let reloader = ConfigReloader::<BaseChainHandle>::new(
+            Some(path),
+            true,
+            shared_config.clone(),
+            SharedRegistry::new(config),
+            Arc::new(RwLock::new(FilterPolicy::default())),
+            Arc::new(RwLock::new(WorkerMap::new())),
+            None,
+        );
+
+        let diff = reloader.reload().unwrap();
+
+        assert!(diff.chains_unchanged());
+        assert!(diff.updated_chains.is_empty());
+        assert!(diff.updated_packet_filters.is_empty());
+        assert_eq!(reloader.registry.read().size(), 0);
+
+        // The running chain keeps the height from which its event source started
+        let config = shared_config.acquire_read();
+        let chain_config = config.find_chain(&chain_a).unwrap();
+        assert_eq!(chain_config.event_source.start_height(), Some(start_height));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn diff_chains() {
+        let old = config();
+        let mut new = config();
+
+        let chain_a = ChainId::from_string("chain_A");
+        let chain_b = ChainId::from_string("chain_B");
+        let chain_c = ChainId::from_string("chain_C");
+
+        new.chains.retain(|chain| chain.id != chain_b);
+
+        let mut added = old.chains[0].clone();
+        added.id = chain_c.clone();
+        new.chains.push(added);
+
+        new.find_chain_mut(&chain_a).unwrap().packet_filter = allow("channel-42");
+        new.mode.packets.clear_interval += 1;
+
+        let diff = ConfigDiff::new(&old, &new);
+
+        assert_eq!(diff.added_chains, vec![chain_c]);
+        assert_eq!(diff.removed_chains, vec![chain_b]);
+        assert!(diff.updated_chains.is_empty());
+        assert_eq!(diff.updated_packet_filters, vec![chain_a.clone()]);
+        assert_eq!(diff.ignored_sections, vec!["mode".to_string()]);
+
+        new.find_chain_mut(&chain_a).unwrap().rpc_timeout += core::time::Duration::from_secs(1);
+
+        let diff = ConfigDiff::new(&old, &new);
+
+        assert_eq!(diff.updated_chains, vec![chain_a]);
+        assert!(diff.updated_packet_filters.is_empty());
+    }
+
+    #[test]
+    fn packet_filter_update_affects_channel_workers() {
+        let chain_a = ChainId::from_string("chain_A");
+        let chain_b = ChainId::from_string("chain_B");
+
+        let packet = |src_chain_id: &ChainId, dst_chain_id: &ChainId, channel: &str| {
+            Object::Packet(Packet {
+                dst_chain_id: dst_chain_id.clone(),
+                src_chain_id: src_chain_id.clone(),
+                src_channel_id: channel.parse().unwrap(),
+                src_port_id: PortId::transfer(),
+            })
+        };
+
+        let old = allow("channel-0");
+        let new = PacketFilter::new(
+            ChannelPolicy::Allow(ChannelFilters::new(vec![
+                (
+                    FilterPattern::Exact(PortId::transfer()),
+                    FilterPattern::Exact("channel-0".parse().unwrap()),
+                ),
+                (
+                    FilterPattern::Exact(PortId::transfer()),
+                    FilterPattern::Exact("channel-1".parse().unwrap()),
+                ),
+            ])),
+            Default::default(),
+        );
+
+        let affected = |object: &Object, old: &PacketFilter, new: &PacketFilter| {
+            affected_by_packet_filter_update(object, &chain_a, old, new)
+        };
+
+        // Still allowed
+        assert!(!affected(
+            &packet(&chain_a, &chain_b, "channel-0"),
+            &old,
+            &new
+        ));
+        // Newly allowed
+        assert!(affected(
+            &packet(&chain_a, &chain_b, "channel-1"),
+            &old,
+            &new
+        ));
+        // No longer allowed
+        assert!(affected(
+            &packet(&chain_a, &chain_b, "channel-1"),
+            &new,
+            &old
+        ));
+        // Relaying from the counterparty chain, whose filter is unchanged
+        assert!(!affected(
+            &packet(&chain_b, &chain_a, "channel-1"),
+            &old,
+            &new
+        ));
+    }
+}
@@ -1,5 +1,7 @@
+use alloc::sync::Arc;
 use core::fmt::{Display, Error as FmtError, Formatter};
 use std::collections::BTreeMap;
+use std::thread;
 
 use itertools::Itertools;
 use tracing::{debug, error, error_span, info, warn};
@@ -32,6 +34,7 @@ use crate::{
     path::PathIdentifiers,
     registry::Registry,
     supervisor::client_state_filter::{FilterPolicy, Permission},
+    util::rate_limit::RateLimiter,
 };
 
 use crate::chain::counterparty::{unreceived_acknowledgements, unreceived_packets};
@@ -261,6 +264,90 @@ impl ChannelScan {
     }
 }
 
+/// Limits the rate of the queries made to the chains while scanning them, per RPC endpoint,
+/// so that chains served by the same node share the limit set in the configuration.
+#[derive(Debug)]
+struct ScanRateLimiter {
+    limiter: RateLimiter<String>,
+    rpc_addresses: BTreeMap<ChainId, String>,
+}
+
+impl ScanRateLimiter {
+    fn new(config: &Config) -> Self {
+        Self {
+            limiter: RateLimiter::new(config.scan.max_queries_per_second),
+            rpc_addresses: config
+                .chains
+                .iter()
+                .map(|chain| (chain.id.clone(), chain.rpc_addr.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Block until a query can be made to the RPC endpoint of the given chain.
+    fn acquire(&self, chain_id: &ChainId) {
+        if let Some(rpc_address) = self.rpc_addresses.get(chain_id) {
+            self.limiter.acquire(rpc_address);
+        }
+    }
+}
+
+/// Processes the given items from as many threads as there are states, each thread
+/// processing items with its own state, and calls `on_done` on the current thread with
+/// the result for each item as soon as it is available, in the order the items complete.
+///
+/// Returns the states of the threads, once all the items have been processed.
+fn process_concurrently<T, S, R>(
+    items: &[T],
+    states: Vec<S>,
+    process: impl Fn(&mut S, &T) -> R + Sync,
+    mut on_done: impl FnMut(&T, R),
+) -> Vec<S>
+where
+    T: Sync,
+    S: Send,
+    R: Send,
+{
+    let (queue_tx, queue_rx) = crossbeam_channel::unbounded();
+    for item in items {
+        queue_tx.send(item).unwrap();
+    }
+    drop(queue_tx);
+
+    let (done_tx, done_rx) = crossbeam_channel::unbounded();
+
+    thread::scope(|s| {
+        let threads = states
+            .into_iter()
+            .map(|mut state| {
+                let queue_rx = queue_rx.clone();
+                let done_tx = done_tx.clone();
+                let process = &process;
+
+                s.spawn(move || {
+                    for item in queue_rx {
+                        let result = process(&mut state, item);
+                        let _ = done_tx.send((item, result));
+                    }
+
+                    state
+                })
+            })
+            .collect_vec();
+
+        drop(done_tx);
+
+        for (item, result) in done_rx {
+            on_done(item, result);
+        }
+
+        threads
+            .into_iter()
+            .map(|thread| thread.join().expect("scan thread panicked"))
+            .collect()
+    })
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ScanMode {
     Auto,
@@ -272,6 +359,7 @@ pub struct ChainScanner<'a, Chain: ChainHandle> {
     registry: &'a mut Registry<Chain>,
     client_state_filter: &'a mut FilterPolicy,
     scan_mode: ScanMode,
+    rate_limiter: Arc<ScanRateLimiter>,
 }
 
 impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
@@ -281,21 +369,97 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
         client_state_filter: &'a mut FilterPolicy,
         scan_mode: ScanMode,
     ) -> Self {
+        let rate_limiter = Arc::new(ScanRateLimiter::new(config));
+
         Self {
             config,
             registry,
             client_state_filter,
             scan_mode,
+            rate_limiter,
         }
     }
 
-    pub fn scan_chains(mut self) -> ChainsScan {
+    pub fn scan_chains(self) -> ChainsScan {
+        self.scan_chains_with(|_, _| {})
+    }
+
+    /// Scan the chains concurrently, up to the concurrency limit set in the configuration,
+    /// calling `on_scan` with the scan of each chain as soon as it is done, eg. to spawn
+    /// the workers for that chain without waiting for the scans of the other chains.
+    pub fn scan_chains_with(
+        self,
+        mut on_scan: impl FnMut(&mut Registry<Chain>, &ChainScan),
+    ) -> ChainsScan {
+        let chains = &self.config.chains;
+        let total = chains.len();
+
         let mut scans = ChainsScan {
-            chains: Vec::with_capacity(self.config.chains.len()),
+            chains: Vec::with_capacity(total),
         };
 
-        for chain in self.config.chains.clone() {
-            scans.chains.push(self.scan_chain(&chain));
+        if total == 0 {
+            return scans;
+        }
+
+        // Spawn the runtimes of all the chains upfront, so that they are
+        // shared by the scans, which each query their counterparty chains
+        for chain_config in chains {
+            if let Err(e) = self.registry.spawn(&chain_config.id) {
+                error!(chain = %chain_config.id, "failed to spawn chain runtime: {}", e);
+            }
+        }
+
+        let concurrency = self.config.scan.concurrency.clamp(1, total);
+
+        info!("scanning {} chains, {} at a time...", total, concurrency);
+
+        // Each thread scans with its own fork of the registry and client state filter,
+        // which are merged back once all the chains are scanned
+        let states = (0..concurrency)
+            .map(|_| (self.registry.fork(), FilterPolicy::default()))
+            .collect_vec();
+
+        let config = self.config;
+        let scan_mode = self.scan_mode;
+        let rate_limiter = &self.rate_limiter;
+        let registry = &mut *self.registry;
+
+        let mut done = 0;
+
+        let states = process_concurrently(
+            chains,
+            states,
+            |(registry, client_state_filter), chain_config| {
+                ChainScanner {
+                    config,
+                    registry,
+                    client_state_filter,
+                    scan_mode,
+                    rate_limiter: rate_limiter.clone(),
+                }
+                .scan_chain(chain_config)
+            },
+            |chain_config, scan| {
+                done += 1;
+
+                match &scan {
+                    Ok(chain_scan) => {
+                        info!(chain = %chain_config.id, "scanned chain ({}/{})", done, total);
+                        on_scan(registry, chain_scan);
+                    }
+                    Err(e) => {
+                        error!(chain = %chain_config.id, "failed to scan chain ({}/{}): {}", done, total, e);
+                    }
+                }
+
+                scans.chains.push(scan);
+            },
+        );
+
+        for (registry, client_state_filter) in states {
+            self.registry.merge(registry);
+            self.client_state_filter.merge(client_state_filter);
         }
 
         scans
@@ -350,7 +514,13 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
         info!("querying allowed channels...");
 
         for (port_id, channel_id) in filters.iter_exact() {
-            let result = scan_allowed_channel(self.registry, chain, port_id, channel_id);
+            let result = scan_allowed_channel(
+                self.registry,
+                &self.rate_limiter,
+                chain,
+                port_id,
+                channel_id,
+            );
 
             match result {
                 Ok(ScannedChannel {
@@ -401,6 +571,7 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
     pub fn scan_all_clients(&mut self, chain: &Chain, scan: &mut ChainScan) -> Result<(), Error> {
         info!("scanning all clients...");
 
+        self.rate_limiter.acquire(&chain.id());
         let clients = query_all_clients(chain)?;
 
         for client in clients {
@@ -466,7 +637,8 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
             return Ok(None);
         }
 
-        let client_connections_ids = query_client_connections(chain, &client.client_id)?;
+        let client_connections_ids =
+            query_client_connections(chain, &self.rate_limiter, &client.client_id)?;
 
         let mut scan = ClientScan::new(client);
 
@@ -522,6 +694,7 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
 
         scan.counterparty_state = Some(counterparty_state);
 
+        self.rate_limiter.acquire(&chain.id());
         let channels = match query_connection_channels(chain, scan.connection.id()) {
             Ok(channels) => channels,
             Err(e) => {
@@ -535,10 +708,14 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
             .get_or_spawn(&client.client_state.chain_id())
             .map_err(Error::spawn)?;
 
+        let rate_limiter = self.rate_limiter.clone();
+
         let channels = channels
             .into_iter()
             .filter(|channel| self.channel_allowed(chain, channel))
             .map(|channel| {
+                rate_limiter.acquire(&counterparty_chain.id());
+
                 let counterparty =
                     channel_on_destination(&channel, &scan.connection, &counterparty_chain)
                         .unwrap_or_default();
@@ -567,6 +744,8 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
             .get_or_spawn(&client.client_state.chain_id())
             .map_err(Error::spawn)?;
 
+        self.rate_limiter.acquire(&counterparty_chain.id());
+
         let counterparty_state = connection_state_on_destination(connection, &counterparty_chain)
             .map_err(|e| {
             Error::counterparty_connection_state(
@@ -670,6 +849,7 @@ struct ScannedChannel {
 
 fn scan_allowed_channel<Chain: ChainHandle>(
     registry: &'_ mut Registry<Chain>,
+    rate_limiter: &ScanRateLimiter,
     chain: &Chain,
     port_id: &PortId,
     channel_id: &ChannelId,
@@ -678,6 +858,7 @@ fn scan_allowed_channel<Chain: ChainHandle>(
     let _guard = span.enter();
 
     info!("querying channel...");
+    rate_limiter.acquire(&chain.id());
     let channel = query_channel(chain, port_id, channel_id)?;
 
     if channel
@@ -691,6 +872,7 @@ fn scan_allowed_channel<Chain: ChainHandle>(
         ));
     }
 
+    rate_limiter.acquire(&chain.id());
     let connection = query_connection_for_channel(chain, &channel)?;
     let client_id = connection.connection_end.client_id();
 
@@ -700,6 +882,7 @@ fn scan_allowed_channel<Chain: ChainHandle>(
     );
 
     info!(client = %client_id, "querying client...");
+    rate_limiter.acquire(&chain.id());
     let client = query_client(chain, client_id)?;
 
     let counterparty_chain_id = client.client_state.chain_id();
@@ -714,6 +897,7 @@ fn scan_allowed_channel<Chain: ChainHandle>(
         .get_or_spawn(&counterparty_chain_id)
         .map_err(Error::spawn)?;
 
+    rate_limiter.acquire(&counterparty_chain_id);
     let counterparty_channel =
         channel_on_destination(&channel, &connection, &counterparty_chain).unwrap_or_default();
 
@@ -727,6 +911,7 @@ fn scan_allowed_channel<Chain: ChainHandle>(
         "found counterparty channel"
     );
 
+    rate_limiter.acquire(&counterparty_chain_id);
     let counterparty_connection_state =
         connection_state_on_destination(&connection, &counterparty_chain)
             .map(Some)
@@ -823,8 +1008,11 @@ fn query_all_clients<Chain: ChainHandle>(
 
 fn query_client_connections<Chain: ChainHandle>(
     chain: &Chain,
+    rate_limiter: &ScanRateLimiter,
     client_id: &ClientId,
 ) -> Result<Vec<IdentifiedConnectionEnd>, Error> {
+    rate_limiter.acquire(&chain.id());
+
     let ids = chain
         .query_client_connections(QueryClientConnectionsRequest {
             client_id: client_id.clone(),
@@ -834,6 +1022,8 @@ fn query_client_connections<Chain: ChainHandle>(
     let connections = ids
         .into_iter()
         .filter_map(|id| {
+            rate_limiter.acquire(&chain.id());
+
             query_connection(chain, &id)
                 .map_err(|e| error!("failed to query connection: {}", e))
                 .ok()
@@ -951,3 +1141,105 @@ fn init_telemetry(
 
     telemetry!(init_worker_by_type, WorkerType::Wallet);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::time::Duration;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    #[test]
+    fn chains_served_by_the_same_node_share_the_rate_limit() {
+        let mut config = crate::config::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/config/fixtures/relayer_conf_example.toml"
+        ))
+        .unwrap();
+
+        config.chains[1].rpc_addr = config.chains[0].rpc_addr.clone();
+
+        let rate_limiter = ScanRateLimiter::new(&config);
+        let rpc_address = |chain: &ChainConfig| rate_limiter.rpc_addresses.get(&chain.id);
+
+        assert_eq!(
+            rpc_address(&config.chains[0]),
+            rpc_address(&config.chains[1])
+        );
+        assert_eq!(rate_limiter.rpc_addresses.len(), 2);
+    }
+
+    #[test]
+    fn every_item_is_processed_once() {
+        let items = (0..20).collect_vec();
+        let mut done = vec![];
+
+        let states = process_concurrently(
+            &items,
+            vec![0; 4],
+            |processed, item| {
+                *processed += 1;
+                item * 2
+            },
+            |item, result| {
+                assert_eq!(result, item * 2);
+                done.push(*item);
+            },
+        );
+
+        done.sort();
+        assert_eq!(done, items);
+        assert_eq!(states.len(), 4);
+        assert_eq!(states.iter().sum::<usize>(), items.len());
+    }
+
+    #[test]
+    fn concurrency_is_bounded_by_the_number_of_states() {
+        let items = (0..20).collect_vec();
+        let active = AtomicUsize::new(0);
+        let max_active = AtomicUsize::new(0);
+
+        process_concurrently(
+            &items,
+            vec![(); 3],
+            |_, _| {
+                let now_active = active.fetch_add(1, Ordering::SeqCst) + 1;
+                max_active.fetch_max(now_active, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(5));
+                active.fetch_sub(1, Ordering::SeqCst);
+            },
+            |_, _| {},
+        );
+
+        assert!(max_active.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn results_are_handled_as_soon_as_available() {
+        // The first item is only done once the result of the second one was handled,
+        // eg. once the workers for the second chain scanned are spawned
+        let (handled_tx, handled_rx) = crossbeam_channel::bounded(1);
+        let handled = Mutex::new(vec![]);
+
+        process_concurrently(
+            &[0, 1],
+            vec![(); 2],
+            |_, item| {
+                if *item == 0 {
+                    handled_rx.recv_timeout(Duration::from_secs(5)).expect(
+                        "result of the second item was not handled before the first item completed",
+                    );
+                }
+            },
+            |item, _| {
+                handled.lock().unwrap().push(*item);
+                if *item == 1 {
+                    handled_tx.send(()).unwrap();
+                }
+            },
+        );
+
+        assert_eq!(handled.into_inner().unwrap(), vec![1, 0]);
+    }
+}
@@ -35,7 +35,7 @@ macro_rules! telemetry {
         #[cfg(feature = "telemetry")]
         #[allow(unused_imports, unused_variables)]
         {
-            use ::ibc_telemetry::state::WorkerType;
+            use ::ibc_telemetry::state::{ClientStatus, WorkerType};
 
             ::ibc_telemetry::global().$id($($args),*);
         }
@@ -45,7 +45,7 @@ macro_rules! telemetry {
         #[cfg(feature = "telemetry")]
         #[allow(unused_imports, unused_variables)]
         {
-            use ::ibc_telemetry::state::WorkerType;
+            use ::ibc_telemetry::state::{ClientStatus, WorkerType};
 
             $e;
         }
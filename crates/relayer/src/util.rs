@@ -1,6 +1,7 @@
 mod block_on;
 pub use block_on::{block_on, spawn_blocking};
 
+pub mod append_log;
 pub mod collate;
 pub mod debug_section;
 pub mod diff;
@@ -9,6 +10,7 @@ pub mod lock;
 pub mod pretty;
 pub mod profiling;
 pub mod queue;
+pub mod rate_limit;
 pub mod retry;
 pub mod stream;
 pub mod task;
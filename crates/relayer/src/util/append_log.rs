@@ -0,0 +1,195 @@
+//! Append-only logs of JSON records, one per line, such as the accounting ledger
+//! and the packet audit log, along with the global sinks which the relayer
+//! opens once on start and then records to from any thread.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flex_error::{define_error, TraceError};
+use once_cell::sync::OnceCell;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+define_error! {
+    Error {
+        Io
+            { path: PathBuf }
+            [ TraceError<io::Error> ]
+            |e| { format!("I/O error on {}", e.path.display()) },
+
+        Encode
+            { path: PathBuf }
+            [ TraceError<serde_json::Error> ]
+            |e| { format!("failed to encode record for {}", e.path.display()) },
+
+        Decode
+            { path: PathBuf, line: usize }
+            [ TraceError<serde_json::Error> ]
+            |e| { format!("failed to decode record at line {} of {}", e.line, e.path.display()) },
+    }
+}
+
+/// An append-only file to which records are written, one per line.
+#[derive(Debug)]
+pub struct AppendLog {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl AppendLog {
+    /// Opens the log at the given path for appending, creating it if needed.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| Error::io(dir.to_path_buf(), e))?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| Error::io(path.to_path_buf(), e))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+        })
+    }
+
+    /// The path of the log.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends the given records to the log.
+    pub fn append<T: Serialize>(&self, records: &[T]) -> Result<(), Error> {
+        let mut lines = String::new();
+
+        for record in records {
+            let line =
+                serde_json::to_string(record).map_err(|e| Error::encode(self.path.clone(), e))?;
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+
+        // Write the records at once, so that they are not interleaved
+        // with the records appended concurrently
+        self.file
+            .lock()
+            .unwrap()
+            .write_all(lines.as_bytes())
+            .map_err(|e| Error::io(self.path.clone(), e))
+    }
+}
+
+/// Reads the records written to the log at the given path, in the order they were written.
+pub fn read_log<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>, Error> {
+    let file = File::open(path).map_err(|e| Error::io(path.to_path_buf(), e))?;
+
+    let mut records = Vec::new();
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| Error::io(path.to_path_buf(), e))?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = serde_json::from_str::<T>(&line)
+            .map_err(|e| Error::decode(path.to_path_buf(), index + 1, e))?;
+
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Seconds since the Unix epoch, at which records are timestamped.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// A sink, such as a log, which is opened at most once per process if enabled,
+/// and then shared by all the components recording to it.
+#[derive(Debug)]
+pub struct GlobalSink<T>(OnceCell<T>);
+
+impl<T> GlobalSink<T> {
+    pub const fn new() -> Self {
+        Self(OnceCell::new())
+    }
+
+    /// Opens the sink with the given function, unless it is open already.
+    /// Concurrent calls wait for the sink to be opened by one of them.
+    pub fn init<E>(&self, open: impl FnOnce() -> Result<T, E>) -> Result<(), E> {
+        self.0.get_or_try_init(open).map(|_| ())
+    }
+
+    /// The sink, if open.
+    pub fn get(&self) -> Option<&T> {
+        self.0.get()
+    }
+}
+
+impl<T> Default for GlobalSink<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Record {
+        time: u64,
+        name: String,
+    }
+
+    #[test]
+    fn append_and_read_log() {
+        let dir = std::env::temp_dir().join(format!("hermes-append-log-{}", std::process::id()));
+        let path = dir.join("records.jsonl");
+
+        let record = |time, name: &str| Record {
+            time,
+            name: name.to_string(),
+        };
+
+        let log = AppendLog::open(&path).unwrap();
+        log.append(&[record(1, "a"), record(2, "b")]).unwrap();
+        log.append(&[record(3, "c")]).unwrap();
+
+        assert_eq!(
+            read_log::<Record>(&path).unwrap(),
+            vec![record(1, "a"), record(2, "b"), record(3, "c")]
+        );
+
+        fs::write(&path, "{\"time\":1,\"name\":\"a\"}\n\nnot json\n").unwrap();
+        let e = read_log::<Record>(&path).unwrap_err();
+        assert!(e.to_string().contains("line 3"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn global_sink_is_opened_once() {
+        let sink = GlobalSink::new();
+
+        assert!(sink.get().is_none());
+        assert!(sink.init(|| Err::<u32, _>("unavailable")).is_err());
+        assert!(sink.get().is_none());
+
+        sink.init(|| Ok::<_, ()>(1)).unwrap();
+        sink.init(|| Ok::<_, ()>(2)).unwrap();
+        assert_eq!(sink.get(), Some(&1));
+    }
+}
@@ -0,0 +1,86 @@
+use alloc::collections::BTreeMap as HashMap;
+use core::time::Duration;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+/// Limits the rate at which requests are made to each of a set of endpoints,
+/// identified by a key, by spacing out the requests made to the same endpoint.
+///
+/// The limiter can be shared between threads, in which case the limit
+/// applies to the requests made by all the threads together.
+#[derive(Debug)]
+pub struct RateLimiter<K> {
+    interval: Option<Duration>,
+    next_slots: Mutex<HashMap<K, Instant>>,
+}
+
+impl<K: Ord + Clone> RateLimiter<K> {
+    /// Create a limiter allowing at most the given number of requests
+    /// per second to each endpoint, or any number of requests if zero.
+    pub fn new(max_per_second: u32) -> Self {
+        let interval = (max_per_second > 0).then(|| Duration::from_secs(1) / max_per_second);
+
+        Self {
+            interval,
+            next_slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Block until a request can be made to the endpoint with the given key,
+    /// and reserve the slot for that request.
+    pub fn acquire(&self, key: &K) {
+        if let Some(delay) = self.reserve(key, Instant::now()) {
+            thread::sleep(delay);
+        }
+    }
+
+    /// Reserve the next slot for a request to the endpoint with the given key,
+    /// returning how long to wait from `now` until that slot.
+    fn reserve(&self, key: &K, now: Instant) -> Option<Duration> {
+        let interval = self.interval?;
+
+        let mut next_slots = self.next_slots.lock().unwrap();
+
+        let slot = match next_slots.get(key) {
+            Some(next_slot) if *next_slot > now => *next_slot,
+            _ => now,
+        };
+
+        next_slots.insert(key.clone(), slot + interval);
+
+        (slot > now).then(|| slot - now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited() {
+        let limiter = RateLimiter::new(0);
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            assert_eq!(limiter.reserve(&"a", now), None);
+        }
+    }
+
+    #[test]
+    fn requests_are_spaced_out_per_key() {
+        let limiter = RateLimiter::new(10);
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve(&"a", now), None);
+        assert_eq!(limiter.reserve(&"a", now), Some(Duration::from_millis(100)));
+        assert_eq!(limiter.reserve(&"a", now), Some(Duration::from_millis(200)));
+
+        // Other endpoints are not affected
+        assert_eq!(limiter.reserve(&"b", now), None);
+
+        // Once the reserved slots have passed, requests are not delayed anymore
+        let later = now + Duration::from_secs(1);
+        assert_eq!(limiter.reserve(&"a", later), None);
+    }
+}
@@ -8,6 +8,9 @@ use tracing::{debug, error, warn};
 
 use crate::util::lock::LockExt;
 
+/// How often a paused task checks whether it has been resumed or shut down.
+const PAUSED_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 /**
    A task handle holds the endpoints for stopping or waiting for a
    background task to terminate.
@@ -22,6 +25,7 @@ use crate::util::lock::LockExt;
 pub struct TaskHandle {
     shutdown_sender: Sender<()>,
     stopped: Arc<RwLock<bool>>,
+    paused: Arc<RwLock<bool>>,
     join_handle: DropJoinHandle,
 }
 
@@ -76,7 +80,9 @@ pub enum Next {
    The function returns a [`TaskHandle`] that can be used to shutdown the
    background task. If the [`TaskHandle`] is dropped or if explicit shutdown
    instruction is sent, the task runner will stop calling the step runner
-   and abort the background task.
+   and abort the background task. The [`TaskHandle`] can also be used to
+   [`pause`](TaskHandle::pause) the task, in which case the step runner
+   is not called until the task is [`resume`](TaskHandle::resume)d.
 
    If the step runner is receiving commands from other
    [channels](crossbeam_channel::Receiver), it should use the
@@ -95,6 +101,9 @@ pub fn spawn_background_task<E: Display>(
     let stopped = Arc::new(RwLock::new(false));
     let write_stopped = stopped.clone();
 
+    let paused = Arc::new(RwLock::new(false));
+    let read_paused = paused.clone();
+
     let (shutdown_sender, receiver) = bounded(1);
 
     let join_handle = thread::spawn(move || {
@@ -104,6 +113,10 @@ pub fn spawn_background_task<E: Display>(
                 Ok(()) => {
                     break;
                 }
+                _ if *read_paused.acquire_read() => {
+                    thread::sleep(PAUSED_POLL_INTERVAL);
+                    continue;
+                }
                 _ => match step_runner() {
                     Ok(Next::Continue) => {}
                     Ok(Next::Abort) => {
@@ -132,6 +145,7 @@ pub fn spawn_background_task<E: Display>(
     TaskHandle {
         shutdown_sender,
         stopped,
+        paused,
         join_handle: DropJoinHandle(Some(join_handle)),
     }
 }
@@ -179,6 +193,28 @@ impl TaskHandle {
     pub fn is_stopped(&self) -> bool {
         *self.stopped.acquire_read()
     }
+
+    /**
+       Stop calling the step runner of the background task, until the task
+       is resumed. The step currently being executed, if any, is completed.
+    */
+    pub fn pause(&self) {
+        *self.paused.acquire_write() = true;
+    }
+
+    /**
+       Resume calling the step runner of a paused background task.
+    */
+    pub fn resume(&self) {
+        *self.paused.acquire_write() = false;
+    }
+
+    /**
+       Check whether a background task is paused.
+    */
+    pub fn is_paused(&self) -> bool {
+        *self.paused.acquire_read()
+    }
 }
 
 impl Drop for DropJoinHandle {
@@ -7,9 +7,11 @@ use tracing::error;
 
 use crate::foreign_client::ForeignClient;
 use crate::link::{Link, LinkParameters, Resubmit};
+use crate::store::SharedStateStore;
+use crate::worker::wallet::{BalanceThresholds, CriticalWallets};
 use crate::{
     chain::handle::{ChainHandle, ChainHandlePair},
-    config::Config,
+    config::{Config, EventSourceMode},
     object::Object,
 };
 
@@ -59,9 +61,13 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
     id: WorkerId,
     object: Object,
     config: &Config,
+    critical_wallets: &CriticalWallets,
+    state_store: Option<&SharedStateStore>,
 ) -> WorkerHandle {
     let mut task_handles = Vec::new();
 
+    let mut activity = None;
+
     let (cmd_tx, data) = match &object {
         Object::Client(client) => {
             let client = ForeignClient::restore(client.dst_client_id.clone(), chains.b, chains.a);
@@ -123,18 +129,34 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
             );
 
             match link_res {
-                Ok(link) => {
+                Ok(mut link) => {
+                    let src_chain_config =
+                        config.chains.iter().find(|chain| chain.id == chains.a.id());
+
+                    // When the state of the path is restored and the events of the source chain
+                    // since the processed height are replayed, the path is up to date without a
+                    // full clearing of its pending packets.
+                    let restored = state_store.map_or(false, |state_store| {
+                        link.a_to_b.restore_state(state_store.clone())
+                            && state_store.processed_height(&chains.a.id()).is_some()
+                            && src_chain_config.map_or(false, |chain_config| {
+                                matches!(
+                                    chain_config.event_source,
+                                    EventSourceMode::Pull { .. } | EventSourceMode::Hybrid { .. }
+                                )
+                            })
+                    });
+
+                    activity = Some(link.a_to_b.activity());
+
                     let channel_ordering = link.a_to_b.channel().ordering;
-                    let should_clear_on_start =
-                        packets_config.clear_on_start || channel_ordering == Ordering::Ordered;
+                    let should_clear_on_start = (packets_config.clear_on_start && !restored)
+                        || channel_ordering == Ordering::Ordered;
 
                     let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded();
                     let link = Arc::new(Mutex::new(link));
                     let resubmit = Resubmit::from_clear_interval(packets_config.clear_interval);
 
-                    let src_chain_config =
-                        config.chains.iter().find(|chain| chain.id == chains.a.id());
-
                     let fee_filter = match src_chain_config {
                         Some(chain_config) => chain_config
                             .packet_filter
@@ -163,6 +185,7 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
                             should_clear_on_start,
                             packets_config.clear_interval,
                             path.clone(),
+                            critical_wallets.clone(),
                         ),
                     };
                     task_handles.push(packet_task);
@@ -182,7 +205,13 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
         Object::Wallet(wallet) => {
             assert_eq!(wallet.chain_id, chains.a.id());
 
-            let wallet_task = wallet::spawn_wallet_worker(chains.a);
+            let thresholds = config
+                .find_chain(&wallet.chain_id)
+                .map(BalanceThresholds::from_config)
+                .unwrap_or_default();
+
+            let wallet_task =
+                wallet::spawn_wallet_worker(chains.a, thresholds, critical_wallets.clone());
             task_handles.push(wallet_task);
 
             (None, None)
@@ -202,5 +231,5 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
         }
     };
 
-    WorkerHandle::new(id, object, data, cmd_tx, task_handles)
+    WorkerHandle::new(id, object, data, cmd_tx, task_handles, activity)
 }
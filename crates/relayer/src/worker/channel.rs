@@ -41,7 +41,7 @@ pub fn spawn_channel_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
             let max_block_times = max_block_times(&chains);
             if let Ok(cmd) = cmd_rx.try_recv() {
                 match cmd {
-                    WorkerCmd::IbcEvents { batch } => {
+                    WorkerCmd::IbcEvents { batch, .. } => {
                         // there can be up to two event for this channel, e.g. init and try.
                         // process the last event, the one with highest "rank".
                         let last_event = batch.events.last();
@@ -105,7 +105,7 @@ pub fn detect_misbehavior_task<ChainA: ChainHandle, ChainB: ChainHandle>(
                 debug!("misbehavior detection result: {:?}", result);
             }
 
-            if let Ok(WorkerCmd::IbcEvents { batch }) = receiver.try_recv() {
+            if let Ok(WorkerCmd::IbcEvents { batch, .. }) = receiver.try_recv() {
                 trace!("received batch: {:?}", batch);
 
                 for event_with_height in batch.events {
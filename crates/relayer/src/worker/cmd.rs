@@ -1,14 +1,20 @@
+use alloc::sync::Arc;
 use core::fmt::{Display, Error as FmtError, Formatter};
 
 use ibc_relayer_types::{core::ics02_client::events::NewBlock, Height};
 
 use crate::event::source::EventBatch;
+use crate::store::ProcessedHeight;
 
 /// A command for a [`WorkerHandle`](crate::worker::WorkerHandle).
 #[derive(Debug, Clone)]
 pub enum WorkerCmd {
     /// A batch of packet events need to be relayed
-    IbcEvents { batch: EventBatch },
+    IbcEvents {
+        batch: EventBatch,
+        /// Records the height of the batch as processed once handled, if the state is persisted
+        processed_height: Option<Arc<ProcessedHeight>>,
+    },
 
     /// A new block has been committed
     NewBlock { height: Height, new_block: NewBlock },
@@ -26,6 +32,19 @@ impl WorkerCmd {
         matches!(self, Self::IbcEvents { .. })
     }
 
+    /// Returns the guard recording the height of the batch of events as processed,
+    /// if the cmd is [`IbcEvents`] and the state of the relayer is persisted.
+    ///
+    /// [`IbcEvents`]: WorkerCmd::IbcEvents
+    pub fn processed_height(&self) -> Option<Arc<ProcessedHeight>> {
+        match self {
+            Self::IbcEvents {
+                processed_height, ..
+            } => processed_height.clone(),
+            _ => None,
+        }
+    }
+
     /// Returns `true` if the worker cmd is [`NewBlock`].
     ///
     /// [`NewBlock`]: WorkerCmd::NewBlock
@@ -46,7 +65,7 @@ impl WorkerCmd {
 impl Display for WorkerCmd {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
-            WorkerCmd::IbcEvents { batch } => {
+            WorkerCmd::IbcEvents { batch, .. } => {
                 write!(f, "IbcEvents batch from {}: ", batch.chain_id)?;
                 for e in &batch.events {
                     write!(f, "{e}; ")?;
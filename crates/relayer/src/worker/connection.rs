@@ -26,7 +26,7 @@ pub fn spawn_connection_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
         move || {
             if let Ok(cmd) = cmd_rx.try_recv() {
                 match cmd {
-                    WorkerCmd::IbcEvents { batch } => {
+                    WorkerCmd::IbcEvents { batch, .. } => {
                         // there can be up to two event for this connection, e.g. init and try.
                         // process the last event, the one with highest "rank".
                         let last_event_with_height = batch.events.last();
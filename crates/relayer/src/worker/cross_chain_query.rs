@@ -65,7 +65,7 @@ fn handle_cross_chain_query<ChainA: ChainHandle, ChainB: ChainHandle>(
     cmd: WorkerCmd,
     cross_chain_query: &CrossChainQuery,
 ) -> Result<(), TaskError<RunError>> {
-    if let WorkerCmd::IbcEvents { batch } = &cmd {
+    if let WorkerCmd::IbcEvents { batch, .. } = &cmd {
         let queries: Vec<CrossChainQueryRequest> = batch
             .events
             .iter()
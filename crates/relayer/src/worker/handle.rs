@@ -1,3 +1,4 @@
+use alloc::sync::Arc;
 use core::fmt;
 use core::mem;
 
@@ -13,6 +14,8 @@ use ibc_relayer_types::{
 
 use crate::chain::tracking::TrackingId;
 use crate::event::IbcEventWithHeight;
+use crate::link::activity::{RelayActivity, SharedRelayActivity};
+use crate::store::ProcessedHeight;
 use crate::util::lock::{LockExt, RwArc};
 use crate::util::task::TaskHandle;
 use crate::{event::source::EventBatch, object::Object};
@@ -31,6 +34,7 @@ pub struct WorkerHandle {
     data: Option<WorkerData>,
     tx: RwArc<Option<Sender<WorkerCmd>>>,
     task_handles: Vec<TaskHandle>,
+    activity: Option<SharedRelayActivity>,
 }
 
 impl WorkerHandle {
@@ -40,6 +44,7 @@ impl WorkerHandle {
         data: Option<WorkerData>,
         tx: Option<Sender<WorkerCmd>>,
         task_handles: Vec<TaskHandle>,
+        activity: Option<SharedRelayActivity>,
     ) -> Self {
         Self {
             id,
@@ -47,6 +52,7 @@ impl WorkerHandle {
             data,
             tx: <RwArc<_>>::new_lock(tx),
             task_handles,
+            activity,
         }
     }
 
@@ -70,6 +76,7 @@ impl WorkerHandle {
         events: Vec<IbcEventWithHeight>,
         chain_id: ChainId,
         tracking_id: TrackingId,
+        processed_height: Option<Arc<ProcessedHeight>>,
     ) {
         let batch = EventBatch {
             chain_id,
@@ -78,7 +85,10 @@ impl WorkerHandle {
             tracking_id,
         };
 
-        self.try_send_command(WorkerCmd::IbcEvents { batch });
+        self.try_send_command(WorkerCmd::IbcEvents {
+            batch,
+            processed_height,
+        });
     }
 
     /// Send a batch of [`NewBlock`] event to the worker.
@@ -107,6 +117,26 @@ impl WorkerHandle {
         // Drop handle automatically handles the waiting for tasks to terminate.
     }
 
+    /// Pause all worker tasks. The commands sent to the worker while it is paused
+    /// are queued, and are processed once the worker is resumed.
+    pub fn pause(&self) {
+        for task in self.task_handles.iter() {
+            task.pause()
+        }
+    }
+
+    /// Resume all worker tasks.
+    pub fn resume(&self) {
+        for task in self.task_handles.iter() {
+            task.resume()
+        }
+    }
+
+    /// Whether the worker tasks are paused.
+    pub fn is_paused(&self) -> bool {
+        self.task_handles.iter().any(|task| task.is_paused())
+    }
+
     pub fn is_stopped(&self) -> bool {
         for task in self.task_handles.iter() {
             if !task.is_stopped() {
@@ -152,6 +182,13 @@ impl WorkerHandle {
     pub fn data(&self) -> Option<&WorkerData> {
         self.data.as_ref()
     }
+
+    /// The outcome of the latest relaying attempts of a packet worker.
+    pub fn relay_activity(&self) -> Option<RelayActivity> {
+        self.activity
+            .as_ref()
+            .map(|activity| activity.acquire_read().clone())
+    }
 }
 
 // Drop handle to send shutdown signals to background tasks in parallel
@@ -10,16 +10,19 @@ use crate::{
     chain::handle::{ChainHandle, ChainHandlePair},
     config::Config,
     object::Object,
+    store::SharedStateStore,
     telemetry,
 };
 
-use super::{spawn_worker_tasks, WorkerHandle, WorkerId};
+use super::{spawn_worker_tasks, wallet::CriticalWallets, WorkerHandle, WorkerId};
 
 /// Manage the lifecycle of [`WorkerHandle`]s associated with [`Object`]s.
 #[derive(Debug)]
 pub struct WorkerMap {
     workers: HashMap<Object, WorkerHandle>,
     latest_worker_id: WorkerId,
+    critical_wallets: CriticalWallets,
+    state_store: Option<SharedStateStore>,
 }
 
 impl Default for WorkerMap {
@@ -27,6 +30,8 @@ impl Default for WorkerMap {
         Self {
             workers: HashMap::new(),
             latest_worker_id: WorkerId::new(0),
+            critical_wallets: CriticalWallets::default(),
+            state_store: None,
         }
     }
 }
@@ -39,11 +44,32 @@ impl WorkerMap {
         Self::default()
     }
 
+    /// Create a new worker map, whose packet workers restore their state from
+    /// the given store and keep it up-to-date.
+    pub fn with_state_store(state_store: SharedStateStore) -> Self {
+        Self {
+            workers: HashMap::new(),
+            latest_worker_id: WorkerId::new(0),
+            critical_wallets: CriticalWallets::default(),
+            state_store: Some(state_store),
+        }
+    }
+
+    /// The store in which the packet workers persist their state, if any.
+    pub fn state_store(&self) -> Option<&SharedStateStore> {
+        self.state_store.as_ref()
+    }
+
     /// Returns `true` if there is a spawned [`WorkerHandle`] associated with the given [`Object`].
     pub fn contains(&self, object: &Object) -> bool {
         self.workers.contains_key(object)
     }
 
+    /// Returns the spawned [`WorkerHandle`] with the given [`WorkerId`], if any.
+    pub fn get(&self, id: WorkerId) -> Option<&WorkerHandle> {
+        self.workers.values().find(|worker| worker.id() == id)
+    }
+
     /// Remove the [`WorkerHandle`] associated with the given [`Object`] from
     /// the map and wait for its thread to terminate.
     pub fn remove_stopped(&mut self, id: WorkerId, object: Object) -> bool {
@@ -151,6 +177,21 @@ impl WorkerMap {
         }
     }
 
+    /// Shut down the worker for the given [`Object`], if any, waiting for it
+    /// to terminate, and spawn a new worker in its place.
+    pub fn respawn<Chain: ChainHandle>(
+        &mut self,
+        src: Chain,
+        dst: Chain,
+        object: &Object,
+        config: &Config,
+    ) -> &WorkerHandle {
+        self.shutdown_worker(object);
+
+        let worker = self.spawn_worker(src, dst, object, config);
+        self.workers.entry(object.clone()).or_insert(worker)
+    }
+
     /// Force spawn a worker for the given [`Object`].
     fn spawn_worker<Chain: ChainHandle>(
         &mut self,
@@ -166,6 +207,8 @@ impl WorkerMap {
             self.next_worker_id(),
             object.clone(),
             config,
+            &self.critical_wallets,
+            self.state_store.as_ref(),
         )
     }
 
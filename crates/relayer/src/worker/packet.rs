@@ -25,16 +25,19 @@ use ibc_relayer_types::Height;
 
 use crate::chain::handle::ChainHandle;
 use crate::config::filter::FeePolicy;
+use crate::event::monitor::{self, RelayerEvent, RelayerEventKind};
 use crate::event::source::EventBatch;
 use crate::foreign_client::HasExpiredOrFrozenError;
 use crate::link::Resubmit;
 use crate::link::{error::LinkError, Link};
+use crate::notifications::{self, Incident};
 use crate::object::Packet;
 use crate::telemetry;
 use crate::util::lock::{LockExt, RwArc};
 use crate::util::task::{spawn_background_task, Next, TaskError, TaskHandle};
 
 use super::error::RunError;
+use super::wallet::CriticalWallets;
 use super::WorkerCmd;
 
 const INCENTIVIZED_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
@@ -54,6 +57,45 @@ fn handle_link_error_in_task(e: LinkError) -> TaskError<RunError> {
     }
 }
 
+/// Record the error of a failed step of a packet worker in the activity of its relay path,
+/// and publish it to the subscribers of the activity of the relayer.
+fn record_error<ChainA: ChainHandle, ChainB: ChainHandle>(
+    link: &Link<ChainA, ChainB>,
+    result: Result<(), TaskError<RunError>>,
+) -> Result<(), TaskError<RunError>> {
+    if let Err(TaskError::Ignore(e) | TaskError::Fatal(e)) = &result {
+        let activity = link.a_to_b.activity();
+        let failures = {
+            let mut activity = activity.acquire_write();
+            activity.record_error(e);
+            activity.consecutive_errors
+        };
+
+        if notifications::config().map_or(false, |config| failures >= config.tx_failures) {
+            notifications::notify(Incident::TxFailures {
+                chain_id: link.a_to_b.src_chain().id(),
+                port_id: link.a_to_b.src_port_id().clone(),
+                channel_id: link.a_to_b.src_channel_id().clone(),
+                counterparty_chain_id: link.a_to_b.dst_chain().id(),
+                failures,
+                last_error: e.to_string(),
+            });
+        }
+
+        monitor::publish(|| {
+            RelayerEvent::new(
+                link.a_to_b.src_chain().id(),
+                Some(link.a_to_b.src_channel_id().clone()),
+                RelayerEventKind::Error {
+                    message: e.to_string(),
+                },
+            )
+        });
+    }
+
+    result
+}
+
 /// Spawns a packet worker task in the background that handles the work of
 /// processing pending txs between `ChainA` and `ChainB`.
 pub fn spawn_packet_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
@@ -74,7 +116,13 @@ pub fn spawn_packet_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     };
 
     spawn_background_task(span, Some(Duration::from_millis(1000)), move || {
-        handle_execute_schedule(&mut link.lock().unwrap(), &path, resubmit)?;
+        let mut link = link.lock().unwrap();
+        let result = handle_execute_schedule(&mut link, &path, resubmit);
+
+        // Persist the state even if the execution failed midway
+        link.a_to_b.persist_state();
+
+        record_error(&link, result)?;
         Ok(Next::Continue)
     })
 }
@@ -86,6 +134,7 @@ pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     mut should_clear_on_start: bool,
     clear_interval: u64,
     path: Packet,
+    critical_wallets: CriticalWallets,
 ) -> TaskHandle {
     let span = {
         let relay_path = &link.lock().unwrap().a_to_b;
@@ -109,19 +158,38 @@ pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     spawn_background_task(span, Some(Duration::from_millis(200)), move || {
         if let Ok(cmd) = cmd_rx.try_recv() {
             let is_new_batch = cmd.is_ibc_events();
+            let processed_height = cmd.processed_height();
+
+            // Periodic clearing is paused while the relayer wallet on either chain is critically low
+            let clearing_paused = {
+                let relay_path = &link.lock().unwrap().a_to_b;
+                critical_wallets.contains(&relay_path.src_chain().id())
+                    || critical_wallets.contains(&relay_path.dst_chain().id())
+            };
 
             // Try to clear pending packets. At different levels down in `handle_packet_cmd` there
             // are retries mechanisms for MAX_RETRIES (current value hardcoded at 5).
             // If clearing fails after all these retries with ignorable error the task continues
             // (see `handle_link_error_in_task`) and clearing is retried with the next
             // (`NewBlock`) `cmd` that matches the clearing interval.
-            handle_packet_cmd(
-                &mut link.lock().unwrap(),
-                &mut should_clear_on_start,
-                clear_interval,
-                &path,
-                cmd,
-            )?;
+            {
+                let mut link = link.lock().unwrap();
+
+                let result = handle_packet_cmd(
+                    &mut link,
+                    &mut should_clear_on_start,
+                    clear_interval,
+                    clearing_paused,
+                    &path,
+                    cmd,
+                );
+
+                if let (Err(_), Some(processed_height)) = (&result, &processed_height) {
+                    processed_height.fail();
+                }
+
+                record_error(&link, result)?;
+            }
 
             if is_new_batch {
                 idle_worker_timer = 0;
@@ -171,13 +239,22 @@ pub fn spawn_incentivized_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHa
 
     spawn_background_task(span, Some(Duration::from_millis(200)), move || {
         if let Ok(cmd) = cmd_rx.try_recv() {
-            handle_incentivized_packet_cmd(
-                &mut link.lock().unwrap(),
+            let processed_height = cmd.processed_height();
+            let mut link = link.lock().unwrap();
+
+            let result = handle_incentivized_packet_cmd(
+                &mut link,
                 &path,
                 cmd,
                 &incentivized_recv_cache,
                 &fee_filter,
-            )?;
+            );
+
+            if let (Err(_), Some(processed_height)) = (&result, &processed_height) {
+                processed_height.fail();
+            }
+
+            record_error(&link, result)?;
         }
 
         Ok(Next::Continue)
@@ -190,7 +267,7 @@ pub fn spawn_incentivized_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHa
 /// packet clearing if the `should_clear_on_start` flag has been toggled.
 ///
 /// Given a `NewBlock` command, checks if packet clearing should occur
-/// and performs it if so.
+/// and performs it if so, unless periodic clearing is paused.
 ///
 /// Given a `ClearPendingPackets` command, clears pending packets.
 ///
@@ -200,12 +277,13 @@ fn handle_packet_cmd<ChainA: ChainHandle, ChainB: ChainHandle>(
     link: &mut Link<ChainA, ChainB>,
     should_clear_on_start: &mut bool,
     clear_interval: u64,
+    clearing_paused: bool,
     path: &Packet,
     cmd: WorkerCmd,
 ) -> Result<(), TaskError<RunError>> {
     // Handle packet clearing which is triggered from a command
     let (do_clear, maybe_height) = match &cmd {
-        WorkerCmd::IbcEvents { batch } => {
+        WorkerCmd::IbcEvents { batch, .. } => {
             if *should_clear_on_start {
                 (true, Some(batch.height))
             } else {
@@ -216,7 +294,9 @@ fn handle_packet_cmd<ChainA: ChainHandle, ChainB: ChainHandle>(
         // Handle the arrival of an event signaling that the
         // source chain has advanced to a new block
         WorkerCmd::NewBlock { height, .. } => {
-            if *should_clear_on_start || should_clear_packets(clear_interval, *height) {
+            if *should_clear_on_start
+                || (!clearing_paused && should_clear_packets(clear_interval, *height))
+            {
                 (true, Some(*height))
             } else {
                 (false, None)
@@ -236,7 +316,7 @@ fn handle_packet_cmd<ChainA: ChainHandle, ChainB: ChainHandle>(
     }
 
     // Handle command-specific task
-    if let WorkerCmd::IbcEvents { batch } = cmd {
+    if let WorkerCmd::IbcEvents { batch, .. } = cmd {
         handle_update_schedule(link, clear_interval, path, batch)?;
     }
 
@@ -261,7 +341,7 @@ fn handle_incentivized_packet_cmd<ChainA: ChainHandle, ChainB: ChainHandle>(
     fee_filter: &FeePolicy,
 ) -> Result<(), TaskError<RunError>> {
     // Handle command-specific task
-    if let WorkerCmd::IbcEvents { mut batch } = cmd {
+    if let WorkerCmd::IbcEvents { mut batch, .. } = cmd {
         // Iterate through the batch in order to retrieve the IncentivizedPacket
         // which will be used to confirm if a SendPacket event is incentivized.
         for event in batch.events.clone() {
@@ -1,16 +1,165 @@
-use std::time::Duration;
+use alloc::collections::{BTreeSet, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
-use tracing::{error_span, trace, warn};
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use tracing::{error, error_span, info, trace, warn};
 
 use crate::{
-    chain::handle::ChainHandle,
+    accounting,
+    chain::{
+        cosmos::{gas::default_tx_fee, types::gas::GasConfig},
+        handle::ChainHandle,
+    },
+    config::ChainConfig,
+    notifications::{self, Incident},
     telemetry,
-    util::task::{spawn_background_task, Next, TaskError, TaskHandle},
+    util::{
+        lock::LockExt,
+        task::{spawn_background_task, Next, TaskError, TaskHandle},
+    },
 };
 
-pub fn spawn_wallet_worker<Chain: ChainHandle>(chain: Chain) -> TaskHandle {
+/// The window over which the rate at which the wallet is spent on fees is computed.
+const SPEND_RATE_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// The balance thresholds of a wallet, in the denomination of the gas price,
+/// below which the wallet is reported as running low or critically low.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BalanceThresholds {
+    pub warning: Option<u128>,
+    pub critical: Option<u128>,
+    pub pause_on_critical: bool,
+}
+
+impl BalanceThresholds {
+    pub fn from_config(config: &ChainConfig) -> Self {
+        let tx_fee = default_tx_fee(&GasConfig::from(config));
+
+        Self {
+            warning: config.min_balance.as_ref().map(|b| b.amount(tx_fee)),
+            critical: config.critical_balance.as_ref().map(|b| b.amount(tx_fee)),
+            pause_on_critical: config.pause_on_critical_balance,
+        }
+    }
+
+    fn level(&self, balance: u128) -> BalanceLevel {
+        if self.critical.map_or(false, |critical| balance < critical) {
+            BalanceLevel::Critical
+        } else if self.warning.map_or(false, |warning| balance < warning) {
+            BalanceLevel::Warning
+        } else {
+            BalanceLevel::Ok
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum BalanceLevel {
+    Ok,
+    Warning,
+    Critical,
+}
+
+/// The set of chains on which the relayer wallet is critically low,
+/// and on which the periodic clearing of pending packets is paused.
+#[derive(Clone, Debug, Default)]
+pub struct CriticalWallets(Arc<RwLock<BTreeSet<ChainId>>>);
+
+impl CriticalWallets {
+    /// Returns `true` if the relayer wallet on the given chain is critically low.
+    pub fn contains(&self, chain_id: &ChainId) -> bool {
+        self.0.acquire_read().contains(chain_id)
+    }
+
+    fn insert(&self, chain_id: ChainId) {
+        self.0.acquire_write().insert(chain_id);
+    }
+
+    fn remove(&self, chain_id: &ChainId) {
+        self.0.acquire_write().remove(chain_id);
+    }
+}
+
+/// Tracks the rate at which a wallet is spent on fees.
+///
+/// The rate is computed from the total fees paid for the transactions submitted
+/// to the chain, as accounted for by [`accounting::account_for_submitted_tx`]
+/// whether or not `tx_confirmation` is enabled, so that the transfers to and
+/// from the wallet do not distort it.
+#[derive(Debug)]
+struct SpendTracker {
+    window: Duration,
+    started_at: Option<Instant>,
+    last_fees_paid: Option<u128>,
+    spent: VecDeque<(Instant, u128)>,
+}
+
+impl SpendTracker {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            started_at: None,
+            last_fees_paid: None,
+            spent: VecDeque::new(),
+        }
+    }
+
+    /// Records the total fees paid so far, of which the increase since
+    /// the previous sample is accounted as spent in the meantime.
+    fn record(&mut self, now: Instant, fees_paid: u128) {
+        self.started_at.get_or_insert(now);
+
+        if let Some(last_fees_paid) = self.last_fees_paid {
+            if fees_paid > last_fees_paid {
+                self.spent.push_back((now, fees_paid - last_fees_paid));
+            }
+        }
+
+        self.last_fees_paid = Some(fees_paid);
+
+        while let Some((at, _)) = self.spent.front() {
+            if now.duration_since(*at) > self.window {
+                self.spent.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// The amount spent per second over the window, if anything was spent.
+    fn rate(&self, now: Instant) -> Option<f64> {
+        let elapsed = now
+            .duration_since(self.started_at?)
+            .min(self.window)
+            .as_secs_f64();
+
+        let spent: u128 = self.spent.iter().map(|(_, amount)| amount).sum();
+
+        if spent == 0 || elapsed == 0.0 {
+            None
+        } else {
+            Some(spent as f64 / elapsed)
+        }
+    }
+
+    /// The estimated time until the given balance is spent, if anything was spent.
+    fn runway(&self, now: Instant, balance: u128) -> Option<Duration> {
+        self.rate(now)
+            .map(|rate| Duration::from_secs_f64(balance as f64 / rate))
+    }
+}
+
+pub fn spawn_wallet_worker<Chain: ChainHandle>(
+    chain: Chain,
+    thresholds: BalanceThresholds,
+    critical_wallets: CriticalWallets,
+) -> TaskHandle {
     let span = error_span!("wallet", chain = %chain.id());
 
+    let mut tracker = SpendTracker::new(SPEND_RATE_WINDOW);
+    let mut level = BalanceLevel::Ok;
+
     spawn_background_task(span, Some(Duration::from_secs(5)), move || {
         let key = chain.get_key().map_err(|e| {
             TaskError::Fatal(format!("failed to get key in use by the relayer: {e}"))
@@ -44,12 +193,87 @@ pub fn spawn_wallet_worker<Chain: ChainHandle>(chain: Chain) -> TaskHandle {
                 );
             }
         }
+
+        let amount = match balance.amount.parse::<u128>() {
+            Ok(amount) => amount,
+            Err(e) => {
+                warn!(
+                    %balance.amount, denom = %balance.denom, account = %key.account(),
+                    "unable to parse the wallet balance into a u128, the time until the wallet is empty will therefore not be estimated. Reason: {}", e
+                );
+                return Ok(Next::Continue);
+            }
+        };
+
+        let now = Instant::now();
+        tracker.record(
+            now,
+            accounting::fees_paid(&chain.id(), &key.account(), &balance.denom),
+        );
+        let runway = tracker.runway(now, amount);
+
+        telemetry!(
+            wallet_runway,
+            &chain.id(),
+            &key.account(),
+            runway.map_or(f64::INFINITY, |runway| runway.as_secs_f64()),
+            &balance.denom,
+        );
+
+        let runway = runway
+            .map(|runway| humantime::format_duration(Duration::from_secs(runway.as_secs())))
+            .map_or_else(|| "unknown".to_string(), |runway| runway.to_string());
+
+        let new_level = thresholds.level(amount);
+
+        // Only report the crossing of a threshold, not every sample below it
+        if new_level != level {
+            match new_level {
+                BalanceLevel::Ok => info!(
+                    %balance.amount, denom = %balance.denom, account = %key.account(), %runway,
+                    "wallet balance is back above its minimum balance"
+                ),
+                BalanceLevel::Warning => warn!(
+                    %balance.amount, denom = %balance.denom, account = %key.account(), %runway,
+                    "wallet balance is running low, please top up the relayer wallet"
+                ),
+                BalanceLevel::Critical => error!(
+                    %balance.amount, denom = %balance.denom, account = %key.account(), %runway,
+                    "wallet balance is critically low, please top up the relayer wallet"
+                ),
+            }
+
+            if new_level != BalanceLevel::Ok {
+                notifications::notify(Incident::LowBalance {
+                    chain_id: chain.id(),
+                    wallet: key.account(),
+                    balance: balance.amount.clone(),
+                    denom: balance.denom,
+                    critical: new_level == BalanceLevel::Critical,
+                });
+            }
+
+            if thresholds.pause_on_critical {
+                if new_level == BalanceLevel::Critical {
+                    warn!("pausing the periodic clearing of pending packets until the wallet is topped up");
+                    critical_wallets.insert(chain.id());
+                } else if level == BalanceLevel::Critical {
+                    info!("resuming the periodic clearing of pending packets");
+                    critical_wallets.remove(&chain.id());
+                }
+            }
+
+            level = new_level;
+        }
+
         Ok(Next::Continue)
     })
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     use ibc_relayer_types::bigint::U256;
 
     // Test to confirm that any u256 fits in f64
@@ -60,4 +284,48 @@ mod tests {
 
         assert!(f64_max > u256_max.to_string().parse::<f64>().unwrap());
     }
+
+    #[test]
+    fn spend_tracker_runway() {
+        let start = Instant::now();
+        let mut tracker = SpendTracker::new(Duration::from_secs(100));
+
+        tracker.record(start, 500);
+        assert_eq!(tracker.runway(start, 10_000), None);
+
+        // Paid 1000 in fees in 10s, ie. 100/s
+        tracker.record(start + Duration::from_secs(10), 1_500);
+        assert_eq!(
+            tracker.runway(start + Duration::from_secs(10), 9_000),
+            Some(Duration::from_secs(90))
+        );
+
+        // A top-up of the balance does not change the spend rate
+        tracker.record(start + Duration::from_secs(20), 1_500);
+        assert_eq!(
+            tracker.runway(start + Duration::from_secs(20), 19_000),
+            Some(Duration::from_secs(380))
+        );
+
+        // Spending older than the window is forgotten
+        tracker.record(start + Duration::from_secs(200), 1_500);
+        assert_eq!(
+            tracker.runway(start + Duration::from_secs(200), 19_000),
+            None
+        );
+    }
+
+    #[test]
+    fn balance_thresholds_level() {
+        let thresholds = BalanceThresholds {
+            warning: Some(1000),
+            critical: Some(100),
+            pause_on_critical: false,
+        };
+
+        assert_eq!(thresholds.level(1000), BalanceLevel::Ok);
+        assert_eq!(thresholds.level(999), BalanceLevel::Warning);
+        assert_eq!(thresholds.level(99), BalanceLevel::Critical);
+        assert_eq!(BalanceThresholds::default().level(0), BalanceLevel::Ok);
+    }
 }
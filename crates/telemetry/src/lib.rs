@@ -2,6 +2,7 @@ pub mod encoder;
 mod path_identifier;
 pub mod server;
 pub mod state;
+pub mod traces;
 
 use std::error::Error;
 use std::net::{SocketAddr, ToSocketAddrs};
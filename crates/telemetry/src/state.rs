@@ -8,7 +8,7 @@ use std::{
 use dashmap::{DashMap, DashSet};
 use opentelemetry::{
     global,
-    metrics::{Counter, ObservableGauge, UpDownCounter},
+    metrics::{Counter, Histogram, ObservableGauge, UpDownCounter},
     Context, KeyValue,
 };
 use opentelemetry_prometheus::PrometheusExporter;
@@ -64,6 +64,11 @@ const QUERY_TYPES: [&str; 26] = [
     "query_staking_params",
 ];
 
+/// The bounds of the histogram buckets of the `endpoint_latency` metric, in milliseconds.
+const ENDPOINT_LATENCY_BUCKETS: [f64; 12] = [
+    5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0, 30000.0,
+];
+
 // Constant value used to define the number of seconds
 // the rewarded fees Cache value live.
 // Current value is 7 days.
@@ -92,6 +97,28 @@ impl Display for WorkerType {
     }
 }
 
+/// The status of a client, as observed when refreshing it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ClientStatus {
+    Active,
+    Expired,
+    Frozen,
+}
+
+impl ClientStatus {
+    const ALL: [Self; 3] = [Self::Active, Self::Expired, Self::Frozen];
+}
+
+impl Display for ClientStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Active => write!(f, "active"),
+            Self::Expired => write!(f, "expired"),
+            Self::Frozen => write!(f, "frozen"),
+        }
+    }
+}
+
 pub struct TelemetryState {
     exporter: PrometheusExporter,
 
@@ -104,6 +131,18 @@ pub struct TelemetryState {
     /// Number of misbehaviours detected and submitted per client
     client_misbehaviours_submitted: Counter<u64>,
 
+    /// The status of each client, ie. 1 for its current status and 0 for the others
+    client_status: ObservableGauge<u64>,
+
+    /// The time elapsed since the latest update of each client, measured
+    /// with the time of the chain it tracks. Seconds.
+    client_update_age: ObservableGauge<u64>,
+
+    /// The time remaining until each client expires if it is not updated,
+    /// ie. until the end of the trusting period of its latest consensus state.
+    /// Zero if the client is expired. Seconds.
+    client_expiry: ObservableGauge<u64>,
+
     /// Number of confirmed receive packets per channel
     receive_packets_confirmed: Counter<u64>,
 
@@ -119,9 +158,26 @@ pub struct TelemetryState {
     /// Number of cache hits for queries submitted by Hermes, per chain and query type
     queries_cache_hits: Counter<u64>,
 
+    /// Number of cache misses for queries submitted by Hermes, per chain and query type
+    queries_cache_misses: Counter<u64>,
+
+    /// Number of entries evicted from the query caches, per chain, query type and cause
+    queries_cache_evictions: Counter<u64>,
+
+    /// Number of entries in the query caches, per chain and query type
+    queries_cache_size: ObservableGauge<u64>,
+
     /// Number of times Hermes reconnected to the websocket endpoint, per chain
     ws_reconnect: Counter<u64>,
 
+    /// The latency of the requests to the RPC and gRPC endpoints, per chain and endpoint.
+    /// Milliseconds.
+    endpoint_latency: Histogram<u64>,
+
+    /// Number of failed requests to the RPC and gRPC endpoints, per chain, endpoint
+    /// and class of error
+    endpoint_errors: Counter<u64>,
+
     /// How many IBC events did Hermes receive via the WebSocket subscription, per chain
     ws_events: Counter<u64>,
 
@@ -131,6 +187,10 @@ pub struct TelemetryState {
     /// The balance of each wallet Hermes uses per chain
     wallet_balance: ObservableGauge<f64>,
 
+    /// The estimated time until each wallet Hermes uses is empty, given
+    /// the rate at which it was spent on fees during the last hour. Seconds.
+    wallet_runway: ObservableGauge<f64>,
+
     /// Indicates the latency for all transactions submitted to a specific chain,
     /// i.e. the difference between the moment when Hermes received a batch of events
     /// until the corresponding transaction(s) were submitted. Milliseconds.
@@ -185,6 +245,14 @@ pub struct TelemetryState {
     /// Total amount of fees received from ICS29 fees.
     fee_amounts: Counter<u64>,
 
+    /// Total amount of fees paid for the transactions confirmed on each chain,
+    /// per chain, wallet, channel, message type and denom
+    tx_fees_paid: Counter<u64>,
+
+    /// Total amount of ICS29 fees rewarded to the wallet by the transactions
+    /// confirmed on each chain, per chain, wallet, channel and denom
+    tx_fees_rewarded: Counter<u64>,
+
     /// List of addresses for which rewarded fees from ICS29 should be recorded.
     visible_fee_addresses: DashSet<String>,
 
@@ -238,6 +306,23 @@ impl TelemetryState {
                 .with_description("Number of misbehaviours detected and submitted")
                 .init(),
 
+            client_status: meter
+                .u64_observable_gauge("client_status")
+                .with_description("The status of each client, as observed when refreshing it: 1 for its current status (active, expired or frozen), 0 for the others")
+                .init(),
+
+            client_update_age: meter
+                .u64_observable_gauge("client_update_age_seconds")
+                .with_unit(Unit::new("seconds"))
+                .with_description("The time elapsed since the latest update of each client, as observed when refreshing it (seconds)")
+                .init(),
+
+            client_expiry: meter
+                .u64_observable_gauge("client_expiry_seconds")
+                .with_unit(Unit::new("seconds"))
+                .with_description("The time remaining until each client expires if it is not updated, as observed when refreshing it (seconds). 0 if the client is expired")
+                .init(),
+
             receive_packets_confirmed: meter
                 .u64_counter("receive_packets_confirmed")
                 .with_description("Number of confirmed receive packets. Available if relayer runs with Tx confirmation enabled")
@@ -265,11 +350,37 @@ impl TelemetryState {
                 .with_description("Number of cache hits for queries submitted by Hermes")
                 .init(),
 
+            queries_cache_misses: meter
+                .u64_counter("queries_cache_misses")
+                .with_description("Number of cache misses for queries submitted by Hermes")
+                .init(),
+
+            queries_cache_evictions: meter
+                .u64_counter("queries_cache_evictions")
+                .with_description("Number of entries evicted from the query caches, per chain, query type and cause (expired or size)")
+                .init(),
+
+            queries_cache_size: meter
+                .u64_observable_gauge("queries_cache_size")
+                .with_description("Number of entries in the query caches, per chain and query type")
+                .init(),
+
             ws_reconnect: meter
                 .u64_counter("ws_reconnect")
                 .with_description("Number of times Hermes reconnected to the websocket endpoint")
                 .init(),
 
+            endpoint_latency: meter
+                .u64_histogram("endpoint_latency")
+                .with_unit(Unit::new("milliseconds"))
+                .with_description("The latency of the requests to the RPC and gRPC endpoints, per chain and endpoint. Milliseconds.")
+                .init(),
+
+            endpoint_errors: meter
+                .u64_counter("endpoint_errors")
+                .with_description("Number of failed requests to the RPC and gRPC endpoints, per chain, endpoint and class of error (timeout, 5xx, decode, rate_limited or other)")
+                .init(),
+
             ws_events: meter
                 .u64_counter("ws_events")
                 .with_description("How many IBC events did Hermes receive via the websocket subscription")
@@ -285,6 +396,11 @@ impl TelemetryState {
                 .with_description("The balance of each wallet Hermes uses per chain. Please note that when converting the balance to f64 a loss in precision might be introduced in the displayed value")
                 .init(),
 
+            wallet_runway: meter
+                .f64_observable_gauge("wallet_runway_seconds")
+                .with_description("The estimated time until each wallet Hermes uses is empty, given the fees it paid during the last hour (seconds). +Inf if no fees were paid")
+                .init(),
+
             send_packet_events: meter
                 .u64_counter("send_packet_events")
                 .with_description("Number of SendPacket events received")
@@ -354,6 +470,16 @@ impl TelemetryState {
                 .with_description("Total amount received from ICS29 fees")
                 .init(),
 
+            tx_fees_paid: meter
+                .u64_counter("tx_fees_paid")
+                .with_description("Total amount of fees paid for the transactions confirmed on each chain, per chain, wallet, channel, message type and denom")
+                .init(),
+
+            tx_fees_rewarded: meter
+                .u64_counter("tx_fees_rewarded")
+                .with_description("Total amount of ICS29 fees rewarded to the wallet by the transactions confirmed on each chain, per chain, wallet, channel and denom")
+                .init(),
+
             visible_fee_addresses: DashSet::new(),
 
             cached_fees: Mutex::new(Vec::new()),
@@ -483,6 +609,7 @@ impl TelemetryState {
             ];
 
             self.queries_cache_hits.add(&cx, 0, labels);
+            self.queries_cache_misses.add(&cx, 0, labels);
         }
     }
 
@@ -531,6 +658,39 @@ impl TelemetryState {
         self.client_misbehaviours_submitted.add(&cx, count, labels);
     }
 
+    /// The status of a client, along with the time elapsed since its latest update
+    /// and the time remaining until it expires, if known
+    pub fn client_expiry(
+        &self,
+        src_chain: &ChainId,
+        dst_chain: &ChainId,
+        client: &ClientId,
+        status: ClientStatus,
+        expiry: Option<(Duration, Duration)>,
+    ) {
+        let cx = Context::current();
+
+        let labels = [
+            KeyValue::new("src_chain", src_chain.to_string()),
+            KeyValue::new("dst_chain", dst_chain.to_string()),
+            KeyValue::new("client", client.to_string()),
+        ];
+
+        for s in ClientStatus::ALL {
+            let mut status_labels = labels.to_vec();
+            status_labels.push(KeyValue::new("status", s.to_string()));
+
+            self.client_status
+                .observe(&cx, u64::from(s == status), &status_labels);
+        }
+
+        if let Some((update_age, expiry)) = expiry {
+            self.client_update_age
+                .observe(&cx, update_age.as_secs(), &labels);
+            self.client_expiry.observe(&cx, expiry.as_secs(), &labels);
+        }
+    }
+
     /// Number of receive packets relayed, per channel
     #[allow(clippy::too_many_arguments)]
     pub fn receive_packets_confirmed(
@@ -640,6 +800,48 @@ impl TelemetryState {
         self.queries_cache_hits.add(&cx, 1, labels);
     }
 
+    /// Number of cache misses for queries emitted by the relayer, per chain and query type
+    pub fn queries_cache_misses(&self, chain_id: &ChainId, query_type: &'static str) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("query_type", query_type),
+        ];
+
+        self.queries_cache_misses.add(&cx, 1, labels);
+    }
+
+    /// Number of entries evicted from the query caches, per chain, query type and cause
+    pub fn queries_cache_evictions(
+        &self,
+        chain_id: &ChainId,
+        query_type: &'static str,
+        cause: &'static str,
+    ) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("query_type", query_type),
+            KeyValue::new("cause", cause),
+        ];
+
+        self.queries_cache_evictions.add(&cx, 1, labels);
+    }
+
+    /// Number of entries in the query caches, per chain and query type
+    pub fn queries_cache_size(&self, chain_id: &ChainId, query_type: &'static str, size: u64) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("query_type", query_type),
+        ];
+
+        self.queries_cache_size.observe(&cx, size, labels);
+    }
+
     /// Number of time the relayer had to reconnect to the WebSocket endpoint, per chain
     pub fn ws_reconnect(&self, chain_id: &ChainId) {
         let cx = Context::current();
@@ -649,6 +851,36 @@ impl TelemetryState {
         self.ws_reconnect.add(&cx, 1, labels);
     }
 
+    /// The latency of a request to an RPC or gRPC endpoint of a chain,
+    /// along with the class of its error if it failed
+    pub fn endpoint_request(
+        &self,
+        chain_id: &ChainId,
+        endpoint: &str,
+        latency: Duration,
+        error: Option<&'static str>,
+    ) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("endpoint", endpoint.to_string()),
+        ];
+
+        self.endpoint_latency
+            .record(&cx, latency.as_millis() as u64, labels);
+
+        if let Some(error) = error {
+            let labels = &[
+                KeyValue::new("chain", chain_id.to_string()),
+                KeyValue::new("endpoint", endpoint.to_string()),
+                KeyValue::new("error", error),
+            ];
+
+            self.endpoint_errors.add(&cx, 1, labels);
+        }
+    }
+
     /// How many IBC events did Hermes receive via the WebSocket subscription, per chain
     pub fn ws_events(&self, chain_id: &ChainId, count: u64) {
         let cx = Context::current();
@@ -681,6 +913,18 @@ impl TelemetryState {
         self.wallet_balance.observe(&cx, amount, labels);
     }
 
+    pub fn wallet_runway(&self, chain_id: &ChainId, account: &str, seconds: f64, denom: &str) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("account", account.to_string()),
+            KeyValue::new("denom", denom.to_string()),
+        ];
+
+        self.wallet_runway.observe(&cx, seconds, labels);
+    }
+
     pub fn received_event_batch(&self, tracking_id: impl ToString) {
         self.in_flight_events
             .insert(tracking_id.to_string(), Instant::now());
@@ -982,6 +1226,57 @@ impl TelemetryState {
         }
     }
 
+    /// The fee paid for a message of a transaction confirmed on the given chain.
+    /// Messages which do not relate to a channel are recorded with an empty channel.
+    pub fn tx_fee_paid(
+        &self,
+        chain_id: &ChainId,
+        wallet: &str,
+        channel_id: Option<&ChannelId>,
+        msg_type: &str,
+        denom: &str,
+        amount: u64,
+    ) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("wallet", wallet.to_string()),
+            KeyValue::new(
+                "channel",
+                channel_id.map(ToString::to_string).unwrap_or_default(),
+            ),
+            KeyValue::new("msg_type", msg_type.to_string()),
+            KeyValue::new("denom", denom.to_string()),
+        ];
+
+        self.tx_fees_paid.add(&cx, amount, labels);
+    }
+
+    /// The ICS29 fee rewarded to the wallet by a transaction confirmed on the given chain.
+    pub fn tx_fee_rewarded(
+        &self,
+        chain_id: &ChainId,
+        wallet: &str,
+        channel_id: Option<&ChannelId>,
+        denom: &str,
+        amount: u64,
+    ) {
+        let cx = Context::current();
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("wallet", wallet.to_string()),
+            KeyValue::new(
+                "channel",
+                channel_id.map(ToString::to_string).unwrap_or_default(),
+            ),
+            KeyValue::new("denom", denom.to_string()),
+        ];
+
+        self.tx_fees_rewarded.add(&cx, amount, labels);
+    }
+
     /// Record the rewarded fee from ICS29 if the address is in the registered addresses
     /// list.
     pub fn fees_amount(&self, chain_id: &ChainId, receiver: &Signer, fee_amounts: Coin<String>) {
@@ -1101,6 +1396,11 @@ impl AggregatorSelector for CustomAggregatorSelector {
     fn aggregator_for(&self, descriptor: &Descriptor) -> Option<Arc<dyn Aggregator + Send + Sync>> {
         match descriptor.name() {
             "wallet_balance" => Some(Arc::new(last_value())),
+            "wallet_runway_seconds" => Some(Arc::new(last_value())),
+            "queries_cache_size" => Some(Arc::new(last_value())),
+            "client_status" => Some(Arc::new(last_value())),
+            "client_update_age_seconds" => Some(Arc::new(last_value())),
+            "client_expiry_seconds" => Some(Arc::new(last_value())),
             "backlog_oldest_sequence" => Some(Arc::new(last_value())),
             "backlog_oldest_timestamp" => Some(Arc::new(last_value())),
             "backlog_size" => Some(Arc::new(last_value())),
@@ -1109,6 +1409,7 @@ impl AggregatorSelector for CustomAggregatorSelector {
             // TODO: Once quantile sketches are supported, replace histograms with that.
             "tx_latency_submitted" => Some(Arc::new(histogram(&self.get_submitted_range()))),
             "tx_latency_confirmed" => Some(Arc::new(histogram(&self.get_confirmed_range()))),
+            "endpoint_latency" => Some(Arc::new(histogram(&ENDPOINT_LATENCY_BUCKETS))),
             "ics29_period_fees" => Some(Arc::new(last_value())),
             _ => Some(Arc::new(sum())),
         }
@@ -0,0 +1,71 @@
+//! Export of the spans recorded by the relayer as OpenTelemetry traces, over OTLP/gRPC.
+
+use opentelemetry::sdk::trace::{self, Tracer};
+use opentelemetry::sdk::Resource;
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use tracing::Subscriber;
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+pub use opentelemetry::trace::TraceError;
+
+/// A layer which exports the spans it records to the OTLP/gRPC collector
+/// at the given endpoint, under the given service name.
+///
+/// The spans are exported in batches by a task spawned on the current Tokio runtime,
+/// hence this function must be called from within a Tokio runtime.
+pub fn layer<S>(
+    endpoint: &str,
+    service_name: &str,
+) -> Result<OpenTelemetryLayer<S, Tracer>, TraceError>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(trace::config().with_resource(Resource::new([KeyValue::new(
+            "service.name",
+            service_name.to_string(),
+        )])))
+        .install_batch(opentelemetry::runtime::Tokio)?;
+
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Make the given span part of the trace with the given identifier.
+///
+/// The span becomes the child of a remote span, which stands for the origin
+/// of the trace, so that all the spans given the same identifier end up in
+/// the same trace, even when they are recorded on different threads.
+pub fn set_trace_id(span: &tracing::Span, trace_id: u128) {
+    span.set_parent(Context::new().with_remote_span_context(origin(trace_id)));
+}
+
+/// Link the given span to the origin of the trace with the given identifier,
+/// so that the span can be found from that trace, while being part of another one.
+pub fn add_link(span: &tracing::Span, trace_id: u128) {
+    span.add_link(origin(trace_id));
+}
+
+/// Export the spans which are not exported yet, and stop exporting spans.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// The context of the remote span standing for the origin of the trace with the given identifier.
+fn origin(trace_id: u128) -> SpanContext {
+    SpanContext::new(
+        TraceId::from_bytes(trace_id.to_be_bytes()),
+        SpanId::from_bytes(((trace_id as u64).max(1)).to_be_bytes()),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    )
+}
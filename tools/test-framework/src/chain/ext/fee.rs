@@ -164,6 +164,7 @@ impl<'a, Chain: Send> ChainFeeMethodsExt<Chain> for MonoTagged<Chain, &'a ChainD
         address: &MonoTagged<Chain, &WalletAddress>,
     ) -> Result<Option<MonoTagged<Counterparty, WalletAddress>>, Error> {
         self.value().runtime.block_on(query_counterparty_payee(
+            &self.tx_config().value().chain_id,
             &self.tx_config().value().grpc_address,
             channel_id,
             address,
@@ -176,6 +177,7 @@ impl<'a, Chain: Send> ChainFeeMethodsExt<Chain> for MonoTagged<Chain, &'a ChainD
         port_id: &TaggedPortIdRef<'_, Chain, Counterparty>,
     ) -> Result<Vec<IdentifiedPacketFees>, Error> {
         self.value().runtime.block_on(query_incentivized_packets(
+            &self.tx_config().value().chain_id,
             &self.tx_config().value().grpc_address,
             channel_id,
             port_id,
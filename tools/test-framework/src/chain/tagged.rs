@@ -3,11 +3,12 @@
 */
 
 use ibc_proto::google::protobuf::Any;
+use ibc_relayer::chain::cosmos::compatibility::comet_compat;
 use ibc_relayer::chain::cosmos::tx::simple_send_tx;
 use ibc_relayer::chain::cosmos::types::config::TxConfig;
 use ibc_relayer::event::IbcEventWithHeight;
 use serde_json as json;
-use tendermint_rpc::client::{Client, CompatMode, HttpClient};
+use tendermint_rpc::client::{Client, HttpClient};
 
 use crate::chain::cli::query::query_recipient_transactions;
 use crate::chain::driver::ChainDriver;
@@ -97,9 +98,8 @@ impl<'a, Chain: Send> TaggedChainDriverExt<Chain> for MonoTagged<Chain, &'a Chai
         let mut client = HttpClient::new(rpc_address).map_err(handle_generic_error)?;
 
         let status = rt.block_on(client.status()).map_err(handle_generic_error)?;
-        let compat_mode =
-            CompatMode::from_version(status.node_info.version).map_err(handle_generic_error)?;
-        client.set_compat_mode(compat_mode);
+        let comet_compat = comet_compat(&status.node_info.version).map_err(handle_generic_error)?;
+        client.set_compat_mode(comet_compat.compat_mode);
 
         Ok(MonoTagged::new(client))
     }
@@ -62,6 +62,8 @@ impl RelayerDriver {
             SupervisorOptions {
                 health_check: false,
                 force_full_scan: false,
+                record_events: None,
+                config_path: None,
             },
         )
         .map_err(Error::supervisor)
@@ -14,6 +14,7 @@ use ibc_relayer_types::applications::ics29_fee::msgs::register_payee::{
 };
 use ibc_relayer_types::applications::ics29_fee::packet_fee::IdentifiedPacketFees;
 use ibc_relayer_types::core::ics04_channel::packet::Sequence;
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 use tendermint_rpc::HttpClient;
 
 use crate::error::{handle_generic_error, Error};
@@ -186,11 +187,13 @@ pub async fn register_payee<Chain, Counterparty>(
 }
 
 pub async fn query_counterparty_payee<Chain, Counterparty>(
+    chain_id: &ChainId,
     grpc_address: &Uri,
     channel_id: &TaggedChannelIdRef<'_, Chain, Counterparty>,
     address: &MonoTagged<Chain, &WalletAddress>,
 ) -> Result<Option<MonoTagged<Counterparty, WalletAddress>>, Error> {
     let counterparty_payee = raw_query_counterparty_payee(
+        chain_id,
         grpc_address,
         channel_id.value(),
         &address.value().0.parse().map_err(handle_generic_error)?,
@@ -202,11 +205,12 @@ pub async fn query_counterparty_payee<Chain, Counterparty>(
 }
 
 pub async fn query_incentivized_packets<Chain, Counterparty>(
+    chain_id: &ChainId,
     grpc_address: &Uri,
     channel_id: &TaggedChannelIdRef<'_, Chain, Counterparty>,
     port_id: &TaggedPortIdRef<'_, Chain, Counterparty>,
 ) -> Result<Vec<IdentifiedPacketFees>, Error> {
-    raw_query_incentivized_packets(grpc_address, channel_id.value(), port_id.value())
+    raw_query_incentivized_packets(chain_id, grpc_address, channel_id.value(), port_id.value())
         .await
         .map_err(handle_generic_error)
 }
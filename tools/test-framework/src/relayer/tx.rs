@@ -45,6 +45,7 @@ pub fn new_tx_config_for_test(
     address_type: AddressType,
 ) -> Result<TxConfig, Error> {
     let rpc_address = Url::from_str(&raw_rpc_address).map_err(handle_generic_error)?;
+    let broadcast_addresses = Vec::new();
     let grpc_address = Uri::from_str(&raw_grpc_address).map_err(handle_generic_error)?;
     let gas_config = gas_config_for_test();
     let rpc_timeout = Duration::from_secs(30);
@@ -56,11 +57,13 @@ pub fn new_tx_config_for_test(
         chain_id,
         gas_config,
         rpc_address,
+        broadcast_addresses,
         grpc_address,
         rpc_timeout,
         address_type,
         max_msg_num,
         max_tx_size,
         extension_options,
+        block_scanner: None,
     })
 }
@@ -139,6 +139,7 @@ impl FullNode {
             r#type: ChainType::CosmosSdk,
             rpc_addr: Url::from_str(&self.chain_driver.rpc_address())?,
             grpc_addr: Url::from_str(&self.chain_driver.grpc_address())?,
+            broadcast_addrs: Vec::new(),
             event_source: config::EventSourceMode::Push {
                 url: WebSocketClientUrl::from_str(&self.chain_driver.websocket_address())?,
                 batch_delay: config::default::batch_delay(),
@@ -156,9 +157,11 @@ impl FullNode {
             gas_adjustment: None,
             gas_multiplier: Some(GasMultiplier::unsafe_new(1.2)),
             fee_granter: None,
+            authz_granter: None,
             max_msg_num: Default::default(),
             max_tx_size: Default::default(),
             max_grpc_decoding_size: config::default::max_grpc_decoding_size(),
+            block_scan_window: config::default::block_scan_window(),
             max_block_time: Duration::from_secs(30),
             clock_drift: Duration::from_secs(5),
             trusting_period: Some(Duration::from_secs(14 * 24 * 3600)),
@@ -166,11 +169,15 @@ impl FullNode {
             trust_threshold: Default::default(),
             gas_price: config::GasPrice::new(0.003, "stake".to_string()),
             packet_filter: Default::default(),
+            min_balance: None,
+            critical_balance: None,
             address_type: chain_type.address_type(),
             memo_prefix: Default::default(),
             proof_specs: Default::default(),
             extension_options: Default::default(),
             sequential_batch_tx: false,
+            pause_on_critical_balance: false,
+            cache: Default::default(),
         })
     }
 